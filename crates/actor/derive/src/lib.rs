@@ -32,10 +32,27 @@ impl Parse for Invocation {
 pub fn export_actor(input: TokenStream) -> TokenStream {
     let Invocation { target, handlers } = parse_macro_input!(input);
     quote! {
+        /// Handles `op` using the first handler supporting it
+        fn __wasmcloud_actor_handle(
+            op: &str,
+            request: &mut ::wasmcloud_actor::Payload<'_>,
+            response: &mut dyn ::std::io::Write,
+        ) -> Result<(), String> {
+            let handler = #target::default();
+            #(
+                match ::wasmcloud_actor::Handler::<dyn #handlers>::handle_stream(&handler, op, request, response) {
+                    Some(Ok(())) => return Ok(()),
+                    Some(Err(e)) => return Err(format!("failed to call `{}`: {}", op, e)),
+                    None => {},
+                }
+            )*;
+            Err(format!("no handler found for operation `{}`", op))
+        }
+
         // version of the host-actor API
         #[no_mangle]
         pub extern "C" fn __wasmbus_rpc_version() -> u32 {
-            1
+            2
         }
 
         #[no_mangle]
@@ -66,31 +83,45 @@ pub fn export_actor(input: TokenStream) -> TokenStream {
                 },
             };
 
-            let handler = #target::default();
-            #(
-                match ::wasmcloud_actor::Handler::<dyn #handlers>::handle(&handler, &op, pld) {
-                    Some(Ok(res)) => {
-                        unsafe {
-                            __guest_response(res.as_ptr(), res.len() as _);
-                        }
-                        return 1
+            let mut pld = ::std::io::Cursor::new(pld);
+            let mut res = vec![];
+            match __wasmcloud_actor_handle(&op, &mut ::wasmcloud_actor::Payload::new(&mut pld), &mut res) {
+                Ok(()) => {
+                    unsafe {
+                        __guest_response(res.as_ptr(), res.len() as _);
                     }
-                    Some(Err(e)) => {
-                        let e = format!("failed to call `{}`: {}", op, e);
-                        unsafe {
-                            __guest_error(e.as_ptr(), e.len() as _);
-                        }
-                        return 0
+                    1
+                }
+                Err(e) => {
+                    unsafe {
+                        __guest_error(e.as_ptr(), e.len() as _);
                     }
-                    None => {},
+                    0
                 }
-            )*;
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn __guest_call_v2(op_len: i32) -> i32 {
+            use ::wasmcloud_actor::wasmcloud::bus::guest;
+
+            let op_len = op_len.try_into().expect("operation too long");
+            let op = match guest::operation(op_len) {
+                Ok(op) => op,
+                Err(e) => {
+                    guest::error(&e);
+                    return 0
+                },
+            };
 
-            let e = format!("no handler found for operation `{}`", op);
-            unsafe {
-                __guest_error(e.as_ptr(), e.len() as _);
+            let mut request = guest::Request;
+            match __wasmcloud_actor_handle(&op, &mut ::wasmcloud_actor::Payload::new(&mut request), &mut guest::Response) {
+                Ok(()) => 1,
+                Err(e) => {
+                    guest::error(&e);
+                    0
+                }
             }
-            0
         }
     }
     .into()
@@ -26,4 +26,33 @@ impl<T: Handler> super::Handler<dyn Handler> for T {
             _ => None,
         }
     }
+
+    fn handle_stream(
+        &self,
+        operation: &str,
+        request: &mut super::Payload<'_>,
+        response: &mut dyn std::io::Write,
+    ) -> Option<Result<(), String>> {
+        match operation {
+            "default:http-server/HttpServer.HandleRequest" | "HttpServer.HandleRequest" => {
+                let res = match rmp_serde::from_read(request) {
+                    Ok(req) => self.handle_request(req),
+                    Err(e) => return Some(Err(format!("failed to deserialize request: {e}"))),
+                };
+                let res = match res {
+                    Ok(res) => rmp_serde::to_vec(&res),
+                    Err(e) => return Some(Err(e)),
+                };
+                match res {
+                    Ok(res) => Some(
+                        response
+                            .write_all(&res)
+                            .map_err(|e| format!("failed to write response: {e}")),
+                    ),
+                    Err(e) => Some(Err(format!("failed to serialize response: {e}"))),
+                }
+            }
+            _ => None,
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::io::{Cursor, Read, Write};
+
 pub mod wasi;
 pub mod wasmcloud;
 
@@ -15,4 +17,66 @@ pub trait Handler<T: ?Sized> {
     type Error: ToString;
 
     fn handle(&self, operation: &str, payload: Vec<u8>) -> Option<Result<Vec<u8>, Self::Error>>;
+
+    /// Handles `operation` reading the payload from `request` and writing the result to `response`.
+    /// Returns [None] without reading from `request` if `operation` is not handled.
+    ///
+    /// The default implementation buffers the payload and the result and calls [`Handler::handle`],
+    /// the buffered payload remains available to subsequent handlers if `operation` is not handled.
+    fn handle_stream(
+        &self,
+        operation: &str,
+        request: &mut Payload<'_>,
+        response: &mut dyn Write,
+    ) -> Option<Result<(), String>> {
+        let payload = match request.buffer() {
+            Ok(payload) => payload,
+            Err(e) => return Some(Err(format!("failed to read request: {e}"))),
+        };
+        match self.handle(operation, payload)? {
+            Ok(res) => Some(
+                response
+                    .write_all(&res)
+                    .map_err(|e| format!("failed to write response: {e}")),
+            ),
+            Err(e) => Some(Err(e.to_string())),
+        }
+    }
+}
+
+/// Payload of a guest call, which is streamed until buffered by a [Handler]
+pub struct Payload<'a> {
+    stream: &'a mut dyn Read,
+    buffer: Option<Cursor<Vec<u8>>>,
+}
+
+impl<'a> Payload<'a> {
+    pub fn new(stream: &'a mut dyn Read) -> Self {
+        Self {
+            stream,
+            buffer: None,
+        }
+    }
+
+    /// Reads the whole payload into memory and returns it.
+    /// The buffered payload is kept, so that it is available to other handlers.
+    pub fn buffer(&mut self) -> std::io::Result<Vec<u8>> {
+        if let Some(buffer) = &self.buffer {
+            return Ok(buffer.get_ref().clone());
+        }
+        let mut buf = vec![];
+        self.stream.read_to_end(&mut buf)?;
+        self.buffer = Some(Cursor::new(buf.clone()));
+        Ok(buf)
+    }
+}
+
+impl Read for Payload<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if let Some(buffer) = &mut self.buffer {
+            buffer.read(buf)
+        } else {
+            self.stream.read(buf)
+        }
+    }
 }
@@ -0,0 +1,51 @@
+use super::host::{host_error, ERROR};
+
+#[link(wasm_import_module = "wasmbus")]
+extern "C" {
+    fn __guest_operation(ptr: *mut u8);
+    fn __guest_request_read(ptr: *mut u8, len: usize) -> usize;
+    fn __guest_response_write(ptr: *const u8, len: usize) -> usize;
+    fn __guest_error(ptr: *const u8, len: usize);
+}
+
+/// Returns the operation of the current wasmbus v2 guest call, `len` is the length passed to `__guest_call_v2`
+pub fn operation(len: usize) -> Result<String, String> {
+    let mut buf = vec![0; len];
+    unsafe { __guest_operation(buf.as_mut_ptr()) };
+    String::from_utf8(buf).map_err(|e| format!("operation is not valid UTF-8: {e}"))
+}
+
+/// Set the error of the current guest call
+pub fn error(err: &str) {
+    unsafe { __guest_error(err.as_ptr(), err.len()) }
+}
+
+/// Request stream of the current wasmbus v2 guest call
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Request;
+
+impl std::io::Read for Request {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match unsafe { __guest_request_read(buf.as_mut_ptr(), buf.len()) } {
+            ERROR => Err(std::io::Error::new(std::io::ErrorKind::Other, host_error())),
+            n => Ok(n),
+        }
+    }
+}
+
+/// Response stream of the current wasmbus v2 guest call
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Response;
+
+impl std::io::Write for Response {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match unsafe { __guest_response_write(buf.as_ptr(), buf.len()) } {
+            ERROR => Err(std::io::Error::new(std::io::ErrorKind::Other, host_error())),
+            _ => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
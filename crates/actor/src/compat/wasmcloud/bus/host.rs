@@ -20,9 +20,6 @@ pub fn call(
 
         pub fn __host_response(ptr: *mut u8);
         pub fn __host_response_len() -> usize;
-
-        pub fn __host_error(ptr: *mut u8);
-        pub fn __host_error_len() -> usize;
     }
 
     match unsafe {
@@ -45,14 +42,121 @@ pub fn call(
         }
         _ => {
             // call failed
-            let mut buf = vec![0; unsafe { __host_error_len() }];
-            unsafe { __host_error(buf.as_mut_ptr()) };
-            match String::from_utf8(buf) {
-                Ok(e) => Err(e),
-                Err(e) => Err(format!(
-                    "the host provided an error, which is not valid UTF-8: {e}"
-                )),
-            }
+            Err(host_error())
+        }
+    }
+}
+
+#[link(wasm_import_module = "wasmbus")]
+extern "C" {
+    fn __host_call_start(
+        binding_ptr: *const u8,
+        binding_len: usize,
+        namespace_ptr: *const u8,
+        namespace_len: usize,
+        operation_ptr: *const u8,
+        operation_len: usize,
+    ) -> usize;
+    fn __host_call_write(call: usize, ptr: *const u8, len: usize) -> usize;
+    fn __host_call_close(call: usize) -> usize;
+    fn __host_call_read(call: usize, ptr: *mut u8, len: usize) -> usize;
+    fn __host_call_finish(call: usize) -> usize;
+}
+
+/// Value returned by wasmbus functions on failure
+pub(crate) const ERROR: usize = usize::MAX;
+
+/// Returns the error set by the host on last failed call
+pub(crate) fn host_error() -> String {
+    #[link(wasm_import_module = "wasmbus")]
+    extern "C" {
+        fn __host_error(ptr: *mut u8);
+        fn __host_error_len() -> usize;
+    }
+
+    let mut buf = vec![0; unsafe { __host_error_len() }];
+    unsafe { __host_error(buf.as_mut_ptr()) };
+    match String::from_utf8(buf) {
+        Ok(e) => e,
+        Err(e) => format!("the host provided an error, which is not valid UTF-8: {e}"),
+    }
+}
+
+/// Streaming invocation of an operation on the host using wasmbus v2.
+///
+/// The request is written using [`std::io::Write`] and the response is read using [`std::io::Read`].
+/// The request stream is closed on first read of the response.
+pub struct Call {
+    call: usize,
+    finished: bool,
+}
+
+impl Call {
+    /// Start invocation of an operation on the host
+    pub fn start(binding: &str, namespace: &str, operation: &str) -> Result<Self, String> {
+        match unsafe {
+            __host_call_start(
+                binding.as_ptr(),
+                binding.len(),
+                namespace.as_ptr(),
+                namespace.len(),
+                operation.as_ptr(),
+                operation.len(),
+            )
+        } {
+            ERROR => Err(host_error()),
+            call => Ok(Self {
+                call,
+                finished: false,
+            }),
+        }
+    }
+
+    /// Close the request stream
+    pub fn close(&mut self) -> Result<(), String> {
+        match unsafe { __host_call_close(self.call) } {
+            ERROR => Err(host_error()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Wait for the invocation to finish and return its result
+    pub fn finish(mut self) -> Result<(), String> {
+        self.finished = true;
+        match unsafe { __host_call_finish(self.call) } {
+            ERROR => Err(host_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        if !self.finished {
+            // NOTE: The result is ignored, since there is no way to report it
+            unsafe { __host_call_finish(self.call) };
+        }
+    }
+}
+
+impl std::io::Write for Call {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match unsafe { __host_call_write(self.call, buf.as_ptr(), buf.len()) } {
+            ERROR => Err(std::io::Error::new(std::io::ErrorKind::Other, host_error())),
+            _ => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl std::io::Read for Call {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match unsafe { __host_call_read(self.call, buf.as_mut_ptr(), buf.len()) } {
+            ERROR => Err(std::io::Error::new(std::io::ErrorKind::Other, host_error())),
+            n => Ok(n),
         }
     }
 }
@@ -1,3 +1,6 @@
+#[cfg(feature = "module")]
+pub mod guest;
+
 #[cfg(feature = "module")]
 pub mod host;
 
@@ -50,22 +50,22 @@ mod test {
             );
             let _: Vec<u8> = wasi::random::random::get_random_bytes(4);
             let _: u64 = wasi::random::random::get_random_u64();
-            // TODO: Add support for HTTP
-            //outgoing_http::handle(
-            //    types::new_outgoing_request(
-            //        types::MethodParam::Get,
-            //        "path",
-            //        "query",
-            //        Some(types::SchemeParam::Https),
-            //        "authority",
-            //        types::new_fields(&[("myheader", "myvalue")]),
-            //    ),
-            //    Some(types::RequestOptions {
-            //        connect_timeout_ms: Some(42),
-            //        first_byte_timeout_ms: Some(42),
-            //        between_bytes_timeout_ms: Some(42),
-            //    }),
-            //);
+            #[cfg(not(any(feature = "module", feature = "compat")))]
+            let _: wasi::http::types::FutureIncomingResponse = wasi::http::outgoing_handler::handle(
+                wasi::http::types::new_outgoing_request(
+                    &wasi::http::types::Method::Get,
+                    Some("/path?query"),
+                    Some(&wasi::http::types::Scheme::Https),
+                    Some("authority"),
+                    wasi::http::types::new_fields(&[("myheader", "myvalue".as_bytes())]),
+                )
+                .unwrap(),
+                Some(wasi::http::types::RequestOptions {
+                    connect_timeout_ms: Some(42),
+                    first_byte_timeout_ms: Some(42),
+                    between_bytes_timeout_ms: Some(42),
+                }),
+            );
             // TODO
             #[cfg(not(feature = "module"))]
             let (_, _, _): (
@@ -0,0 +1,251 @@
+use crate::oci;
+
+use std::env::temp_dir;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, ensure, Context as _};
+use sha2::{Digest, Sha256};
+use tempfile::NamedTempFile;
+use tokio::fs;
+use tokio::task::spawn_blocking;
+use tracing::{debug, instrument};
+use url::Url;
+
+/// Actor fetcher, which caches actors fetched from remote locations on disk
+#[derive(Clone, Debug)]
+pub struct Fetcher {
+    /// Directory fetched actors are cached in
+    pub cache_dir: PathBuf,
+    /// Whether only previously cached actors are used, without accessing the network
+    pub offline: bool,
+    /// Configuration used for OCI references
+    pub oci_opts: oci::Config,
+    /// Timeout of fetching an actor from an `https` URL, including reading the response body
+    pub timeout: Duration,
+}
+
+impl Default for Fetcher {
+    fn default() -> Self {
+        Self {
+            cache_dir: temp_dir().join("wasmcloud_actorcache"),
+            offline: false,
+            oci_opts: oci::Config::default(),
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Returns hex-encoded sha256 digest of `buf`
+fn sha256(buf: impl AsRef<[u8]>) -> String {
+    hex::encode(Sha256::digest(buf))
+}
+
+fn verify(buf: impl AsRef<[u8]>, expected: &str) -> anyhow::Result<()> {
+    let digest = sha256(buf);
+    ensure!(
+        digest.eq_ignore_ascii_case(expected),
+        "sha256 digest `{digest}` does not match expected `{expected}`"
+    );
+    Ok(())
+}
+
+impl Fetcher {
+    /// Path of the cache entry for `url`
+    #[must_use]
+    pub fn cache_path(&self, url: &Url) -> PathBuf {
+        self.cache_dir
+            .join(format!("{}.wasm", sha256(url.as_str())))
+    }
+
+    async fn fetch_remote(&self, url: &Url) -> anyhow::Result<Vec<u8>> {
+        match url.scheme() {
+            "https" => {
+                let res = reqwest::Client::builder()
+                    .timeout(self.timeout)
+                    .build()
+                    .context("failed to build HTTP client")?
+                    .get(url.clone())
+                    .send()
+                    .await
+                    .context("failed to send request")?
+                    .error_for_status()
+                    .context("request failed")?;
+                let buf = res.bytes().await.context("failed to read response body")?;
+                Ok(buf.to_vec())
+            }
+            "oci" => {
+                let oci_ref = url
+                    .as_str()
+                    .strip_prefix("oci://")
+                    .context("invalid OCI reference")?;
+                oci::fetch_actor(oci_ref, None, &self.oci_opts)
+                    .await
+                    .with_context(|| {
+                        format!("failed to fetch actor under OCI reference `{oci_ref}`")
+                    })
+            }
+            "bindle" => {
+                let bindle_id = url
+                    .as_str()
+                    .strip_prefix("bindle://")
+                    .context("invalid Bindle reference")?;
+                crate::bindle::fetch_actor(None, bindle_id)
+                    .await
+                    .with_context(|| {
+                        format!("failed to fetch actor under Bindle reference `{bindle_id}`")
+                    })
+            }
+            scheme => bail!("`{scheme}` URLs are not supported"),
+        }
+    }
+
+    /// Fetches actor from `url`, which is either a `file`, `https`, `oci` or `bindle` URL.
+    /// If `sha256` is specified, the fetched actor must match the digest and a matching cached
+    /// actor is used without accessing the network.
+    /// Actors fetched from remote locations are cached, unless [`Self::offline`] is set, in which case
+    /// only the cache is used.
+    ///
+    /// # Errors
+    ///
+    /// Fails if fetching fails, the actor does not match `sha256` or if the actor is not cached in offline mode
+    #[instrument(skip(self))]
+    pub async fn fetch_actor(&self, url: &Url, sha256: Option<&str>) -> anyhow::Result<Vec<u8>> {
+        if url.scheme() == "file" {
+            let path = url
+                .to_file_path()
+                .map_err(|()| anyhow!("failed to convert `{url}` to a file path"))?;
+            let buf = fs::read(&path)
+                .await
+                .with_context(|| format!("failed to read `{}`", path.display()))?;
+            if let Some(sha256) = sha256 {
+                verify(&buf, sha256)?;
+            }
+            return Ok(buf);
+        }
+
+        let path = self.cache_path(url);
+        match fs::read(&path).await {
+            Ok(buf) if self.offline => {
+                debug!(path = %path.display(), "use cached actor in offline mode");
+                if let Some(sha256) = sha256 {
+                    verify(&buf, sha256).context("cached actor does not match digest")?;
+                }
+                return Ok(buf);
+            }
+            Ok(buf) if sha256.is_some_and(|sha256| verify(&buf, sha256).is_ok()) => {
+                debug!(path = %path.display(), "use cached actor matching digest");
+                return Ok(buf);
+            }
+            Ok(_) => {}
+            Err(e) if self.offline => {
+                return Err(anyhow!(e).context(format!(
+                    "`{url}` is not cached at `{}` and offline mode is enabled",
+                    path.display()
+                )))
+            }
+            Err(_) => {}
+        }
+
+        let buf = self.fetch_remote(url).await?;
+        if let Some(sha256) = sha256 {
+            verify(&buf, sha256)?;
+        }
+        fs::create_dir_all(&self.cache_dir).await.with_context(|| {
+            format!(
+                "failed to create cache directory `{}`",
+                self.cache_dir.display()
+            )
+        })?;
+        // NOTE: Write to a temporary file first, so that a partially-written actor is never used
+        let dir = self.cache_dir.clone();
+        let (buf, path) = spawn_blocking(move || {
+            let mut tmp = NamedTempFile::new_in(&dir).with_context(|| {
+                format!("failed to create temporary file in `{}`", dir.display())
+            })?;
+            tmp.write_all(&buf)
+                .with_context(|| format!("failed to write `{}`", tmp.path().display()))?;
+            tmp.persist(&path)
+                .with_context(|| format!("failed to persist `{}`", path.display()))?;
+            anyhow::Ok((buf, path))
+        })
+        .await
+        .context("failed to join cache write task")??;
+        debug!(path = %path.display(), "cached actor");
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    use tokio::net::TcpListener;
+    use tokio::{spawn, time};
+
+    #[tokio::test]
+    async fn cache() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+        let fetcher = Fetcher {
+            cache_dir: dir.path().into(),
+            offline: true,
+            ..Default::default()
+        };
+        let url = Url::parse("https://example.com/actor.wasm").context("failed to parse URL")?;
+        assert!(fetcher.fetch_actor(&url, None).await.is_err());
+
+        fs::write(fetcher.cache_path(&url), b"actor").await?;
+        assert_eq!(fetcher.fetch_actor(&url, None).await?, b"actor");
+        let digest = sha256(b"actor");
+        assert_eq!(fetcher.fetch_actor(&url, Some(&digest)).await?, b"actor");
+        assert!(fetcher
+            .fetch_actor(&url, Some(&sha256(b"other")))
+            .await
+            .is_err());
+
+        // a cached actor matching the digest is used without accessing the network
+        let fetcher = Fetcher {
+            offline: false,
+            ..fetcher
+        };
+        assert_eq!(fetcher.fetch_actor(&url, Some(&digest)).await?, b"actor");
+
+        let path = dir.path().join("local.wasm");
+        fs::write(&path, b"local").await?;
+        let url = Url::from_file_path(&path).map_err(|()| anyhow!("invalid path"))?;
+        assert_eq!(fetcher.fetch_actor(&url, None).await?, b"local");
+        assert!(fetcher.fetch_actor(&url, Some(&digest)).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn timeout() -> anyhow::Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let addr = listener.local_addr()?;
+        // connections are accepted, but never responded to
+        let server = spawn(async move {
+            let mut conns = vec![];
+            while let Ok((conn, _)) = listener.accept().await {
+                conns.push(conn);
+            }
+        });
+
+        let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+        let fetcher = Fetcher {
+            cache_dir: dir.path().into(),
+            timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let url =
+            Url::parse(&format!("https://{addr}/actor.wasm")).context("failed to parse URL")?;
+        time::timeout(Duration::from_secs(10), fetcher.fetch_actor(&url, None))
+            .await
+            .context("fetch did not time out")?
+            .expect_err("fetch must time out");
+        server.abort();
+        Ok(())
+    }
+}
@@ -16,10 +16,18 @@ pub mod bindle;
 /// OCI artifact fetching
 pub mod oci;
 
+/// Cached actor fetching
+pub mod fetch;
+
 /// Provider archive functionality
 mod par;
 
-pub use local::{Host as LocalHost, HostConfig as LocalHostConfig};
+/// Actor log handling
+mod logging;
+
+pub use local::{
+    Host as LocalHost, HostConfig as LocalHostConfig, Overrides as LocalHostOverrides,
+};
 pub use wasmbus::{Host as WasmbusHost, HostConfig as WasmbusHostConfig};
 
 pub use url;
@@ -75,21 +83,25 @@ impl<'a> TryFrom<&'a str> for ResourceRef<'a> {
     }
 }
 
-/// Fetch an actor from a reference.
+/// Fetch an actor from a reference using [`fetch::Fetcher`], which caches actors fetched from
+/// remote locations.
 #[instrument(skip(actor_ref))]
 pub async fn fetch_actor(
     actor_ref: impl AsRef<str>,
     oci_opts: &oci::Config,
 ) -> anyhow::Result<Vec<u8>> {
-    match ResourceRef::try_from(actor_ref.as_ref())? {
-        ResourceRef::File(actor_ref) => fs::read(actor_ref).await.context("failed to read actor"),
-        ResourceRef::Bindle(actor_ref) => crate::bindle::fetch_actor(None, &actor_ref)
-            .await
-            .with_context(|| format!("failed to fetch actor under Bindle reference `{actor_ref}`")),
-        ResourceRef::Oci(actor_ref) => crate::oci::fetch_actor(&actor_ref, None, oci_opts)
-            .await
-            .with_context(|| format!("failed to fetch actor under OCI reference `{actor_ref}`")),
+    let actor_ref = actor_ref.as_ref();
+    let url = match ResourceRef::try_from(actor_ref)? {
+        ResourceRef::File(..) | ResourceRef::Bindle(..) => Url::parse(actor_ref),
+        ResourceRef::Oci(actor_ref) => Url::parse(&format!("oci://{actor_ref}")),
+    }
+    .with_context(|| format!("invalid actor reference `{actor_ref}`"))?;
+    fetch::Fetcher {
+        oci_opts: oci_opts.clone(),
+        ..fetch::Fetcher::default()
     }
+    .fetch_actor(&url, None)
+    .await
 }
 
 /// Fetch a provider from a reference.
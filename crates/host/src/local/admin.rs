@@ -0,0 +1,306 @@
+use super::{get_actor, Snapshot, Socket};
+
+use crate::socket_pair;
+
+use core::convert::Infallible;
+use core::fmt::{self, Debug};
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context as _};
+use futures::stream::{AbortHandle, Abortable};
+use futures::{try_join, StreamExt};
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use http::{HeaderValue, Method, StatusCode};
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::Body;
+use serde::Serialize;
+use serde_json::json;
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::spawn;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio_stream::wrappers::TcpListenerStream;
+use tracing::{debug, error, info, instrument};
+
+/// Request to reload the host configuration, which is answered with the result of the reload
+pub(super) type ReloadRequest = oneshot::Sender<anyhow::Result<()>>;
+
+/// Compares `a` and `b` in time independent of the position of the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn json_response(status: StatusCode, value: &impl Serialize) -> hyper::Response<Body> {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => {
+            error!("failed to encode JSON response: {e}");
+            let mut response = hyper::Response::new(Body::empty());
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            return response;
+        }
+    };
+    let mut response = hyper::Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+fn error_response(status: StatusCode, e: &anyhow::Error) -> hyper::Response<Body> {
+    json_response(status, &json!({ "error": format!("{e:#}") }))
+}
+
+/// Admin API server
+#[derive(Clone)]
+struct Server {
+    token: Arc<RwLock<String>>,
+    snapshot: Arc<RwLock<Arc<Snapshot>>>,
+    reload: mpsc::Sender<ReloadRequest>,
+}
+
+impl Server {
+    /// Returns `true` if `request` carries the expected bearer token
+    async fn authorized(&self, request: &hyper::Request<Body>) -> bool {
+        let Some(token) = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            return false;
+        };
+        constant_time_eq(token.as_bytes(), self.token.read().await.as_bytes())
+    }
+
+    /// Lists actors along with their claims and number of pooled instances
+    async fn actors(&self) -> hyper::Response<Body> {
+        let snapshot = Arc::clone(&*self.snapshot.read().await);
+        let mut names: Vec<_> = snapshot.actors.keys().collect();
+        names.sort();
+        let mut actors = Vec::with_capacity(names.len());
+        for name in names {
+            let actor = &snapshot.actors[name];
+            actors.push(json!({
+                "name": name,
+                "url": actor.url.as_str(),
+                "claims": actor.actor.claims(),
+                "instances": {
+                    "pooled": actor.actor.pooled().await,
+                    "limit": actor.actor.get_limit().await,
+                },
+            }));
+        }
+        json_response(StatusCode::OK, &actors)
+    }
+
+    /// Lists TCP listeners and the protocol served on them
+    async fn listeners(&self) -> hyper::Response<Body> {
+        let snapshot = Arc::clone(&*self.snapshot.read().await);
+        let mut addrs: Vec<_> = snapshot.sockets.keys().collect();
+        addrs.sort();
+        let listeners: Vec<_> = addrs
+            .into_iter()
+            .map(|addr| match &snapshot.sockets[addr] {
+                Socket::Tcp(chain) => json!({ "addr": addr, "kind": "tcp", "chain": chain }),
+                Socket::Http(_) => json!({ "addr": addr, "kind": "http" }),
+            })
+            .collect();
+        json_response(StatusCode::OK, &listeners)
+    }
+
+    /// Requests a reload of the configuration file and waits for it to complete
+    async fn reload(&self) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.reload
+            .send(tx)
+            .await
+            .map_err(|_| anyhow!("host is not running"))?;
+        rx.await.context("reload request was dropped")?
+    }
+
+    /// Invokes `operation` on actor `name` with `payload`, returning the response
+    async fn invoke(&self, name: &str, operation: &str, payload: Vec<u8>) -> hyper::Response<Body> {
+        let snapshot = Arc::clone(&*self.snapshot.read().await);
+        let actor = match get_actor(&snapshot.actors, name) {
+            Ok(actor) => actor,
+            Err(e) => return error_response(StatusCode::NOT_FOUND, &e),
+        };
+        let res = async {
+            let (mut response, response_w) = socket_pair()?;
+            let mut buf = vec![];
+            try_join!(
+                actor.call(
+                    &snapshot.actors,
+                    operation,
+                    Cursor::new(payload),
+                    response_w
+                ),
+                async {
+                    response
+                        .read_to_end(&mut buf)
+                        .await
+                        .context("failed to read response")
+                }
+            )?;
+            anyhow::Ok(buf)
+        }
+        .await;
+        match res {
+            Ok(buf) => hyper::Response::new(Body::from(buf)),
+            Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+        }
+    }
+
+    #[instrument(skip_all, fields(method = %request.method(), path = request.uri().path()))]
+    async fn handle(&self, request: hyper::Request<Body>) -> anyhow::Result<hyper::Response<Body>> {
+        if !self.authorized(&request).await {
+            return Ok(error_response(
+                StatusCode::UNAUTHORIZED,
+                &anyhow!("invalid or missing bearer token"),
+            ));
+        }
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let response = match (method, path.as_str()) {
+            (Method::GET, "/actors") => self.actors().await,
+            (Method::GET, "/links") => {
+                let snapshot = Arc::clone(&*self.snapshot.read().await);
+                json_response(StatusCode::OK, &snapshot.links)
+            }
+            (Method::GET, "/listeners") => self.listeners().await,
+            (Method::POST, "/reload") => match self.reload().await {
+                Ok(()) => json_response(StatusCode::OK, &json!({})),
+                Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+            },
+            (Method::POST, path) => {
+                let Some((name, operation)) = path
+                    .strip_prefix("/actors/")
+                    .and_then(|path| path.split_once("/invoke/"))
+                else {
+                    return Ok(error_response(StatusCode::NOT_FOUND, &anyhow!("not found")));
+                };
+                let payload = hyper::body::to_bytes(request.into_body())
+                    .await
+                    .context("failed to read request body")?;
+                self.invoke(name, operation, payload.to_vec()).await
+            }
+            _ => error_response(StatusCode::NOT_FOUND, &anyhow!("not found")),
+        };
+        Ok(response)
+    }
+
+    async fn serve(self, stream: TcpStream) -> anyhow::Result<()> {
+        let service = service_fn(move |request| {
+            let server = self.clone();
+            async move {
+                Ok::<_, Infallible>(server.handle(request).await.unwrap_or_else(|e| {
+                    error!("failed to handle admin API request: {e:?}");
+                    error_response(StatusCode::INTERNAL_SERVER_ERROR, &e)
+                }))
+            }
+        });
+        Http::new()
+            .serve_connection(stream, service)
+            .await
+            .context("failed to serve HTTP connection")
+    }
+}
+
+/// Admin API listener, which is stopped when dropped
+pub(super) struct Listener {
+    addr: String,
+    token: Arc<RwLock<String>>,
+    abort: AbortHandle,
+}
+
+impl Debug for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Listener")
+            .field("addr", &self.addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        info!(addr = self.addr, "stop admin API listener");
+        self.abort.abort();
+    }
+}
+
+impl Listener {
+    /// Serves the admin API on `listener` bound to `addr`, requiring `token` to be supplied by clients.
+    /// Reload requests are sent to `reload`.
+    pub(super) fn serve(
+        addr: String,
+        token: String,
+        listener: TcpListener,
+        snapshot: Arc<RwLock<Arc<Snapshot>>>,
+        reload: mpsc::Sender<ReloadRequest>,
+    ) -> Self {
+        info!(addr, "start admin API listener");
+        let token = Arc::new(RwLock::new(token));
+        let server = Server {
+            token: Arc::clone(&token),
+            snapshot,
+            reload,
+        };
+        let (abort, abort_reg) = AbortHandle::new_pair();
+        spawn(
+            Abortable::new(TcpListenerStream::new(listener), abort_reg).for_each(move |stream| {
+                let server = server.clone();
+                async move {
+                    spawn(async move {
+                        if let Err(e) = async {
+                            let stream = stream.context("failed to initialize TCP stream")?;
+                            debug!("received admin API connection");
+                            server.serve(stream).await
+                        }
+                        .await
+                        {
+                            error!("failed to handle admin API connection: {e:?}");
+                        }
+                    });
+                }
+            }),
+        );
+        Self { addr, token, abort }
+    }
+
+    /// Address the listener is bound to
+    pub(super) fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Replaces the bearer token clients must supply
+    pub(super) async fn set_token(&self, token: String) {
+        *self.token.write().await = token;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn token_comparison() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn unencodable_json() {
+        // NOTE: JSON object keys must be strings
+        let response = json_response(StatusCode::OK, &HashMap::from([(vec![1u8], 1)]));
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,11 +1,417 @@
 use core::fmt;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env;
+use std::path::{Path, PathBuf};
 
+use anyhow::{bail, ensure, Context};
 use serde::{de, Deserialize, Deserializer, Serialize};
+use tokio::fs;
 use url::Url;
 
+/// Configuration file format
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// TOML
+    Toml,
+    /// YAML
+    Yaml,
+    /// JSON
+    Json,
+}
+
+impl Format {
+    /// Determines the [Format] of a configuration file from the extension of `path`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the extension is missing or not supported
+    pub fn from_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(Self::Toml),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            Some("json") => Ok(Self::Json),
+            _ => bail!(
+                "unsupported format of `{}`, expected a `.toml`, `.yaml`, `.yml` or `.json` file",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Replaces all `${NAME}` references in `s` by the value returned by `lookup` for `NAME`.
+/// `$$` is replaced by a literal `$`.
+fn interpolate(s: &str, lookup: impl Fn(&str) -> Option<String>) -> anyhow::Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some(tail) = rest.strip_prefix("$$") {
+            out.push('$');
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("${") {
+            let end = tail.find('}').context("unterminated variable reference")?;
+            let name = &tail[..end];
+            ensure!(!name.is_empty(), "empty variable reference");
+            let value = lookup(name)
+                .with_context(|| format!("environment variable `{name}` is not set"))?;
+            out.push_str(&value);
+            rest = &tail[end + 1..];
+        } else {
+            out.push('$');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Wraps a [Deserializer], visitor or an access and interpolates all string scalars deserialized
+/// through it using [interpolate]. Map keys and enum variant names are left unchanged.
+struct Interpolate<'a, T, F> {
+    inner: T,
+    lookup: &'a F,
+}
+
+impl<'a, T, F> Interpolate<'a, T, F>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    fn wrap<U>(&self, inner: U) -> Interpolate<'a, U, F> {
+        Interpolate {
+            inner,
+            lookup: self.lookup,
+        }
+    }
+
+    fn interpolate<E: de::Error>(&self, s: &str) -> Result<String, E> {
+        interpolate(s, self.lookup).map_err(|e| {
+            E::custom(format!(
+                "failed to interpolate environment variables: {e:#}"
+            ))
+        })
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                let visitor = self.wrap(visitor);
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D, F> Deserializer<'de> for Interpolate<'_, D, F>
+where
+    D: Deserializer<'de>,
+    F: Fn(&str) -> Option<String>,
+{
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V, F> de::Visitor<'de> for Interpolate<'_, V, F>
+where
+    V: de::Visitor<'de>,
+    F: Fn(&str) -> Option<String>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    forward_visit! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.contains('$') {
+            let v = self.interpolate(v)?;
+            self.inner.visit_string(v)
+        } else {
+            self.inner.visit_str(v)
+        }
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.contains('$') {
+            let v = self.interpolate(v)?;
+            self.inner.visit_string(v)
+        } else {
+            self.inner.visit_borrowed_str(v)
+        }
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.contains('$') {
+            let v = self.interpolate(&v)?;
+            self.inner.visit_string(v)
+        } else {
+            self.inner.visit_string(v)
+        }
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.wrap(deserializer);
+        self.inner.visit_some(deserializer)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.wrap(deserializer);
+        self.inner.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let seq = self.wrap(seq);
+        self.inner.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let map = self.wrap(map);
+        self.inner.visit_map(map)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        let data = self.wrap(data);
+        self.inner.visit_enum(data)
+    }
+}
+
+impl<'de, S, F> de::DeserializeSeed<'de> for Interpolate<'_, S, F>
+where
+    S: de::DeserializeSeed<'de>,
+    F: Fn(&str) -> Option<String>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserializer = self.wrap(deserializer);
+        self.inner.deserialize(deserializer)
+    }
+}
+
+impl<'de, A, F> de::SeqAccess<'de> for Interpolate<'_, A, F>
+where
+    A: de::SeqAccess<'de>,
+    F: Fn(&str) -> Option<String>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let seed = self.wrap(seed);
+        self.inner.next_element_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A, F> de::MapAccess<'de> for Interpolate<'_, A, F>
+where
+    A: de::MapAccess<'de>,
+    F: Fn(&str) -> Option<String>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        self.inner.next_key_seed(seed)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let seed = self.wrap(seed);
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, 'de, A, F> de::EnumAccess<'de> for Interpolate<'a, A, F>
+where
+    A: de::EnumAccess<'de>,
+    F: Fn(&str) -> Option<String>,
+{
+    type Error = A::Error;
+    type Variant = Interpolate<'a, A::Variant, F>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let lookup = self.lookup;
+        let (value, inner) = self.inner.variant_seed(seed)?;
+        Ok((value, Interpolate { inner, lookup }))
+    }
+}
+
+impl<'de, A, F> de::VariantAccess<'de> for Interpolate<'_, A, F>
+where
+    A: de::VariantAccess<'de>,
+    F: Fn(&str) -> Option<String>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let seed = self.wrap(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let visitor = self.wrap(visitor);
+        self.inner.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let visitor = self.wrap(visitor);
+        self.inner.struct_variant(fields, visitor)
+    }
+}
+
 /// Declarative local host configuration
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -16,6 +422,146 @@ pub struct Host {
     /// Link definitions
     #[serde(default)]
     pub links: Vec<Link>,
+    /// Remote actor cache
+    #[serde(default)]
+    pub cache: Cache,
+    /// Admin API, which is disabled if not set
+    #[serde(default)]
+    pub admin: Option<Admin>,
+}
+
+fn default_admin_addr() -> String {
+    "127.0.0.1:9009".into()
+}
+
+fn deserialize_admin_token<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let token = String::deserialize(deserializer)?;
+    if token.trim().is_empty() {
+        return Err(de::Error::invalid_value(
+            de::Unexpected::Str(&token),
+            &"a non-empty token",
+        ));
+    }
+    Ok(token)
+}
+
+/// Admin API config
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Admin {
+    /// Address to listen on, which only accepts local connections by default
+    #[serde(default = "default_admin_addr")]
+    pub addr: String,
+    /// Bearer token, which must be supplied in the `Authorization` header of all requests.
+    /// Empty tokens are rejected
+    #[serde(deserialize_with = "deserialize_admin_token")]
+    pub token: String,
+}
+
+impl fmt::Debug for Admin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Admin")
+            .field("addr", &self.addr)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Remote actor cache config
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cache {
+    /// Directory remote actors are cached in, `wasmcloud_actorcache` within the system temporary
+    /// directory is used if not set
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// Whether only previously cached remote actors are used, without accessing the network
+    #[serde(default)]
+    pub offline: bool,
+}
+
+thread_local! {
+    /// Directory relative actor paths are resolved against while deserializing [Host], the current
+    /// working directory is used if not set
+    static BASE_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+impl Host {
+    /// Parses [Host] configuration in specified [Format] from `s` and replaces `${NAME}` references
+    /// within string values by values of the corresponding environment variables.
+    /// Relative actor paths are resolved against the current working directory.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a referenced environment variable is not set or if the configuration is not valid
+    pub fn parse(s: &str, format: Format) -> anyhow::Result<Self> {
+        Self::parse_with(s, format, None, |name| env::var(name).ok())
+    }
+
+    fn parse_with(
+        s: &str,
+        format: Format,
+        base: Option<&Path>,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<Self> {
+        // NOTE: Interpolation happens as string values are deserialized, so that values cannot alter
+        // the structure of the configuration. The format deserializers are used directly, so that
+        // errors contain the location within `s`
+        let lookup = &lookup;
+        let prev = BASE_DIR.with(|dir| dir.replace(base.map(Path::to_path_buf)));
+        let res = match format {
+            Format::Toml => serde_path_to_error::deserialize(Interpolate {
+                inner: toml::Deserializer::new(s),
+                lookup,
+            })
+            .context("failed to parse TOML"),
+            Format::Yaml => serde_path_to_error::deserialize(Interpolate {
+                inner: serde_yaml::Deserializer::from_str(s),
+                lookup,
+            })
+            .context("failed to parse YAML"),
+            Format::Json => {
+                let mut de = serde_json::Deserializer::from_str(s);
+                serde_path_to_error::deserialize(Interpolate {
+                    inner: &mut de,
+                    lookup,
+                })
+                .context("failed to parse JSON")
+                .and_then(|host| {
+                    de.end().context("failed to parse JSON")?;
+                    Ok(host)
+                })
+            }
+        };
+        BASE_DIR.with(|dir| dir.replace(prev));
+        res
+    }
+
+    /// Reads [Host] configuration from file at `path`, the [Format] of which is determined by the file extension.
+    /// Relative actor paths are resolved against the directory containing the file.
+    /// See [Host::parse].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read or if [Host::parse] fails
+    pub async fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let format = Format::from_path(path)?;
+        let s = fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        let file = fs::canonicalize(path)
+            .await
+            .with_context(|| format!("failed to resolve `{}`", path.display()))?;
+        let dir = file.parent().with_context(|| {
+            format!("failed to lookup parent directory of `{}`", path.display())
+        })?;
+        Self::parse_with(&s, format, Some(dir), |name| env::var(name).ok())
+            .with_context(|| format!("invalid config `{}`", path.display()))
+    }
 }
 
 fn deserialize_actor_url<'de, D>(deserializer: D) -> Result<Url, D::Error>
@@ -38,10 +584,13 @@ where
             match s.parse() {
                 Ok(url) => Ok(url),
                 Err(url::ParseError::RelativeUrlWithoutBase) => {
-                    let wd = env::current_dir().map_err(|e| {
-                        de::Error::custom(format!("failed to lookup current directory: {e}"))
-                    })?;
-                    Url::from_file_path(wd.join(s)).map_err(|()| {
+                    let dir = match BASE_DIR.with(|dir| dir.borrow().clone()) {
+                        Some(dir) => dir,
+                        None => env::current_dir().map_err(|e| {
+                            de::Error::custom(format!("failed to lookup current directory: {e}"))
+                        })?,
+                    };
+                    Url::from_file_path(dir.join(s)).map_err(|()| {
                         de::Error::custom(
                             "failed to construct a `file` scheme URL for relative path".to_string(),
                         )
@@ -60,9 +609,34 @@ where
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Actor {
-    /// URL of the actor Wasm
+    /// URL of the actor Wasm, either a `file`, `https`, `oci` or `bindle` URL
     #[serde(deserialize_with = "deserialize_actor_url")]
     pub url: Url,
+    /// Hex-encoded sha256 digest the actor Wasm must match
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// `argv[0]` passed to the actor, `main.wasm` is used if not set
+    #[serde(default)]
+    pub argv0: Option<String>,
+    /// Environment variables available to the actor
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Host directories available to the actor
+    #[serde(default)]
+    pub preopens: Vec<Preopen>,
+}
+
+/// Preopened directory config
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preopen {
+    /// Path to the directory on the host
+    pub host: PathBuf,
+    /// Path the directory is mapped to within the actor
+    pub guest: String,
+    /// Whether the actor is only allowed to read the directory contents
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 /// TCP socket configuration
@@ -73,6 +647,57 @@ pub struct TcpSocket {
     pub addr: String, // TODO: Introduce a proper type
 }
 
+/// HTTP route configuration
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpRoute {
+    /// Path prefix matched against request paths segment-wise, `/api` matches `/api` and `/api/users`,
+    /// but not `/apis`. The route with the longest matching prefix is used
+    #[serde(default = "default_http_route_prefix")]
+    pub prefix: String,
+    /// Name of the actor handling requests via its `wasi:http/incoming-handler` export
+    pub target: String,
+}
+
+fn default_http_route_prefix() -> String {
+    "/".into()
+}
+
+/// TLS configuration
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tls {
+    /// Path to the PEM-encoded certificate chain
+    pub cert: PathBuf,
+    /// Path to the PEM-encoded private key
+    pub key: PathBuf,
+    /// TLS handshake timeout in milliseconds, defaults to 10 seconds
+    #[serde(default)]
+    pub handshake_timeout_ms: Option<u64>,
+}
+
+/// Log level
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Trace level
+    Trace,
+    /// Debug level
+    Debug,
+    /// Info level
+    Info,
+    /// Warn level
+    Warn,
+    /// Error level
+    Error,
+    /// Critical level
+    Critical,
+}
+
+fn default_keyvalue_store() -> String {
+    "default".into()
+}
+
 /// Link config
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(tag = "kind", deny_unknown_fields)]
@@ -95,6 +720,68 @@ pub enum Link {
         /// Actor chain
         chain: Vec<String>,
     },
+    /// HTTP link, which terminates HTTP/1.1 and HTTP/2 in the host and invokes the
+    /// `wasi:http/incoming-handler` export of the actor the request path is routed to
+    #[serde(rename = "http")]
+    Http {
+        /// Socket configuration
+        socket: TcpSocket,
+        /// Routes
+        routes: Vec<HttpRoute>,
+        /// TLS configuration, plaintext HTTP is served if not set
+        #[serde(default)]
+        tls: Option<Tls>,
+        /// Timeout in milliseconds for actors to respond with response headers, bodies are streamed
+        /// afterwards
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    /// Built-in key-value link, which binds `wasi:keyvalue` imports of the source actor to an in-memory store
+    #[serde(rename = "keyvalue")]
+    KeyValue {
+        /// Source actor name
+        source: String,
+        /// Store name, actors linked to the same store share its contents
+        #[serde(default = "default_keyvalue_store")]
+        store: String,
+        /// Path to a JSON snapshot file store contents are loaded from. Changes are appended to a log
+        /// next to it, which is compacted into the snapshot
+        #[serde(default)]
+        snapshot: Option<PathBuf>,
+    },
+    /// Built-in logging link, which emits `wasi:logging/logging` logs of the source actor as host `tracing` events
+    #[serde(rename = "logging")]
+    Logging {
+        /// Source actor name
+        source: String,
+        /// Minimum level of logs to emit, all logs are emitted if not set
+        #[serde(default)]
+        level: Option<LogLevel>,
+    },
+    /// Built-in messaging link, which connects the source actor to a pub/sub broker shared by all actors in the host
+    #[serde(rename = "messaging")]
+    Messaging {
+        /// Source actor name
+        source: String,
+        /// Subjects delivered to the `wasmcloud:messaging/handler` export of the source actor.
+        /// `*` matches a single token and a trailing `>` matches one or more tokens, for example `orders.*.created` or `orders.>`
+        #[serde(default)]
+        subscriptions: Vec<String>,
+    },
+    /// Outgoing HTTP link, which allows the source actor to send HTTP requests directly from the host
+    #[serde(rename = "outgoing-http")]
+    OutgoingHttp {
+        /// Source actor name
+        source: String,
+        /// Allowed destination hosts or authorities with an optional scheme, for example `example.com`,
+        /// `localhost:8080` or `https://example.com`. Destinations without a port only allow
+        /// the default port of the scheme
+        #[serde(default)]
+        allow: Vec<String>,
+        /// Maximum size of request and response bodies in bytes, 16 MiB are allowed if not set
+        #[serde(default)]
+        max_body_size: Option<usize>,
+    },
 }
 
 #[cfg(test)]
@@ -104,11 +791,27 @@ mod tests {
     use anyhow::Context;
 
     const CONFIG: &str = r#"
+[cache]
+dir = "/var/cache/wasmcloud"
+offline = true
+
+[admin]
+token = "secret"
+
 [actors.http-parser]
 url = "actors/http-parser.wasm"
 
 [actors.http-server]
 url = "https://example.com/mypath/http-server.wasm"
+argv0 = "http-server"
+sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+preopens = [
+    { host = "/srv/www", guest = "/www", read_only = true },
+    { host = "/tmp/http-server", guest = "/tmp" },
+]
+
+[actors.http-server.env]
+RUST_LOG = "debug"
 
 [[links]]
 kind = "interface"
@@ -124,6 +827,44 @@ chain = [
 ]
 [links.socket]
 addr = "[::]:5000"
+
+[[links]]
+kind = "outgoing-http"
+source = "http-server"
+allow = [
+    "example.com",
+    "localhost:8080",
+]
+max_body_size = 1048576
+
+[[links]]
+kind = "keyvalue"
+source = "http-server"
+snapshot = "kv.json"
+
+[[links]]
+kind = "logging"
+source = "http-server"
+level = "warn"
+
+[[links]]
+kind = "messaging"
+source = "http-parser"
+subscriptions = ["requests.>"]
+
+[[links]]
+kind = "http"
+routes = [
+    { prefix = "/api", target = "http-server" },
+    { target = "http-parser" },
+]
+timeout_ms = 5000
+[links.socket]
+addr = "[::]:8443"
+[links.tls]
+cert = "/etc/wasmcloud/cert.pem"
+key = "/etc/wasmcloud/key.pem"
+handshake_timeout_ms = 2000
 "#;
 
     #[test]
@@ -143,6 +884,10 @@ addr = "[::]:5000"
                                     .display()
                             ))
                             .expect("failed to parse URL"),
+                            argv0: None,
+                            env: HashMap::default(),
+                            preopens: vec![],
+                            sha256: None,
                         }
                     ),
                     (
@@ -150,6 +895,24 @@ addr = "[::]:5000"
                         Actor {
                             url: Url::parse("https://example.com/mypath/http-server.wasm")
                                 .expect("failed to parse URL"),
+                            argv0: Some("http-server".into()),
+                            env: HashMap::from([("RUST_LOG".into(), "debug".into())]),
+                            preopens: vec![
+                                Preopen {
+                                    host: "/srv/www".into(),
+                                    guest: "/www".into(),
+                                    read_only: true,
+                                },
+                                Preopen {
+                                    host: "/tmp/http-server".into(),
+                                    guest: "/tmp".into(),
+                                    read_only: false,
+                                },
+                            ],
+                            sha256: Some(
+                                "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                                    .into()
+                            ),
                         }
                     ),
                 ]),
@@ -165,9 +928,228 @@ addr = "[::]:5000"
                         },
                         chain: vec!["http-parser".into(), "http-server".into(),]
                     },
+                    Link::OutgoingHttp {
+                        source: "http-server".into(),
+                        allow: vec!["example.com".into(), "localhost:8080".into()],
+                        max_body_size: Some(1_048_576),
+                    },
+                    Link::KeyValue {
+                        source: "http-server".into(),
+                        store: "default".into(),
+                        snapshot: Some("kv.json".into()),
+                    },
+                    Link::Logging {
+                        source: "http-server".into(),
+                        level: Some(LogLevel::Warn),
+                    },
+                    Link::Messaging {
+                        source: "http-parser".into(),
+                        subscriptions: vec!["requests.>".into()],
+                    },
+                    Link::Http {
+                        socket: TcpSocket {
+                            addr: "[::]:8443".into(),
+                        },
+                        routes: vec![
+                            HttpRoute {
+                                prefix: "/api".into(),
+                                target: "http-server".into(),
+                            },
+                            HttpRoute {
+                                prefix: "/".into(),
+                                target: "http-parser".into(),
+                            },
+                        ],
+                        tls: Some(Tls {
+                            cert: "/etc/wasmcloud/cert.pem".into(),
+                            key: "/etc/wasmcloud/key.pem".into(),
+                            handshake_timeout_ms: Some(2000),
+                        }),
+                        timeout_ms: Some(5000),
+                    },
                 ],
+                cache: Cache {
+                    dir: Some("/var/cache/wasmcloud".into()),
+                    offline: true,
+                },
+                admin: Some(Admin {
+                    addr: "127.0.0.1:9009".into(),
+                    token: "secret".into(),
+                }),
             }
         );
         Ok(())
     }
+
+    #[test]
+    fn formats() -> anyhow::Result<()> {
+        const TOML: &str = r#"
+[actors.http-server]
+url = "https://example.com/mypath/http-server.wasm"
+argv0 = "http-server"
+
+[actors.http-server.env]
+RUST_LOG = "debug"
+
+[[links]]
+kind = "outgoing-http"
+source = "http-server"
+allow = ["example.com"]
+"#;
+        const YAML: &str = r#"
+actors:
+  http-server:
+    url: https://example.com/mypath/http-server.wasm
+    argv0: http-server
+    env:
+      RUST_LOG: debug
+links:
+  - kind: outgoing-http
+    source: http-server
+    allow:
+      - example.com
+"#;
+        const JSON: &str = r#"{
+  "actors": {
+    "http-server": {
+      "url": "https://example.com/mypath/http-server.wasm",
+      "argv0": "http-server",
+      "env": { "RUST_LOG": "debug" }
+    }
+  },
+  "links": [
+    { "kind": "outgoing-http", "source": "http-server", "allow": ["example.com"] }
+  ]
+}"#;
+        let toml = Host::parse(TOML, Format::Toml).context("failed to parse TOML")?;
+        assert_eq!(
+            Host::parse(YAML, Format::Yaml).context("failed to parse YAML")?,
+            toml
+        );
+        assert_eq!(
+            Host::parse(JSON, Format::Json).context("failed to parse JSON")?,
+            toml
+        );
+
+        assert_eq!(Format::from_path("wasmcloud.toml")?, Format::Toml);
+        assert_eq!(Format::from_path("wasmcloud.yml")?, Format::Yaml);
+        assert_eq!(Format::from_path("wasmcloud.yaml")?, Format::Yaml);
+        assert_eq!(Format::from_path("wasmcloud.json")?, Format::Json);
+        assert!(Format::from_path("wasmcloud").is_err());
+
+        for (s, format) in [
+            ("[actors.foo]\nurl = \n", Format::Toml),
+            ("links: []\nactors: [\n", Format::Yaml),
+            ("{\n\"actors\": }", Format::Json),
+        ] {
+            let err = Host::parse(s, format).expect_err("invalid config must fail to parse");
+            assert!(format!("{err:#}").contains("line 2"), "{err:#}");
+        }
+        for (s, format, path) in [
+            ("[actors.foo]\nurl = 1\n", Format::Toml, "actors.foo.url"),
+            ("links: []\nactors: 1\n", Format::Yaml, "actors"),
+            ("{\n\"actors\": 1}", Format::Json, "actors"),
+            ("[admin]\ntoken = \"\"\n", Format::Toml, "admin.token"),
+            ("admin:\n  token: ' '\n", Format::Yaml, "admin.token"),
+        ] {
+            let err = Host::parse(s, format).expect_err("invalid config must fail to parse");
+            let err = format!("{err:#}");
+            assert!(err.contains(path), "{err}");
+            assert!(err.contains("line 2"), "{err}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn interpolation() -> anyhow::Result<()> {
+        let lookup = |name: &str| match name {
+            "HOST" => Some("example.com".to_string()),
+            "GREETING" => Some("say \"hi\"\n' = 1\n[actors.evil]".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            interpolate("https://${HOST}/a # $$5 $x", lookup)?,
+            "https://example.com/a # $5 $x"
+        );
+        assert!(interpolate("${HOST", lookup).is_err());
+        assert!(interpolate("${}", lookup).is_err());
+
+        // values are interpolated after parsing and are not able to alter the structure
+        const TOML: &str = r#"
+[actors.foo]
+url = "https://${HOST}/foo.wasm"
+env = { GREETING = "${GREETING}", PRICE = "$$5" }
+"#;
+        const YAML: &str = r#"
+actors:
+  foo:
+    url: https://${HOST}/foo.wasm
+    env:
+      GREETING: ${GREETING}
+      PRICE: $$5
+"#;
+        const JSON: &str = r#"{
+  "actors": {
+    "foo": {
+      "url": "https://${HOST}/foo.wasm",
+      "env": { "GREETING": "${GREETING}", "PRICE": "$$5" }
+    }
+  }
+}"#;
+        for (s, format) in [
+            (TOML, Format::Toml),
+            (YAML, Format::Yaml),
+            (JSON, Format::Json),
+        ] {
+            let Host { actors, .. } = Host::parse_with(s, format, None, lookup)?;
+            assert_eq!(actors.len(), 1);
+            let Actor { url, env, .. } = &actors["foo"];
+            assert_eq!(url.as_str(), "https://example.com/foo.wasm");
+            assert_eq!(
+                env,
+                &HashMap::from([
+                    ("GREETING".into(), "say \"hi\"\n' = 1\n[actors.evil]".into()),
+                    ("PRICE".into(), "$5".into()),
+                ])
+            );
+        }
+
+        let err = Host::parse_with(
+            "[actors.foo]\nurl = \"${MISSING}\"",
+            Format::Toml,
+            None,
+            lookup,
+        )
+        .expect_err("missing variable must fail interpolation");
+        let err = format!("{err:#}");
+        assert!(err.contains("actors.foo.url"), "{err}");
+        assert!(err.contains("line 2"), "{err}");
+        assert!(
+            err.contains("environment variable `MISSING` is not set"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+        let path = dir.path().join("wasmcloud.toml");
+        fs::write(
+            &path,
+            "[actors.foo]\nurl = \"actors/foo.wasm\"\n\n[actors.bar]\nurl = \"/bar.wasm\"\n",
+        )
+        .await
+        .context("failed to write config")?;
+        let Host { actors, .. } = Host::load(&path).await?;
+        let dir = fs::canonicalize(dir.path())
+            .await
+            .context("failed to resolve temporary directory")?;
+        assert_eq!(
+            actors["foo"].url,
+            Url::from_file_path(dir.join("actors/foo.wasm")).expect("failed to construct URL")
+        );
+        assert_eq!(actors["bar"].url.as_str(), "file:///bar.wasm");
+        Ok(())
+    }
 }
@@ -0,0 +1,649 @@
+use super::{get_actor_link, HttpRouteConfig, Snapshot, Socket, TlsConfig};
+
+use core::cmp::Reverse;
+use core::convert::Infallible;
+use core::fmt::{self, Debug};
+use core::str::FromStr;
+use core::time::Duration;
+
+use std::io::{self, Cursor};
+use std::sync::Arc;
+
+use anyhow::{ensure, Context as _};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use http::StatusCode;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::Body;
+use rustls_pemfile::Item;
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use tokio::time;
+use tokio_rustls::rustls::{self, Certificate, PrivateKey};
+use tokio_rustls::TlsAcceptor;
+use tokio_util::io::{ReaderStream, StreamReader};
+use tracing::{error, instrument, trace, warn};
+use wasmcloud_runtime::capability::http::types::RequestOptions;
+use wasmcloud_runtime::capability::{IncomingHttp, OutgoingHttp};
+use wasmcloud_runtime::ActorInstance;
+
+/// Maximum amount of redirects followed by [`HttpClient`]
+const MAX_REDIRECTS: usize = 10;
+
+/// Maximum size of request and response bodies in bytes used by [`HttpClient`] if not configured
+const DEFAULT_MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// TLS handshake timeout used by [`HttpServer`] if not configured
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the default port of `scheme`, if it is supported
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" => Some(80),
+        "https" => Some(443),
+        _ => None,
+    }
+}
+
+/// Destination an [`HttpClient`] is allowed to send requests to
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Destination {
+    scheme: Option<String>,
+    host: String,
+    port: Option<u16>,
+}
+
+impl FromStr for Destination {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (scheme, authority) = match s.split_once("://") {
+            Some((scheme, authority)) => {
+                ensure!(
+                    default_port(scheme).is_some(),
+                    "unsupported scheme `{scheme}` in destination `{s}`"
+                );
+                (Some(scheme.to_string()), authority.trim_end_matches('/'))
+            }
+            None => (None, s),
+        };
+        let authority: http::uri::Authority = authority
+            .parse()
+            .with_context(|| format!("invalid destination `{s}`"))?;
+        Ok(Self {
+            scheme,
+            host: authority.host().to_ascii_lowercase(),
+            port: authority.port_u16(),
+        })
+    }
+}
+
+impl Destination {
+    /// Returns `true` if requests to `host` on `port` using `scheme` are allowed by this destination.
+    /// Destinations without an explicit port only match the default port of the scheme.
+    fn matches(&self, scheme: &str, host: &str, port: Option<u16>) -> bool {
+        let Some(default) = default_port(scheme) else {
+            return false
+        };
+        self.scheme.as_deref().map_or(true, |s| s == scheme)
+            && self.host.eq_ignore_ascii_case(host)
+            && self.port.unwrap_or(default) == port.unwrap_or(default)
+    }
+}
+
+fn is_allowed(allow: &[Destination], scheme: &str, host: &str, port: Option<u16>) -> bool {
+    allow.iter().any(|dst| dst.matches(scheme, host, port))
+}
+
+/// [`OutgoingHttp`] implementation, which sends requests directly from the host to a set of allowed destinations
+#[derive(Clone, Debug)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    allow: Arc<[Destination]>,
+    max_body_size: usize,
+}
+
+impl HttpClient {
+    /// Construct a new [`HttpClient`] allowed to send requests to specified destinations.
+    /// Destinations are either hosts, e.g. `example.com`, authorities, e.g. `example.com:8080`,
+    /// or either of these prefixed by a scheme, e.g. `https://example.com`. Destinations without
+    /// a port only allow the default port of the scheme and destinations without a scheme allow
+    /// both `http` and `https`. Redirects are only followed to allowed destinations.
+    /// Bodies are limited to [`DEFAULT_MAX_BODY_SIZE`], see [`HttpClient::max_body_size`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if any of the destinations is invalid
+    pub fn new(allow: impl IntoIterator<Item = impl AsRef<str>>) -> anyhow::Result<Self> {
+        let allow = allow
+            .into_iter()
+            .map(|dst| dst.as_ref().parse())
+            .collect::<anyhow::Result<Arc<[_]>>>()?;
+        let client = Self::client_builder(&allow)
+            .build()
+            .context("failed to build HTTP client")?;
+        Ok(Self {
+            client,
+            allow,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        })
+    }
+
+    /// Set the maximum size of request and response bodies in bytes. Requests with larger bodies
+    /// fail and so does reading of larger response bodies
+    #[must_use]
+    pub fn max_body_size(self, max_body_size: usize) -> Self {
+        Self {
+            max_body_size,
+            ..self
+        }
+    }
+
+    fn client_builder(allow: &Arc<[Destination]>) -> reqwest::ClientBuilder {
+        let allow = Arc::clone(allow);
+        reqwest::Client::builder().redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            let url = attempt.url();
+            if is_allowed(
+                &allow,
+                url.scheme(),
+                url.host_str().unwrap_or_default(),
+                url.port(),
+            ) {
+                attempt.follow()
+            } else {
+                let err = format!("redirect to `{url}` is not allowed");
+                attempt.error(err)
+            }
+        }))
+    }
+
+    fn is_allowed(&self, uri: &http::Uri) -> bool {
+        match (uri.scheme_str(), uri.host()) {
+            (Some(scheme), Some(host)) => is_allowed(&self.allow, scheme, host, uri.port_u16()),
+            _ => false,
+        }
+    }
+}
+
+#[async_trait]
+impl OutgoingHttp for HttpClient {
+    #[instrument(skip(request))]
+    async fn handle(
+        &self,
+        request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+        options: Option<RequestOptions>,
+    ) -> anyhow::Result<http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>> {
+        let (
+            http::request::Parts {
+                method,
+                uri,
+                headers,
+                ..
+            },
+            mut body,
+        ) = request.into_parts();
+        ensure!(self.is_allowed(&uri), "destination `{uri}` is not allowed");
+
+        let timeout = |ms: Option<u32>| ms.map(|ms| Duration::from_millis(ms.into()));
+        let (connect_timeout, first_byte_timeout, between_bytes_timeout) = options.map_or(
+            (None, None, None),
+            |RequestOptions {
+                 connect_timeout_ms,
+                 first_byte_timeout_ms,
+                 between_bytes_timeout_ms,
+             }| {
+                (
+                    timeout(connect_timeout_ms),
+                    timeout(first_byte_timeout_ms),
+                    timeout(between_bytes_timeout_ms),
+                )
+            },
+        );
+        // connect timeout can only be configured per client
+        let client = if let Some(connect_timeout) = connect_timeout {
+            Self::client_builder(&self.allow)
+                .connect_timeout(connect_timeout)
+                .build()
+                .context("failed to build HTTP client")?
+        } else {
+            self.client.clone()
+        };
+
+        let max_body_size = self.max_body_size;
+        let limit = u64::try_from(max_body_size)
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+        let mut buf = vec![];
+        (&mut body)
+            .take(limit)
+            .read_to_end(&mut buf)
+            .await
+            .context("failed to read request body")?;
+        ensure!(
+            buf.len() <= max_body_size,
+            "request body exceeds maximum size of {max_body_size} bytes"
+        );
+        let req = client
+            .request(method, uri.to_string())
+            .headers(headers)
+            .body(buf)
+            .send();
+        trace!(?uri, "send request");
+        let mut res = if let Some(first_byte_timeout) = first_byte_timeout {
+            time::timeout(first_byte_timeout, req)
+                .await
+                .context("timed out waiting for response")?
+        } else {
+            req.await
+        }
+        .context("failed to send request")?;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let mut body = vec![];
+        loop {
+            let chunk = if let Some(between_bytes_timeout) = between_bytes_timeout {
+                time::timeout(between_bytes_timeout, res.chunk())
+                    .await
+                    .context("timed out waiting for response body")?
+            } else {
+                res.chunk().await
+            }
+            .context("failed to read response body")?;
+            let Some(chunk) = chunk else { break };
+            ensure!(
+                chunk.len() <= max_body_size.saturating_sub(body.len()),
+                "response body exceeds maximum size of {max_body_size} bytes"
+            );
+            body.extend_from_slice(&chunk);
+        }
+
+        let mut response = http::Response::builder()
+            .status(status)
+            .body(Box::new(Cursor::new(body)) as Box<dyn AsyncRead + Sync + Send + Unpin>)
+            .context("failed to build response")?;
+        *response.headers_mut() = headers;
+        Ok(response)
+    }
+}
+
+/// HTTP/1.1 and HTTP/2 server, which routes requests to `wasi:http/incoming-handler` exports of actors
+/// by path prefix
+#[derive(Clone)]
+pub struct HttpServer {
+    /// Routes as `(prefix, target)` pairs, ordered by descending prefix length
+    routes: Arc<[(String, String)]>,
+    tls: Option<TlsAcceptor>,
+    handshake_timeout: Duration,
+    timeout: Option<Duration>,
+}
+
+impl Debug for HttpServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpServer")
+            .field("routes", &self.routes)
+            .field("tls", &self.tls.is_some())
+            .field("handshake_timeout", &self.handshake_timeout)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// Constructs a [`TlsAcceptor`] using PEM-encoded certificate chain and private key read from disk
+#[instrument]
+async fn load_tls(TlsConfig { cert, key, .. }: &TlsConfig) -> anyhow::Result<TlsAcceptor> {
+    let pem = fs::read(cert)
+        .await
+        .with_context(|| format!("failed to read `{}`", cert.display()))?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice())
+        .with_context(|| format!("failed to parse certificates in `{}`", cert.display()))?;
+    ensure!(
+        !certs.is_empty(),
+        "no certificates found in `{}`",
+        cert.display()
+    );
+
+    let pem = fs::read(key)
+        .await
+        .with_context(|| format!("failed to read `{}`", key.display()))?;
+    let private_key = rustls_pemfile::read_all(&mut pem.as_slice())
+        .with_context(|| format!("failed to parse `{}`", key.display()))?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => Some(key),
+            _ => None,
+        })
+        .with_context(|| format!("no private key found in `{}`", key.display()))?;
+
+    let mut config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            certs.into_iter().map(Certificate).collect(),
+            PrivateKey(private_key),
+        )
+        .context("invalid certificate or private key")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+fn status_response(status: StatusCode) -> hyper::Response<Body> {
+    let mut response = hyper::Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+impl HttpServer {
+    /// Construct a new [`HttpServer`] serving `routes`. If `tls` is set, the certificate and
+    /// private key are read from disk and only HTTPS is served. `timeout` limits the time until
+    /// response headers are returned, request and response bodies are streamed.
+    pub async fn new(
+        routes: &[HttpRouteConfig],
+        tls: Option<&TlsConfig>,
+        timeout: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        let handshake_timeout = tls
+            .and_then(|tls| tls.handshake_timeout_ms)
+            .map_or(TLS_HANDSHAKE_TIMEOUT, Duration::from_millis);
+        let tls = if let Some(tls) = tls {
+            Some(load_tls(tls).await.context("failed to configure TLS")?)
+        } else {
+            None
+        };
+        let mut routes: Vec<_> = routes
+            .iter()
+            .map(|HttpRouteConfig { prefix, target }| {
+                (prefix.trim_end_matches('/').to_string(), target.clone())
+            })
+            .collect();
+        routes.sort_by_key(|(prefix, _)| Reverse(prefix.len()));
+        Ok(Self {
+            routes: routes.into(),
+            tls,
+            handshake_timeout,
+            timeout,
+        })
+    }
+
+    /// Returns the name of the actor `path` is routed to
+    fn route(&self, path: &str) -> Option<&str> {
+        self.routes.iter().find_map(|(prefix, target)| {
+            let rest = path.strip_prefix(prefix.as_str())?;
+            (rest.is_empty() || rest.starts_with('/')).then_some(target.as_str())
+        })
+    }
+
+    #[instrument(skip_all, fields(method = %request.method(), uri = %request.uri()))]
+    async fn handle(
+        &self,
+        snapshot: &Snapshot,
+        request: hyper::Request<Body>,
+    ) -> anyhow::Result<hyper::Response<Body>> {
+        let Some(target) = self.route(request.uri().path()) else {
+            return Ok(status_response(StatusCode::NOT_FOUND));
+        };
+        trace!(actor = target, "route request");
+        let (parts, body) = request.into_parts();
+        let body = StreamReader::new(body.map_err(|e| io::Error::new(io::ErrorKind::Other, e)));
+        let request = http::Request::from_parts(
+            parts,
+            Box::new(body) as Box<dyn AsyncRead + Sync + Send + Unpin>,
+        );
+        let response = get_actor_link(&snapshot.actors, target)
+            .await
+            .map(ActorInstance::from)?
+            .into_incoming_http()
+            .await
+            .with_context(|| {
+                format!("failed to instantiate `wasi:http/incoming-handler` of `{target}`")
+            })?
+            .handle(request)
+            .await
+            .with_context(|| format!("`{target}` failed to handle request"))?;
+        let (parts, body) = response.into_parts();
+        Ok(hyper::Response::from_parts(
+            parts,
+            Body::wrap_stream(ReaderStream::new(body)),
+        ))
+    }
+
+    /// Serves HTTP requests received on `stream`. Each request is handled by the server configured
+    /// for `addr` in the snapshot current at the time the request is received, so that requests on
+    /// keep-alive connections observe reloads.
+    pub(super) async fn serve(
+        &self,
+        stream: TcpStream,
+        addr: String,
+        snapshot: Arc<RwLock<Arc<Snapshot>>>,
+    ) -> anyhow::Result<()> {
+        let service = service_fn(move |request| {
+            let addr = addr.clone();
+            let snapshot = Arc::clone(&snapshot);
+            async move {
+                let snapshot = Arc::clone(&*snapshot.read().await);
+                let Some(Socket::Http(server)) = snapshot.sockets.get(&addr) else {
+                    warn!(%addr, "HTTP link removed, reject request");
+                    return Ok::<_, Infallible>(status_response(StatusCode::SERVICE_UNAVAILABLE));
+                };
+                let response = if let Some(timeout) = server.timeout {
+                    time::timeout(timeout, server.handle(&snapshot, request))
+                        .await
+                        .unwrap_or_else(|_| {
+                            warn!(?timeout, "HTTP request timed out");
+                            Ok(status_response(StatusCode::GATEWAY_TIMEOUT))
+                        })
+                } else {
+                    server.handle(&snapshot, request).await
+                };
+                Ok(response.unwrap_or_else(|e| {
+                    error!("failed to handle HTTP request: {e:?}");
+                    status_response(StatusCode::INTERNAL_SERVER_ERROR)
+                }))
+            }
+        });
+        if let Some(tls) = &self.tls {
+            let stream = time::timeout(self.handshake_timeout, tls.accept(stream))
+                .await
+                .context("TLS handshake timed out")?
+                .context("failed to perform TLS handshake")?;
+            Http::new().serve_connection(stream, service).await
+        } else {
+            Http::new().serve_connection(stream, service).await
+        }
+        .context("failed to serve HTTP connection")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    use tokio::net::TcpListener;
+
+    /// Serves `ok` on a random local port, redirects `/redirect` to `location` and delays `/slow`
+    async fn serve(location: Option<String>) -> anyhow::Result<u16> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let port = listener.local_addr()?.port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let location = location.clone();
+                let service = service_fn(move |req: hyper::Request<Body>| {
+                    let location = location.clone();
+                    async move {
+                        let mut res = hyper::Response::new(Body::from("ok"));
+                        match (req.uri().path(), location) {
+                            ("/redirect", Some(location)) => {
+                                *res.status_mut() = StatusCode::FOUND;
+                                res.headers_mut().insert(
+                                    http::header::LOCATION,
+                                    location.parse().expect("invalid location"),
+                                );
+                            }
+                            ("/slow", _) => time::sleep(Duration::from_secs(1)).await,
+                            _ => {}
+                        }
+                        Ok::<_, Infallible>(res)
+                    }
+                });
+                tokio::spawn(Http::new().serve_connection(stream, service));
+            }
+        });
+        Ok(port)
+    }
+
+    async fn get(
+        client: &HttpClient,
+        uri: &str,
+        options: Option<RequestOptions>,
+    ) -> anyhow::Result<Vec<u8>> {
+        let req = http::Request::get(uri)
+            .body(Box::new(tokio::io::empty()) as Box<dyn AsyncRead + Sync + Send + Unpin>)?;
+        let res = client.handle(req, options).await?;
+        ensure!(res.status() == StatusCode::OK, "unexpected status");
+        let mut body = vec![];
+        res.into_body().read_to_end(&mut body).await?;
+        Ok(body)
+    }
+
+    #[tokio::test]
+    async fn outgoing() -> anyhow::Result<()> {
+        let target = serve(None).await?;
+        let origin = serve(Some(format!("http://127.0.0.1:{target}/"))).await?;
+
+        let client = HttpClient::new([format!("127.0.0.1:{origin}")])?;
+        assert_eq!(
+            get(&client, &format!("http://127.0.0.1:{origin}/"), None).await?,
+            b"ok"
+        );
+        get(&client, &format!("http://127.0.0.1:{target}/"), None)
+            .await
+            .expect_err("request to a port not allowed must fail");
+        get(&client, &format!("http://localhost:{origin}/"), None)
+            .await
+            .expect_err("request to a host not allowed must fail");
+        get(
+            &client,
+            &format!("http://127.0.0.1:{origin}/redirect"),
+            None,
+        )
+        .await
+        .expect_err("redirect to a destination not allowed must fail");
+
+        let client = HttpClient::new([format!("https://127.0.0.1:{origin}")])?;
+        get(&client, &format!("http://127.0.0.1:{origin}/"), None)
+            .await
+            .expect_err("request using a scheme not allowed must fail");
+
+        let client = HttpClient::new(["127.0.0.1"])?;
+        get(&client, &format!("http://127.0.0.1:{origin}/"), None)
+            .await
+            .expect_err("destination without a port must only allow the default port");
+
+        let client = HttpClient::new([
+            format!("http://127.0.0.1:{origin}"),
+            format!("127.0.0.1:{target}"),
+        ])?;
+        assert_eq!(
+            get(
+                &client,
+                &format!("http://127.0.0.1:{origin}/redirect"),
+                None
+            )
+            .await?,
+            b"ok"
+        );
+        get(
+            &client,
+            &format!("http://127.0.0.1:{origin}/slow"),
+            Some(RequestOptions {
+                connect_timeout_ms: Some(1000),
+                first_byte_timeout_ms: Some(100),
+                between_bytes_timeout_ms: None,
+            }),
+        )
+        .await
+        .expect_err("slow response must time out");
+
+        let client = HttpClient::new([format!("127.0.0.1:{origin}")])?.max_body_size(2);
+        assert_eq!(
+            get(&client, &format!("http://127.0.0.1:{origin}/"), None).await?,
+            b"ok"
+        );
+        let req = http::Request::post(format!("http://127.0.0.1:{origin}/"))
+            .body(Box::new(Cursor::new(b"foo")) as Box<dyn AsyncRead + Sync + Send + Unpin>)?;
+        client
+            .handle(req, None)
+            .await
+            .expect_err("request body exceeding the maximum size must fail");
+        let client = client.max_body_size(1);
+        get(&client, &format!("http://127.0.0.1:{origin}/"), None)
+            .await
+            .expect_err("response body exceeding the maximum size must fail");
+
+        HttpClient::new(["ftp://example.com"]).expect_err("unsupported scheme must fail");
+        HttpClient::new(["example.com:port"]).expect_err("invalid port must fail");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn routes() -> anyhow::Result<()> {
+        let server = HttpServer::new(
+            &[
+                HttpRouteConfig {
+                    prefix: "/".into(),
+                    target: "web".into(),
+                },
+                HttpRouteConfig {
+                    prefix: "/api/".into(),
+                    target: "api".into(),
+                },
+                HttpRouteConfig {
+                    prefix: "/api/v2".into(),
+                    target: "api-v2".into(),
+                },
+            ],
+            None,
+            None,
+        )
+        .await?;
+        assert_eq!(server.route("/"), Some("web"));
+        assert_eq!(server.route("/index.html"), Some("web"));
+        assert_eq!(server.route("/api"), Some("api"));
+        assert_eq!(server.route("/api/users"), Some("api"));
+        assert_eq!(server.route("/apis"), Some("web"));
+        assert_eq!(server.route("/api/v2/users"), Some("api-v2"));
+        assert_eq!(server.route("/api/v20"), Some("api"));
+
+        let server = HttpServer::new(
+            &[HttpRouteConfig {
+                prefix: "/api".into(),
+                target: "api".into(),
+            }],
+            None,
+            None,
+        )
+        .await?;
+        assert_eq!(server.route("/"), None);
+        assert_eq!(server.route("/api/users"), Some("api"));
+
+        HttpServer::new(
+            &[],
+            Some(&TlsConfig {
+                cert: "/nonexistent/cert.pem".into(),
+                key: "/nonexistent/key.pem".into(),
+                handshake_timeout_ms: None,
+            }),
+            None,
+        )
+        .await
+        .expect_err("missing certificate must fail");
+        Ok(())
+    }
+}
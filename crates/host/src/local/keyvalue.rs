@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::future::Future;
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context as _};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tracing::{instrument, trace, warn};
+use wasmcloud_runtime::capability::provider::MemoryKeyValue;
+use wasmcloud_runtime::capability::{KeyValueAtomic, KeyValueBatch, KeyValueReadWrite};
+
+type Buckets = HashMap<String, HashMap<String, Vec<u8>>>;
+
+/// Minimum size of the log in bytes, before it is compacted into the snapshot
+const MIN_COMPACT_SIZE: usize = 1024 * 1024;
+
+/// Write appended to the log of a persisted [`KeyValue`] store
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", tag = "op")]
+enum LogEntry {
+    /// Values set in a bucket
+    Set {
+        bucket: String,
+        entries: Vec<(String, Vec<u8>)>,
+    },
+    /// Keys deleted from a bucket
+    Delete { bucket: String, keys: Vec<String> },
+}
+
+impl LogEntry {
+    fn apply(self, buckets: &mut Buckets) {
+        match self {
+            Self::Set { bucket, entries } => buckets.entry(bucket).or_default().extend(entries),
+            Self::Delete { bucket, keys } => {
+                if let Some(bucket) = buckets.get_mut(&bucket) {
+                    for key in keys {
+                        bucket.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the path of the log of the store persisted at `snapshot`
+fn log_path(snapshot: &Path) -> PathBuf {
+    let mut path = OsString::from(snapshot);
+    path.push(".log");
+    path.into()
+}
+
+/// Newline-delimited JSON log of writes applied to a persisted [`KeyValue`] store since its
+/// snapshot was written
+#[derive(Debug)]
+struct Log {
+    snapshot: PathBuf,
+    file: fs::File,
+    /// Size of the log in bytes
+    size: usize,
+    /// Size of the snapshot in bytes
+    snapshot_size: usize,
+}
+
+impl Log {
+    /// Writes `buckets` to the snapshot file and starts a new, empty log
+    async fn create(snapshot: PathBuf, buckets: &Buckets) -> anyhow::Result<Self> {
+        let buf = serde_json::to_vec(buckets).context("failed to encode snapshot")?;
+        // NOTE: Write to a temporary file first, so that a partially-written snapshot is never read
+        let tmp = snapshot.with_extension("tmp");
+        fs::write(&tmp, &buf)
+            .await
+            .with_context(|| format!("failed to write `{}`", tmp.display()))?;
+        fs::rename(&tmp, &snapshot)
+            .await
+            .with_context(|| format!("failed to rename `{}`", tmp.display()))?;
+        // NOTE: If the log is not truncated, entries already contained in the snapshot are replayed
+        // on open, which is idempotent
+        let path = log_path(&snapshot);
+        let file = fs::File::create(&path)
+            .await
+            .with_context(|| format!("failed to create `{}`", path.display()))?;
+        trace!(path = %snapshot.display(), "persisted key-value snapshot");
+        Ok(Self {
+            snapshot,
+            file,
+            size: 0,
+            snapshot_size: buf.len(),
+        })
+    }
+
+    /// Appends `entry` to the log
+    async fn append(&mut self, entry: &LogEntry) -> anyhow::Result<()> {
+        let mut buf = serde_json::to_vec(entry).context("failed to encode log entry")?;
+        buf.push(b'\n');
+        self.file
+            .write_all(&buf)
+            .await
+            .context("failed to write log entry")?;
+        self.file.flush().await.context("failed to flush log")?;
+        self.size += buf.len();
+        Ok(())
+    }
+}
+
+/// Replays the log at `path` onto `buckets`. A trailing, partially-written entry is ignored.
+async fn replay(path: &Path, buckets: &mut Buckets) -> anyhow::Result<()> {
+    let buf = match fs::read(path).await {
+        Ok(buf) => buf,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read `{}`", path.display())),
+    };
+    let mut lines = buf.split(|b| *b == b'\n').enumerate().peekable();
+    while let Some((i, line)) = lines.next() {
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_slice::<LogEntry>(line) {
+            Ok(entry) => entry.apply(buckets),
+            Err(e) if lines.peek().is_none() => {
+                warn!(path = %path.display(), "ignore partially-written log entry: {e}");
+            }
+            Err(e) => bail!(
+                "failed to parse entry on line {} of `{}`: {e}",
+                i + 1,
+                path.display()
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// In-memory key-value store, which is optionally persisted to disk as a JSON snapshot and a log of
+/// writes applied since. The log is compacted into the snapshot on open and once it grows larger
+/// than the snapshot.
+#[derive(Debug)]
+pub struct KeyValue {
+    store: MemoryKeyValue,
+    snapshot: Option<PathBuf>,
+    /// Log held locked while writing, which ensures that entries are appended in the order writes
+    /// are applied to the store
+    log: Mutex<Option<Log>>,
+}
+
+impl KeyValue {
+    /// Opens a store persisted at `snapshot`, if set. If the snapshot file does not exist, the
+    /// store is empty.
+    #[instrument]
+    pub async fn open(snapshot: Option<PathBuf>) -> anyhow::Result<Self> {
+        let mut buckets = Buckets::default();
+        let log = if let Some(path) = &snapshot {
+            match fs::read(path).await {
+                Ok(buf) => {
+                    buckets = serde_json::from_slice(&buf)
+                        .with_context(|| format!("failed to parse `{}`", path.display()))?;
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("failed to read `{}`", path.display()))
+                }
+            }
+            replay(&log_path(path), &mut buckets)
+                .await
+                .context("failed to replay key-value log")?;
+            Some(Log::create(path.clone(), &buckets).await?)
+        } else {
+            None
+        };
+        Ok(Self {
+            store: buckets.into(),
+            snapshot,
+            log: Mutex::new(log),
+        })
+    }
+
+    /// Path of the snapshot file, if the store is persisted
+    pub fn snapshot_path(&self) -> Option<&Path> {
+        self.snapshot.as_deref()
+    }
+
+    /// Applies the write `op` to the store and appends the [`LogEntry`] it returns to the log, if
+    /// the store is persisted
+    async fn write<T>(
+        &self,
+        op: impl Future<Output = anyhow::Result<(T, Option<LogEntry>)>> + Send,
+    ) -> anyhow::Result<T> {
+        let mut log = self.log.lock().await;
+        let (ret, entry) = op.await?;
+        let (Some(log), Some(entry)) = (log.as_mut(), entry) else {
+            return Ok(ret);
+        };
+        log.append(&entry).await?;
+        if log.size > log.snapshot_size.max(MIN_COMPACT_SIZE) {
+            *log = Log::create(log.snapshot.clone(), &self.store.snapshot().await)
+                .await
+                .context("failed to compact key-value log")?;
+        }
+        Ok(ret)
+    }
+}
+
+#[async_trait]
+impl KeyValueReadWrite for KeyValue {
+    async fn get(
+        &self,
+        bucket: &str,
+        key: String,
+    ) -> anyhow::Result<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)> {
+        self.store.get(bucket, key).await
+    }
+
+    async fn set(
+        &self,
+        bucket: &str,
+        key: String,
+        mut value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+    ) -> anyhow::Result<()> {
+        let mut buf = vec![];
+        value
+            .read_to_end(&mut buf)
+            .await
+            .context("failed to read value")?;
+        self.write(async {
+            self.store
+                .set(bucket, key.clone(), Box::new(Cursor::new(buf.clone())))
+                .await?;
+            let entry = LogEntry::Set {
+                bucket: bucket.into(),
+                entries: vec![(key, buf)],
+            };
+            Ok(((), Some(entry)))
+        })
+        .await
+    }
+
+    async fn delete(&self, bucket: &str, key: String) -> anyhow::Result<()> {
+        self.write(async {
+            self.store.delete(bucket, key.clone()).await?;
+            let entry = LogEntry::Delete {
+                bucket: bucket.into(),
+                keys: vec![key],
+            };
+            Ok(((), Some(entry)))
+        })
+        .await
+    }
+
+    async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool> {
+        self.store.exists(bucket, key).await
+    }
+}
+
+#[async_trait]
+impl KeyValueAtomic for KeyValue {
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
+        self.write(async {
+            let value = self.store.increment(bucket, key.clone(), delta).await?;
+            let entry = LogEntry::Set {
+                bucket: bucket.into(),
+                entries: vec![(key, value.to_string().into_bytes())],
+            };
+            Ok((value, Some(entry)))
+        })
+        .await
+    }
+
+    async fn compare_and_swap(
+        &self,
+        bucket: &str,
+        key: String,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<bool> {
+        self.write(async {
+            let swapped = self
+                .store
+                .compare_and_swap(bucket, key.clone(), old, new)
+                .await?;
+            let entry = swapped.then(|| LogEntry::Set {
+                bucket: bucket.into(),
+                entries: vec![(key, new.to_string().into_bytes())],
+            });
+            Ok((swapped, entry))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl KeyValueBatch for KeyValue {
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        self.store.get_many(bucket, keys).await
+    }
+
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        self.store.get_keys(bucket).await
+    }
+
+    async fn set_many(
+        &self,
+        bucket: &str,
+        entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        let mut values = Vec::with_capacity(entries.len());
+        for (key, mut value) in entries {
+            let mut buf = vec![];
+            value
+                .read_to_end(&mut buf)
+                .await
+                .with_context(|| format!("failed to read value of `{key}`"))?;
+            values.push((key, buf));
+        }
+        // NOTE: All values are appended to the log as a single entry
+        self.write(async {
+            let entries = values
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.clone(),
+                        Box::new(Cursor::new(value.clone()))
+                            as Box<dyn AsyncRead + Sync + Send + Unpin>,
+                    )
+                })
+                .collect();
+            self.store.set_many(bucket, entries).await?;
+            let entry = LogEntry::Set {
+                bucket: bucket.into(),
+                entries: values,
+            };
+            Ok(((), Some(entry)))
+        })
+        .await
+    }
+
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        self.write(async {
+            self.store.delete_many(bucket, keys.clone()).await?;
+            let entry = LogEntry::Delete {
+                bucket: bucket.into(),
+                keys,
+            };
+            Ok(((), Some(entry)))
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn persistence() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+        let path = dir.path().join("kv.json");
+
+        let kv = KeyValue::open(Some(path.clone())).await?;
+        assert!(!kv.exists("bucket", "foo".into()).await?);
+        kv.set("bucket", "foo".into(), Box::new(Cursor::new(b"bar")))
+            .await?;
+        assert_eq!(kv.increment("counters", "hits".into(), 2).await?, 2);
+
+        // NOTE: Simulate a crash while appending an entry, which must be ignored on open
+        let log = fs::read_to_string(log_path(&path)).await?;
+        assert_eq!(log.lines().count(), 2);
+        fs::write(log_path(&path), format!("{log}{{\"op\":\"set\"")).await?;
+
+        let kv = KeyValue::open(Some(path.clone())).await?;
+        assert!(fs::read(log_path(&path)).await?.is_empty());
+        let (mut value, size) = kv.get("bucket", "foo".into()).await?;
+        let mut buf = vec![];
+        value.read_to_end(&mut buf).await?;
+        assert_eq!((buf.as_slice(), size), (b"bar".as_slice(), 3));
+        assert_eq!(kv.increment("counters", "hits".into(), 1).await?, 3);
+
+        kv.delete("bucket", "foo".into()).await?;
+        assert!(!kv.exists("bucket", "foo".into()).await?);
+
+        let kv = KeyValue::open(None).await?;
+        assert!(kv.get_keys("bucket").await?.is_empty());
+        Ok(())
+    }
+}
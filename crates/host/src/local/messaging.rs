@@ -0,0 +1,208 @@
+use super::{get_actor_link, Snapshot};
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use anyhow::{ensure, Context as _};
+use async_trait::async_trait;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::{spawn, time};
+use tracing::{error, instrument, trace, Instrument as _};
+use wasmcloud_runtime::capability::messaging::types::BrokerMessage;
+use wasmcloud_runtime::capability::{Messaging, MessagingHandler as _};
+use wasmcloud_runtime::ActorInstance;
+
+/// Prefix of subjects used for replies to requests
+const INBOX_PREFIX: &str = "_INBOX.";
+
+/// Returns `true` if `subject` matches subscription `pattern`.
+/// `*` matches a single token and a trailing `>` matches one or more tokens.
+pub fn subject_matches(pattern: &str, subject: &str) -> bool {
+    let mut pattern = pattern.split('.');
+    let mut subject = subject.split('.');
+    loop {
+        match (pattern.next(), subject.next()) {
+            (Some(">"), Some(_)) => return pattern.next().is_none(),
+            (Some("*"), Some(_)) => {}
+            (Some(p), Some(s)) if p == s => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// In-process pub/sub [`Messaging`] implementation shared by all actors of a local host.
+/// Messages are delivered to the `wasmcloud:messaging/handler` export of actors subscribed to the
+/// subject in the current host snapshot.
+#[derive(Debug)]
+pub struct Broker {
+    /// Host snapshot, which is weakly referenced, since actors in the snapshot hold the broker
+    snapshot: Weak<RwLock<Arc<Snapshot>>>,
+    inboxes: Mutex<HashMap<String, mpsc::UnboundedSender<BrokerMessage>>>,
+    next_inbox: AtomicU64,
+}
+
+impl Broker {
+    /// Construct a new [`Broker`] delivering messages to actors in `snapshot`
+    pub(super) fn new(snapshot: Weak<RwLock<Arc<Snapshot>>>) -> Self {
+        Self {
+            snapshot,
+            inboxes: Mutex::default(),
+            next_inbox: AtomicU64::default(),
+        }
+    }
+
+    /// Registers a new unique inbox subject, returning it along with the receiver of replies
+    async fn inbox(&self) -> (String, mpsc::UnboundedReceiver<BrokerMessage>) {
+        let inbox = format!(
+            "{INBOX_PREFIX}{}",
+            self.next_inbox.fetch_add(1, Ordering::Relaxed)
+        );
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.inboxes.lock().await.insert(inbox.clone(), tx);
+        (inbox, rx)
+    }
+
+    /// Delivers `msg` to all actors subscribed to its subject in separate tasks, returning the number of subscribers
+    #[instrument(skip(self, msg), fields(subject = %msg.subject))]
+    async fn deliver(&self, msg: BrokerMessage) -> anyhow::Result<usize> {
+        let snapshot = self.snapshot.upgrade().context("local host is shut down")?;
+        let snapshot = Arc::clone(&*snapshot.read().await);
+        let mut subscribers = 0;
+        for (pattern, target) in &snapshot.subscriptions {
+            if !subject_matches(pattern, &msg.subject) {
+                continue;
+            }
+            trace!(pattern, actor = target, "deliver message");
+            subscribers += 1;
+            let snapshot = Arc::clone(&snapshot);
+            let target = target.clone();
+            let msg = msg.clone();
+            spawn(
+                async move {
+                    if let Err(e) = async {
+                        get_actor_link(&snapshot.actors, &target)
+                            .await
+                            .map(ActorInstance::from)?
+                            .into_messaging_handler()
+                            .await
+                            .with_context(|| {
+                                format!("failed to establish `wasmcloud:messaging/handler` link to `{target}`")
+                            })?
+                            .handle_message(msg)
+                            .await
+                            .with_context(|| format!("`{target}` failed to handle message"))
+                    }
+                    .await
+                    {
+                        error!("failed to deliver message: {e:?}");
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+        Ok(subscribers)
+    }
+}
+
+#[async_trait]
+impl Messaging for Broker {
+    #[instrument(skip(self, body))]
+    async fn request(
+        &self,
+        subject: String,
+        body: Option<Vec<u8>>,
+        timeout: Duration,
+    ) -> anyhow::Result<BrokerMessage> {
+        self.request_multi(subject.clone(), body, timeout, 1)
+            .await?
+            .pop()
+            .with_context(|| format!("request on `{subject}` timed out"))
+    }
+
+    #[instrument(skip(self, body))]
+    async fn request_multi(
+        &self,
+        subject: String,
+        body: Option<Vec<u8>>,
+        timeout: Duration,
+        max_results: u32,
+    ) -> anyhow::Result<Vec<BrokerMessage>> {
+        ensure!(
+            max_results > 0 || !timeout.is_zero(),
+            "either a timeout or maximum number of results must be specified"
+        );
+        let max_results = match max_results {
+            0 => usize::MAX,
+            n => usize::try_from(n).unwrap_or(usize::MAX),
+        };
+        let (inbox, mut rx) = self.inbox().await;
+        let res = async {
+            let subscribers = self
+                .deliver(BrokerMessage {
+                    subject: subject.clone(),
+                    body,
+                    reply_to: Some(inbox.clone()),
+                })
+                .await?;
+            ensure!(subscribers > 0, "no subscribers for `{subject}`");
+            let mut replies = Vec::new();
+            let collect = async {
+                while replies.len() < max_results {
+                    let Some(reply) = rx.recv().await else {
+                        break;
+                    };
+                    replies.push(reply);
+                }
+            };
+            if timeout.is_zero() {
+                collect.await;
+            } else {
+                let _ = time::timeout(timeout, collect).await;
+            }
+            Ok(replies)
+        }
+        .await;
+        self.inboxes.lock().await.remove(&inbox);
+        res
+    }
+
+    #[instrument(skip(self, msg), fields(subject = %msg.subject))]
+    async fn publish(&self, msg: BrokerMessage) -> anyhow::Result<()> {
+        if msg.subject.starts_with(INBOX_PREFIX) {
+            let inbox = self.inboxes.lock().await.get(&msg.subject).cloned();
+            if let Some(inbox) = inbox {
+                trace!("deliver reply");
+                // NOTE: The requester may have stopped waiting for replies already
+                let _ = inbox.send(msg);
+                return Ok(());
+            }
+        }
+        self.deliver(msg).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subjects() {
+        assert!(subject_matches("orders", "orders"));
+        assert!(subject_matches("orders.*.created", "orders.42.created"));
+        assert!(subject_matches("orders.>", "orders.42"));
+        assert!(subject_matches("orders.>", "orders.42.created"));
+        assert!(subject_matches("*.>", "orders.42"));
+
+        assert!(!subject_matches("orders", "orders.42"));
+        assert!(!subject_matches("orders.*", "orders"));
+        assert!(!subject_matches("orders.*", "orders.42.created"));
+        assert!(!subject_matches("orders.>", "orders"));
+        assert!(!subject_matches("orders.*.created", "orders.42.deleted"));
+        assert!(!subject_matches("orders.>.created", "orders.42.created"));
+    }
+}
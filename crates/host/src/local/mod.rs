@@ -1,31 +1,51 @@
 /// Local host configuration
 pub mod config;
 
+mod admin;
+mod http;
+mod keyvalue;
+mod messaging;
+
 pub use config::{
-    Actor as ActorConfig, Host as HostConfig, Link as LinkConfig, TcpSocket as TcpSocketConfig,
+    Actor as ActorConfig, Admin as AdminConfig, Cache as CacheConfig, Host as HostConfig,
+    HttpRoute as HttpRouteConfig, Link as LinkConfig, LogLevel as LogLevelConfig,
+    Preopen as PreopenConfig, TcpSocket as TcpSocketConfig, Tls as TlsConfig,
 };
 
+use crate::fetch::Fetcher;
+use crate::logging::ActorLogger;
 use crate::socket_pair;
 
+use self::admin::ReloadRequest;
+use self::http::{HttpClient, HttpServer};
+use self::keyvalue::KeyValue;
+use self::messaging::Broker;
+
 use core::future::Future;
 use core::pin::Pin;
 
 use std::collections::HashMap;
+use std::mem::replace;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{anyhow, bail, Context as _};
 use async_recursion::async_recursion;
 use async_trait::async_trait;
 use futures::stream::{AbortHandle, Abortable};
 use futures::{stream, try_join, FutureExt, StreamExt, TryStreamExt};
-use tokio::io::{stderr, AsyncRead, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::RwLock;
-use tokio::{fs, spawn};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinSet;
+use tokio::time::{self, MissedTickBehavior};
+use tokio::{fs, select, spawn};
 use tokio_stream::wrappers::TcpListenerStream;
-use tracing::{debug, error, instrument, trace};
-use wasmcloud_runtime::actor::GuestInstance;
-use wasmcloud_runtime::capability::Bus;
+use tracing::{debug, error, info, instrument, trace, Instrument as _};
+use url::Url;
+use wasmcloud_runtime::actor::{GuestInstance, Preopen as ActorPreopen};
+use wasmcloud_runtime::capability::{logging, Bus};
 use wasmcloud_runtime::{ActorInstance, PooledActorInstance, Runtime};
 
 #[instrument]
@@ -57,13 +77,30 @@ async fn get_actor_link<'a>(
         .with_context(|| format!("failed to instantiate `{actor}` link"))
 }
 
-#[derive(Debug)]
+/// Target of a `wasi:logging/logging` link
+#[derive(Clone, Debug)]
+enum LoggingLink {
+    /// Logs are handled by the named actor
+    Actor(String),
+    /// Logs of at least `level` are emitted as host `tracing` events
+    Builtin { level: Option<logging::Level> },
+}
+
+#[derive(Clone, Debug)]
 struct Actor {
     actor: wasmcloud_runtime::ActorInstancePool,
-    logging: Option<String>,
+    /// URL the actor was loaded from
+    url: Url,
+    /// Modification time of the actor file at the time it was loaded, if it is a local file
+    modified: Option<SystemTime>,
+    logging: Option<LoggingLink>,
     incoming_http: Option<String>,
+    outgoing_http: Option<Arc<HttpClient>>,
+    keyvalue: Option<Arc<KeyValue>>,
+    messaging: Option<Arc<Broker>>,
     interfaces: HashMap<String, String>,
     runtime: Runtime,
+    logger: ActorLogger,
 }
 
 #[derive(Default)]
@@ -117,26 +154,77 @@ impl Extend<(String, GuestInstance)> for BusHandler {
 }
 
 impl Actor {
-    #[instrument]
-    pub async fn new(rt: &Runtime, ActorConfig { url }: &ActorConfig) -> anyhow::Result<Self> {
+    #[instrument(skip(fetcher))]
+    pub async fn new(
+        rt: &Runtime,
+        fetcher: &Fetcher,
+        name: &str,
+        ActorConfig {
+            url,
+            sha256,
+            argv0,
+            env,
+            preopens,
+        }: &ActorConfig,
+    ) -> anyhow::Result<Self> {
         trace!("create actor");
-        match url.scheme() {
-            "file" => {
-                let path = url
-                    .to_file_path()
-                    .map_err(|_| anyhow!("failed to convert `{url}` to a file path"))?;
-                let buf = fs::read(path).await.context("failed to read actor")?;
-                let actor = wasmcloud_runtime::Actor::new(rt, buf)
-                    .context("failed to initialize local actor")?;
-                Ok(Self {
-                    actor: actor.into(),
-                    logging: None,
-                    incoming_http: None,
-                    interfaces: HashMap::default(),
-                    runtime: rt.clone(),
-                })
-            }
-            scheme => bail!("`{scheme}` URLs not supported yet"),
+        let modified = modified(url).await;
+        let buf = fetcher
+            .fetch_actor(url, sha256.as_deref())
+            .await
+            .with_context(|| format!("failed to fetch actor from `{url}`"))?;
+        let mut actor =
+            wasmcloud_runtime::Actor::new(rt, buf).context("failed to initialize local actor")?;
+        actor.set_config(wasmcloud_runtime::ActorConfig {
+            argv0: argv0.clone(),
+            env: env.clone().into_iter().collect(),
+            preopens: preopens
+                .iter()
+                .map(
+                    |PreopenConfig {
+                         host,
+                         guest,
+                         read_only,
+                     }| ActorPreopen {
+                        host_path: host.clone(),
+                        guest_path: guest.clone(),
+                        read_only: *read_only,
+                    },
+                )
+                .collect(),
+            ..rt.actor_config().clone()
+        });
+        let logger = ActorLogger::new(
+            actor
+                .claims()
+                .map_or_else(|| name.to_string(), |claims| claims.subject.clone()),
+        )
+        .actor_name(Some(name.to_string()));
+        Ok(Self {
+            actor: actor.into(),
+            url: url.clone(),
+            modified,
+            logging: None,
+            incoming_http: None,
+            outgoing_http: None,
+            keyvalue: None,
+            messaging: None,
+            interfaces: HashMap::default(),
+            runtime: rt.clone(),
+            logger,
+        })
+    }
+
+    /// Returns a copy of the actor sharing the same instance pool, with all links removed
+    fn unlinked(&self) -> Self {
+        Self {
+            logging: None,
+            incoming_http: None,
+            outgoing_http: None,
+            keyvalue: None,
+            messaging: None,
+            interfaces: HashMap::default(),
+            ..self.clone()
         }
     }
 
@@ -150,7 +238,11 @@ impl Actor {
             actor,
             logging,
             incoming_http,
+            outgoing_http,
+            keyvalue,
+            messaging,
             interfaces,
+            logger,
             ..
         } = self;
         trace!("instantiate local actor");
@@ -159,7 +251,7 @@ impl Actor {
             .await
             .context("failed to instantiate actor")?;
         actor
-            .stderr(stderr()) // TODO: Add actor name prefix per-line?
+            .stderr(logger.stderr())
             .await
             .context("failed to set stderr")?;
         // NOTE: Instance pool does not currently support component model interfaces
@@ -179,7 +271,7 @@ impl Actor {
                     .map(Some)
             },
             async {
-                let Some(logging) = logging else {
+                let Some(LoggingLink::Actor(logging)) = logging else {
                     return Ok(None)
                 };
                 get_actor_link(actors, logging)
@@ -211,6 +303,24 @@ impl Actor {
         };
         if let Some(logging) = logging {
             actor.logging(Arc::new(logging));
+        } else {
+            let min_level = match &self.logging {
+                Some(LoggingLink::Builtin { level }) => *level,
+                _ => None,
+            };
+            actor.logging(Arc::new(logger.clone().min_level(min_level)));
+        };
+        if let Some(outgoing_http) = outgoing_http {
+            actor.outgoing_http(Arc::clone(outgoing_http) as _);
+        };
+        if let Some(keyvalue) = keyvalue {
+            actor
+                .keyvalue_readwrite(Arc::clone(keyvalue) as _)
+                .keyvalue_atomic(Arc::clone(keyvalue) as _)
+                .keyvalue_batch(Arc::clone(keyvalue) as _);
+        };
+        if let Some(messaging) = messaging {
+            actor.messaging(Arc::clone(messaging) as _);
         };
         Ok(actor)
     }
@@ -233,11 +343,54 @@ impl Actor {
     }
 }
 
+/// Protocol served on a socket
+#[derive(Debug)]
+enum Socket {
+    /// Raw TCP connections piped through a chain of actors
+    Tcp(Vec<String>),
+    /// HTTP requests routed to actors
+    Http(HttpServer),
+}
+
+/// Actors, links, sockets, messaging subscriptions and key-value stores of a local host, which are
+/// atomically replaced on reload. In-flight TCP connections keep using the snapshot they started
+/// with, while HTTP requests use the snapshot current at the time they are received.
+#[derive(Debug, Default)]
+struct Snapshot {
+    actors: HashMap<String, Actor>,
+    links: Vec<LinkConfig>,
+    sockets: HashMap<String, Socket>,
+    /// Subject patterns and names of actors subscribed to them
+    subscriptions: Vec<(String, String)>,
+    stores: HashMap<String, Arc<KeyValue>>,
+}
+
+/// Overrides applied to every [`HostConfig`] the [Host] is loaded with, e.g. specified on the
+/// command line
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Overrides {
+    /// Whether only previously cached remote actors are used regardless of [`CacheConfig::offline`]
+    pub offline: bool,
+}
+
+impl Overrides {
+    fn apply(self, config: &mut HostConfig) {
+        if self.offline {
+            config.cache.offline = true;
+        }
+    }
+}
+
 /// Local host
 #[derive(Debug)]
 pub struct Host {
-    #[allow(unused)] // TODO: Use and remove
-    actors: Arc<RwLock<HashMap<String, Actor>>>,
+    runtime: Runtime,
+    config: HostConfig,
+    overrides: Overrides,
+    snapshot: Arc<RwLock<Arc<Snapshot>>>,
+    broker: Arc<Broker>,
+    reload_tx: mpsc::Sender<ReloadRequest>,
+    reload_rx: mpsc::Receiver<ReloadRequest>,
     state: State,
 }
 
@@ -245,6 +398,7 @@ pub struct Host {
 #[derive(Debug, Default)]
 pub struct State {
     tcp_listeners: HashMap<String, AbortHandle>,
+    admin: Option<admin::Listener>,
 }
 
 impl Drop for Host {
@@ -255,144 +409,503 @@ impl Drop for Host {
     }
 }
 
-#[instrument]
+/// Calls actor `name`, which is stage number `stage` of a TCP chain, logging the time it took to complete
+#[instrument(skip(snapshot, request, response))]
+async fn call_chain_stage(
+    snapshot: Arc<Snapshot>,
+    stage: usize,
+    name: String,
+    request: impl AsyncRead + Send + Sync + Unpin + 'static,
+    response: impl AsyncWrite + Send + Sync + Unpin + 'static,
+) -> anyhow::Result<()> {
+    const OPERATION: &str = "wasmcloud:actor/stdio-handler.handle";
+    let start = Instant::now();
+    let res = get_actor(&snapshot.actors, &name)?
+        .call(&snapshot.actors, OPERATION, request, response)
+        .await
+        .with_context(|| format!("chain stage {stage} (`{name}`) failed"));
+    debug!(elapsed = ?start.elapsed(), ok = res.is_ok(), "chain stage completed");
+    res
+}
+
+/// Pipes `stream` through `chain`. All actors in the chain run concurrently in separate tasks,
+/// each connected to the next one via a socket pair, which provides backpressure.
+/// If any stage fails, all other stages are aborted, which closes the connection.
+#[instrument(skip(snapshot))]
 async fn handle_tcp_stream(
     stream: TcpStream,
     chain: &[String],
-    actors: &Arc<RwLock<HashMap<String, Actor>>>,
+    snapshot: &Arc<Snapshot>,
 ) -> anyhow::Result<()> {
-    const OPERATION: &str = "wasmcloud:actor/stdio-handler.handle";
+    for name in chain {
+        get_actor(&snapshot.actors, name)?;
+    }
+    let Some((last, init)) = chain.split_last() else {
+        bail!("chain cannot be empty")
+    };
     let (first_req, last_res) = stream.into_split();
-    let actors = actors.read().await;
-    match chain {
-        [] => bail!("chain cannot be empty"),
-        [actor] => {
-            get_actor(&actors, actor)?
-                .call(&actors, OPERATION, first_req, last_res)
-                .await
-        }
-        [first, last] => {
-            let first = get_actor(&actors, first)?;
-            let last = get_actor(&actors, last)?;
-            let (first_res, last_req) = socket_pair()?;
-            try_join!(
-                first.call(&actors, OPERATION, first_req, first_res),
-                last.call(&actors, OPERATION, last_req, last_res)
-            )?;
-            Ok(())
-        }
-        [first, chain @ .., last] => {
-            let first = get_actor(&actors, first)?;
-            let last = get_actor(&actors, last)?;
-            let (res, mut next_req) = socket_pair()?;
-            first.call(&actors, OPERATION, first_req, res).await?;
-            for actor in chain {
-                let actor = get_actor(&actors, actor)?;
-                let (res, next) = socket_pair()?;
-                actor.call(&actors, OPERATION, next_req, res).await?;
-                next_req = next;
+    let mut request: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(first_req);
+    // NOTE: Dropping the set aborts all stages, which are still running
+    let mut stages = JoinSet::new();
+    for (stage, name) in init.iter().enumerate() {
+        let (response, next) = socket_pair()?;
+        let request = replace(&mut request, Box::new(next));
+        stages.spawn(
+            call_chain_stage(Arc::clone(snapshot), stage, name.clone(), request, response)
+                .in_current_span(),
+        );
+    }
+    stages.spawn(
+        call_chain_stage(
+            Arc::clone(snapshot),
+            init.len(),
+            last.clone(),
+            request,
+            last_res,
+        )
+        .in_current_span(),
+    );
+    let start = Instant::now();
+    while let Some(res) = stages.join_next().await {
+        res.context("chain stage task failed")?
+            .context("failed to execute chain")?;
+    }
+    debug!(elapsed = ?start.elapsed(), "chain completed");
+    Ok(())
+}
+
+/// Serves TCP connections accepted by `listener` using the socket configured for `addr` in the current snapshot.
+/// Each connection is handled in a separate task, so aborting the listener does not drop in-flight connections.
+fn serve(addr: String, listener: TcpListener, snapshot: Arc<RwLock<Arc<Snapshot>>>) -> AbortHandle {
+    let (abort, abort_reg) = AbortHandle::new_pair();
+    spawn(
+        Abortable::new(TcpListenerStream::new(listener), abort_reg).for_each(move |stream| {
+            let addr = addr.clone();
+            let snapshot = Arc::clone(&snapshot);
+            async move {
+                spawn(async move {
+                    if let Err(e) = async {
+                        let stream = stream.context("failed to initialize TCP stream")?;
+                        debug!(
+                            "received TCP connection from {}",
+                            stream.peer_addr().map_or_else(
+                                |_| { "unknown address".into() },
+                                |peer| peer.to_string()
+                            )
+                        );
+                        let current = Arc::clone(&*snapshot.read().await);
+                        match current
+                            .sockets
+                            .get(&addr)
+                            .with_context(|| format!("no link configured for `{addr}`"))?
+                        {
+                            Socket::Tcp(chain) => handle_tcp_stream(stream, chain, &current)
+                                .await
+                                .context("failed to handle TCP stream"),
+                            Socket::Http(server) => server
+                                .serve(stream, addr.clone(), Arc::clone(&snapshot))
+                                .await
+                                .context("failed to handle HTTP connection"),
+                        }
+                    }
+                    .await
+                    {
+                        error!("failed to handle request: {e:?}");
+                    }
+                });
+            }
+        }),
+    );
+    abort
+}
+
+/// Returns the modification time of the file `url` refers to, if it is a `file` URL
+async fn modified(url: &Url) -> Option<SystemTime> {
+    if url.scheme() != "file" {
+        return None;
+    }
+    let path = url.to_file_path().ok()?;
+    fs::metadata(path).await.ok()?.modified().ok()
+}
+
+/// Converts a configured [`LogLevelConfig`] into a [`logging::Level`]
+fn log_level(level: LogLevelConfig) -> logging::Level {
+    match level {
+        LogLevelConfig::Trace => logging::Level::Trace,
+        LogLevelConfig::Debug => logging::Level::Debug,
+        LogLevelConfig::Info => logging::Level::Info,
+        LogLevelConfig::Warn => logging::Level::Warn,
+        LogLevelConfig::Error => logging::Level::Error,
+        LogLevelConfig::Critical => logging::Level::Critical,
+    }
+}
+
+/// Applies `links` to `actors`, returning the resulting [Snapshot].
+/// Key-value stores in `prev_stores` are reused, unless their snapshot path changed.
+#[instrument(skip(actors, prev_stores, broker))]
+async fn apply_links(
+    mut actors: HashMap<String, Actor>,
+    links: &[LinkConfig],
+    prev_stores: &HashMap<String, Arc<KeyValue>>,
+    broker: &Arc<Broker>,
+) -> anyhow::Result<Snapshot> {
+    let mut sockets = HashMap::default();
+    let mut subscriptions = Vec::default();
+    let mut stores: HashMap<String, Arc<KeyValue>> = HashMap::default();
+    for link in links {
+        match link {
+            LinkConfig::Tcp {
+                socket: TcpSocketConfig { addr },
+                chain,
+            } => {
+                trace!("link `{addr}` -> `{chain:?}` via TCP");
+                for actor in chain {
+                    get_actor(&actors, actor).context("chain actor not found")?;
+                }
+                sockets.insert(addr.clone(), Socket::Tcp(chain.clone()));
+            }
+            LinkConfig::Http {
+                socket: TcpSocketConfig { addr },
+                routes,
+                tls,
+                timeout_ms,
+            } => {
+                trace!("link `{addr}` -> `{routes:?}` via HTTP");
+                for HttpRouteConfig { target, .. } in routes {
+                    get_actor(&actors, target).context("route target actor not found")?;
+                }
+                let server =
+                    HttpServer::new(routes, tls.as_ref(), timeout_ms.map(Duration::from_millis))
+                        .await
+                        .with_context(|| format!("failed to configure HTTP server on `{addr}`"))?;
+                sockets.insert(addr.clone(), Socket::Http(server));
+            }
+            LinkConfig::Interface {
+                name,
+                source,
+                target,
+            } => {
+                trace!("link `{source}` -> `{target}` via `{name}` interface");
+                let source =
+                    get_actor_mut(&mut actors, source).context("source actor not found")?;
+                match name.as_str() {
+                    "wasi:logging/logging" => {
+                        let _ = source.logging.insert(LoggingLink::Actor(target.clone()));
+                    }
+                    "wasi:http/incoming-handler" => {
+                        let _ = source.incoming_http.insert(target.clone());
+                    }
+                    _ => {
+                        let _ = source.interfaces.insert(name.clone(), target.clone());
+                    }
+                }
+            }
+            LinkConfig::OutgoingHttp {
+                source,
+                allow,
+                max_body_size,
+            } => {
+                trace!("link `{source}` -> `{allow:?}` via `wasi:http/outgoing-handler`");
+                let mut client = HttpClient::new(allow).with_context(|| {
+                    format!("invalid outgoing HTTP destinations for `{source}`")
+                })?;
+                if let Some(max_body_size) = *max_body_size {
+                    client = client.max_body_size(max_body_size);
+                }
+                let source =
+                    get_actor_mut(&mut actors, source).context("source actor not found")?;
+                let _ = source.outgoing_http.insert(Arc::new(client));
+            }
+            LinkConfig::KeyValue {
+                source,
+                store,
+                snapshot,
+            } => {
+                trace!("link `{source}` -> `{store}` via built-in `wasi:keyvalue`");
+                let kv = match stores.get(store) {
+                    Some(kv) if kv.snapshot_path() == snapshot.as_deref() => Arc::clone(kv),
+                    Some(_) => {
+                        bail!("key-value store `{store}` is linked with conflicting snapshot paths")
+                    }
+                    None => {
+                        let kv = match prev_stores.get(store) {
+                            Some(kv) if kv.snapshot_path() == snapshot.as_deref() => Arc::clone(kv),
+                            _ => KeyValue::open(snapshot.clone())
+                                .await
+                                .map(Arc::new)
+                                .with_context(|| {
+                                    format!("failed to open key-value store `{store}`")
+                                })?,
+                        };
+                        stores.insert(store.clone(), Arc::clone(&kv));
+                        kv
+                    }
+                };
+                let source =
+                    get_actor_mut(&mut actors, source).context("source actor not found")?;
+                let _ = source.keyvalue.insert(kv);
+            }
+            LinkConfig::Logging { source, level } => {
+                trace!("link `{source}` -> built-in `wasi:logging/logging` at level `{level:?}`");
+                let source =
+                    get_actor_mut(&mut actors, source).context("source actor not found")?;
+                let _ = source.logging.insert(LoggingLink::Builtin {
+                    level: level.map(log_level),
+                });
+            }
+            LinkConfig::Messaging {
+                source,
+                subscriptions: subjects,
+            } => {
+                trace!("link `{source}` -> `{subjects:?}` via built-in `wasmcloud:messaging`");
+                let actor = get_actor_mut(&mut actors, source).context("source actor not found")?;
+                let _ = actor.messaging.insert(Arc::clone(broker));
+                subscriptions.extend(
+                    subjects
+                        .iter()
+                        .map(|subject| (subject.clone(), source.clone())),
+                );
             }
-            last.call(&actors, OPERATION, next_req, last_res).await
         }
     }
-    .context("failed to execute chain")
+    Ok(Snapshot {
+        actors,
+        links: links.to_vec(),
+        sockets,
+        subscriptions,
+        stores,
+    })
 }
 
 impl Host {
+    /// Interval at which [`Self::watch`] checks files for modifications
+    pub const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
     /// Construct a new [Host]
     #[instrument]
-    pub async fn new(HostConfig { actors, links }: HostConfig) -> anyhow::Result<Self> {
+    pub async fn new(config: HostConfig) -> anyhow::Result<Self> {
+        Self::with_overrides(config, Overrides::default()).await
+    }
+
+    /// Construct a new [Host], which applies `overrides` to `config` and all configurations it is
+    /// reloaded with
+    #[instrument]
+    pub async fn with_overrides(config: HostConfig, overrides: Overrides) -> anyhow::Result<Self> {
         // TODO: Configure
-        let rt = Runtime::builder()
+        let runtime = Runtime::builder()
             .build()
             .context("failed to build runtime")?;
-        let rt = Arc::new(rt);
+        let snapshot = Arc::default();
+        let broker = Arc::new(Broker::new(Arc::downgrade(&snapshot)));
+        let (reload_tx, reload_rx) = mpsc::channel(16);
+        let mut host = Self {
+            runtime,
+            config: HostConfig::default(),
+            overrides,
+            snapshot,
+            broker,
+            reload_tx,
+            reload_rx,
+            state: State::default(),
+        };
+        host.reload(config).await?;
+        Ok(host)
+    }
+
+    /// Reloads the host using `config`. Actors, which are new, have a changed configuration or are
+    /// backed by a local file, which was modified since the actor was loaded, are recompiled,
+    /// all other actors are reused. TCP listeners are added and removed according to the links in `config`.
+    /// The new configuration is swapped in atomically, without dropping in-flight connections.
+    /// [Overrides] of the host are applied to `config`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `config` cannot be applied, in which case the host is left unchanged
+    #[instrument(skip(self))]
+    pub async fn reload(&mut self, mut config: HostConfig) -> anyhow::Result<()> {
+        self.overrides.apply(&mut config);
+        let prev = Arc::clone(&*self.snapshot.read().await);
+        let fetcher = Fetcher::default();
+        let fetcher = Fetcher {
+            cache_dir: config.cache.dir.clone().unwrap_or(fetcher.cache_dir),
+            offline: config.cache.offline,
+            ..fetcher
+        };
+
         trace!("construct actor map");
-        let actors: HashMap<_, _> = stream::iter(actors)
-            .then(|(name, conf)| {
-                let rt = Arc::clone(&rt);
-                async move {
-                    Actor::new(&rt, &conf)
+        let mut actors = HashMap::with_capacity(config.actors.len());
+        for (name, conf) in &config.actors {
+            let modified = modified(&conf.url).await;
+            match prev.actors.get(name) {
+                Some(actor)
+                    if self.config.actors.get(name) == Some(conf) && actor.modified == modified =>
+                {
+                    trace!(actor = name, "reuse unchanged actor");
+                    actors.insert(name.clone(), actor.unlinked());
+                }
+                prev_actor => {
+                    let actor = Actor::new(&self.runtime, &fetcher, name, conf)
                         .await
                         .context("failed to create actor")
-                        .map(|actor| (name, actor))
+                        .context("failed to apply actor config")?;
+                    if prev_actor.is_some() {
+                        info!(actor = name, url = %conf.url, "reload actor");
+                    } else {
+                        info!(actor = name, url = %conf.url, "add actor");
+                    }
+                    actors.insert(name.clone(), actor);
                 }
-            })
-            .try_collect()
+            }
+        }
+        for name in prev.actors.keys() {
+            if !actors.contains_key(name) {
+                info!(actor = name, "remove actor");
+            }
+        }
+
+        let snapshot = apply_links(actors, &config.links, &prev.stores, &self.broker)
             .await
-            .context("failed to apply actor config")?;
-        let actors = Arc::new(RwLock::new(actors));
-
-        let state = stream::iter(links)
-            .map(anyhow::Result::<_>::Ok)
-            .try_fold(State::default(), |mut state, link| async {
-                match link {
-                    LinkConfig::Tcp {
-                        socket: TcpSocketConfig { addr },
-                        chain,
-                    } => {
-                        trace!("link `{addr}` -> `{chain:?}` via TCP");
-                        let listener = TcpListener::bind(&addr)
-                            .await
-                            .with_context(|| format!("failed to bind on `{addr}`"))?;
-                        let actors = Arc::clone(&actors);
-                        let chain = chain.clone();
-                        let (abort, abort_reg) = AbortHandle::new_pair();
-                        spawn(async move {
-                            Abortable::new(TcpListenerStream::new(listener), abort_reg)
-                                .for_each_concurrent(None, |stream| async {
-                                    if let Err(e) = async {
-                                        let stream =
-                                            stream.context("failed to initialize TCP stream")?;
-                                        debug!(
-                                            "received TCP connection from {}",
-                                            stream.peer_addr().map_or_else(
-                                                |_| { "unknown address".into() },
-                                                |peer| peer.to_string()
-                                            )
-                                        );
-                                        handle_tcp_stream(stream, &chain, &actors)
-                                            .await
-                                            .context("failed to handle TCP stream")
-                                    }
-                                    .await
-                                    {
-                                        error!("failed to handle request: {e:?}");
-                                    }
-                                })
-                                .await;
-                        });
-                        if let Some(prev) = state.tcp_listeners.insert(addr, abort) {
-                            prev.abort();
-                        };
-                        Ok(state)
+            .context("failed to apply link config")?;
+        let mut listeners = Vec::new();
+        for addr in snapshot.sockets.keys() {
+            if !self.state.tcp_listeners.contains_key(addr) {
+                let listener = TcpListener::bind(addr)
+                    .await
+                    .with_context(|| format!("failed to bind on `{addr}`"))
+                    .context("failed to apply link config")?;
+                listeners.push((addr.clone(), listener));
+            }
+        }
+        let admin_listener = match &config.admin {
+            Some(AdminConfig { addr, .. })
+                if self.state.admin.as_ref().map(admin::Listener::addr) != Some(addr.as_str()) =>
+            {
+                let listener = TcpListener::bind(addr)
+                    .await
+                    .with_context(|| format!("failed to bind on `{addr}`"))
+                    .context("failed to apply admin config")?;
+                Some(listener)
+            }
+            _ => None,
+        };
+        for link in &self.config.links {
+            if !config.links.contains(link) {
+                info!(?link, "remove link");
+            }
+        }
+        for link in &config.links {
+            if !self.config.links.contains(link) {
+                info!(?link, "add link");
+            }
+        }
+
+        let snapshot = Arc::new(snapshot);
+        *self.snapshot.write().await = Arc::clone(&snapshot);
+        self.state.tcp_listeners.retain(|addr, abort| {
+            if snapshot.sockets.contains_key(addr) {
+                true
+            } else {
+                info!(addr, "stop TCP listener");
+                abort.abort();
+                false
+            }
+        });
+        for (addr, listener) in listeners {
+            info!(addr, "start TCP listener");
+            let abort = serve(addr.clone(), listener, Arc::clone(&self.snapshot));
+            self.state.tcp_listeners.insert(addr, abort);
+        }
+        match (&config.admin, admin_listener) {
+            (Some(AdminConfig { addr, token }), Some(listener)) => {
+                self.state.admin = Some(admin::Listener::serve(
+                    addr.clone(),
+                    token.clone(),
+                    listener,
+                    Arc::clone(&self.snapshot),
+                    self.reload_tx.clone(),
+                ));
+            }
+            (Some(AdminConfig { token, .. }), None) => {
+                if let Some(admin) = &self.state.admin {
+                    admin.set_token(token.clone()).await;
+                }
+            }
+            (None, _) => self.state.admin = None,
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Returns modification times of the configuration file at `path` and of local actor files
+    async fn modification_times(
+        &self,
+        path: &Path,
+    ) -> (Option<SystemTime>, HashMap<String, Option<SystemTime>>) {
+        let config = fs::metadata(path)
+            .await
+            .ok()
+            .and_then(|md| md.modified().ok());
+        let mut actors = HashMap::with_capacity(self.config.actors.len());
+        for (name, ActorConfig { url, .. }) in &self.config.actors {
+            actors.insert(name.clone(), modified(url).await);
+        }
+        (config, actors)
+    }
+
+    /// Reloads the host from the configuration file at `path` on requests received via the admin
+    /// API and, if `watch` is set, whenever the configuration file or local actor files referenced by
+    /// the current configuration are modified.
+    /// Failed reloads are logged and the host keeps running using the previous configuration.
+    /// This future never completes and is expected to be dropped to stop.
+    #[instrument(skip(self, path), fields(path = %path.as_ref().display()))]
+    pub async fn run(&mut self, path: impl AsRef<Path>, watch: bool) {
+        let path = path.as_ref();
+        let mut last = self.modification_times(path).await;
+        let mut interval = time::interval(Self::WATCH_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            select! {
+                _ = interval.tick(), if watch => {
+                    let times = self.modification_times(path).await;
+                    if times == last {
+                        continue;
                     }
-                    LinkConfig::Interface {
-                        name,
-                        source,
-                        target,
-                    } => {
-                        trace!("link `{source}` -> `{target}` via `{name}` interface");
-                        let mut actors = actors.write().await;
-                        let source = get_actor_mut(&mut actors, &source)
-                            .context("source actor not found")?;
-                        match name.as_str() {
-                            "wasi:logging/logging" => {
-                                let _ = source.logging.insert(target);
-                            }
-                            "wasi:http/incoming-handler" => {
-                                let _ = source.incoming_http.insert(target);
-                            }
-                            _ => {
-                                let _ = source.interfaces.insert(name, target);
-                            }
+                    // NOTE: Modification times are recorded even if reload fails, to avoid retrying
+                    // until the files are modified again
+                    let (prev_config, _) = replace(&mut last, times);
+                    let res = if last.0 == prev_config {
+                        debug!("actor file modified, reload actors");
+                        self.reload(self.config.clone()).await
+                    } else {
+                        debug!("configuration file modified, reload configuration");
+                        match HostConfig::load(path).await {
+                            Ok(config) => self.reload(config).await,
+                            Err(e) => Err(e),
                         }
-                        Ok(state)
+                    };
+                    if let Err(e) = res {
+                        error!("failed to reload host: {e:?}");
                     }
                 }
-            })
-            .await
-            .context("failed to apply link config")?;
-        Ok(Self { actors, state })
+                Some(reply) = self.reload_rx.recv() => {
+                    info!("reload configuration on admin API request");
+                    let res = match HostConfig::load(path).await {
+                        Ok(config) => self.reload(config).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = &res {
+                        error!("failed to reload host: {e:?}");
+                    }
+                    // NOTE: The requester may have disconnected already
+                    let _ = reply.send(res);
+                }
+            }
+        }
+    }
+
+    /// Watches the configuration file at `path` and local actor files referenced by the current
+    /// configuration, reloading the host whenever they are modified. See [`Self::run`].
+    pub async fn watch(&mut self, path: impl AsRef<Path>) {
+        self.run(path, true).await;
     }
 }
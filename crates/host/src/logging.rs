@@ -0,0 +1,344 @@
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use std::io;
+
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::io::AsyncWrite;
+use tracing::{debug, error, info, instrument, trace, warn};
+use wasmcloud_runtime::capability::logging::logging;
+use wasmcloud_runtime::capability::Logging;
+
+/// Maximum length of a single line of actor stderr output, longer lines are split
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// [`Logging`] implementation, which emits actor logs as `tracing` events tagged with actor identity
+/// and optionally publishes them on a NATS subject
+#[derive(Clone, Debug)]
+pub struct ActorLogger {
+    actor_id: String,
+    actor_name: Option<String>,
+    instance_id: Option<String>,
+    invocation_id: Option<String>,
+    nats: Option<(async_nats::Client, String)>,
+    min_level: Option<logging::Level>,
+}
+
+/// Returns severity of `level`, where a higher value means a more severe level
+fn severity(level: logging::Level) -> u8 {
+    match level {
+        logging::Level::Trace => 0,
+        logging::Level::Debug => 1,
+        logging::Level::Info => 2,
+        logging::Level::Warn => 3,
+        logging::Level::Error => 4,
+        logging::Level::Critical => 5,
+    }
+}
+
+impl ActorLogger {
+    /// Construct a new [`ActorLogger`] for actor identified by `actor_id`
+    pub fn new(actor_id: impl Into<String>) -> Self {
+        Self {
+            actor_id: actor_id.into(),
+            actor_name: None,
+            instance_id: None,
+            invocation_id: None,
+            nats: None,
+            min_level: None,
+        }
+    }
+
+    /// Set human-readable actor name
+    #[must_use]
+    pub fn actor_name(self, actor_name: Option<String>) -> Self {
+        Self { actor_name, ..self }
+    }
+
+    /// Set actor instance ID
+    #[must_use]
+    pub fn instance_id(self, instance_id: impl Into<String>) -> Self {
+        Self {
+            instance_id: Some(instance_id.into()),
+            ..self
+        }
+    }
+
+    /// Set invocation ID
+    #[must_use]
+    pub fn invocation_id(self, invocation_id: impl Into<String>) -> Self {
+        Self {
+            invocation_id: Some(invocation_id.into()),
+            ..self
+        }
+    }
+
+    /// Publish logs as JSON on `subject` using `nats` in addition to emitting `tracing` events
+    #[must_use]
+    pub fn nats(self, nats: async_nats::Client, subject: impl Into<String>) -> Self {
+        Self {
+            nats: Some((nats, subject.into())),
+            ..self
+        }
+    }
+
+    /// Set minimum level of logs to emit, less severe logs are discarded
+    #[must_use]
+    pub fn min_level(self, min_level: Option<logging::Level>) -> Self {
+        Self { min_level, ..self }
+    }
+
+    /// Returns a line-buffered [`AsyncWrite`] writing to host stderr, which prefixes each line with actor name
+    pub fn stderr(&self) -> ActorStderr {
+        let name = self.actor_name.as_ref().unwrap_or(&self.actor_id);
+        ActorStderr::new(format!("[{name}] "))
+    }
+}
+
+#[async_trait]
+impl Logging for ActorLogger {
+    #[instrument(skip_all)]
+    async fn log(
+        &self,
+        level: logging::Level,
+        context: String,
+        message: String,
+    ) -> anyhow::Result<()> {
+        let Self {
+            actor_id,
+            actor_name,
+            instance_id,
+            invocation_id,
+            nats,
+            min_level,
+        } = self;
+        if min_level.is_some_and(|min_level| severity(level) < severity(min_level)) {
+            return Ok(());
+        }
+        macro_rules! log {
+            ($macro:ident) => {
+                $macro!(
+                    target: "actor",
+                    actor_id = %actor_id,
+                    actor_name = actor_name.as_deref(),
+                    instance_id = instance_id.as_deref(),
+                    invocation_id = invocation_id.as_deref(),
+                    context = %context,
+                    "{message}"
+                )
+            };
+        }
+        let level = match level {
+            logging::Level::Trace => {
+                log!(trace);
+                "trace"
+            }
+            logging::Level::Debug => {
+                log!(debug);
+                "debug"
+            }
+            logging::Level::Info => {
+                log!(info);
+                "info"
+            }
+            logging::Level::Warn => {
+                log!(warn);
+                "warn"
+            }
+            logging::Level::Error => {
+                log!(error);
+                "error"
+            }
+            logging::Level::Critical => {
+                log!(error);
+                "critical"
+            }
+        };
+        if let Some((nats, subject)) = nats {
+            let payload = json!({
+                "level": level,
+                "context": context,
+                "message": message,
+                "actor_id": actor_id,
+                "actor_name": actor_name,
+                "instance_id": instance_id,
+                "invocation_id": invocation_id,
+            });
+            // NOTE: Failure to publish a log must not fail the actor
+            if let Err(e) = nats
+                .publish(subject.clone(), payload.to_string().into())
+                .await
+            {
+                warn!(%subject, "failed to publish actor log: {e}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Line-buffered [`AsyncWrite`] writing to host stderr, which prefixes each line of output.
+/// Pending output is written when the [`ActorStderr`] is dropped.
+pub struct ActorStderr<W: io::Write = io::Stderr> {
+    prefix: String,
+    line: Vec<u8>,
+    out: W,
+}
+
+impl ActorStderr {
+    fn new(prefix: String) -> Self {
+        Self::with_writer(prefix, io::stderr())
+    }
+}
+
+impl<W: io::Write> ActorStderr<W> {
+    fn with_writer(prefix: String, out: W) -> Self {
+        Self {
+            prefix,
+            line: Vec::default(),
+            out,
+        }
+    }
+
+    /// Writes first `n` bytes of the pending line, terminated by a newline
+    fn write_line(&mut self, n: usize) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(self.prefix.len() + n + 1);
+        buf.extend_from_slice(self.prefix.as_bytes());
+        buf.extend(self.line.drain(..n));
+        if buf.last() != Some(&b'\n') {
+            buf.push(b'\n');
+        }
+        // NOTE: Each line is written at once to not interleave with output of other actors
+        self.out.write_all(&buf)
+    }
+
+    /// Writes the pending line, if any, and flushes the output
+    fn write_pending(&mut self) -> io::Result<()> {
+        if !self.line.is_empty() {
+            self.write_line(self.line.len())?;
+        }
+        self.out.flush()
+    }
+}
+
+// NOTE: Like `tracing` output, stderr is written synchronously, which allows writing
+// pending output on drop
+impl<W: io::Write + Unpin> AsyncWrite for ActorStderr<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.line.extend_from_slice(buf);
+        while let Some(i) = this.line.iter().position(|&b| b == b'\n') {
+            this.write_line(i + 1)?;
+        }
+        if this.line.len() >= MAX_LINE_LENGTH {
+            this.write_line(this.line.len())?;
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.get_mut().out.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // NOTE: Host stderr is shared and must not be closed
+        Poll::Ready(self.get_mut().write_pending())
+    }
+}
+
+impl<W: io::Write> Drop for ActorStderr<W> {
+    fn drop(&mut self) {
+        if let Err(e) = self.write_pending() {
+            warn!("failed to write pending actor stderr output: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::AsyncWriteExt;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            let buf = self.0.lock().expect("failed to lock buffer");
+            String::from_utf8(buf.clone()).expect("output is not valid UTF-8")
+        }
+    }
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0
+                .lock()
+                .expect("failed to lock buffer")
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn partial_lines() -> io::Result<()> {
+        let buf = Buffer::default();
+        let mut stderr = ActorStderr::with_writer("[actor] ".into(), buf.clone());
+        stderr.write_all(b"foo").await?;
+        stderr.flush().await?;
+        assert_eq!(buf.contents(), "");
+        stderr.write_all(b"bar\nba").await?;
+        assert_eq!(buf.contents(), "[actor] foobar\n");
+        stderr.write_all(b"z\n").await?;
+        assert_eq!(buf.contents(), "[actor] foobar\n[actor] baz\n");
+
+        stderr.write_all(&[b'a'; MAX_LINE_LENGTH + 1]).await?;
+        let long = "a".repeat(MAX_LINE_LENGTH + 1);
+        assert_eq!(
+            buf.contents(),
+            format!("[actor] foobar\n[actor] baz\n[actor] {long}\n")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn multiple_lines() -> io::Result<()> {
+        let buf = Buffer::default();
+        let mut stderr = ActorStderr::with_writer("[actor] ".into(), buf.clone());
+        stderr.write_all(b"foo\n\nbar\nbaz\n").await?;
+        assert_eq!(
+            buf.contents(),
+            "[actor] foo\n[actor] \n[actor] bar\n[actor] baz\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pending_output() -> io::Result<()> {
+        let buf = Buffer::default();
+        let mut stderr = ActorStderr::with_writer("[actor] ".into(), buf.clone());
+        stderr.write_all(b"foo\nbar").await?;
+        assert_eq!(buf.contents(), "[actor] foo\n");
+        drop(stderr);
+        assert_eq!(buf.contents(), "[actor] foo\n[actor] bar\n");
+
+        let mut stderr = ActorStderr::with_writer("[actor] ".into(), buf.clone());
+        stderr.write_all(b"baz").await?;
+        stderr.shutdown().await?;
+        assert_eq!(buf.contents(), "[actor] foo\n[actor] bar\n[actor] baz\n");
+        drop(stderr);
+        assert_eq!(buf.contents(), "[actor] foo\n[actor] bar\n[actor] baz\n");
+        Ok(())
+    }
+}
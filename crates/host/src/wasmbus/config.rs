@@ -1,8 +1,13 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::oci::Config as OciConfig;
 
+pub use wasmcloud_runtime::NativeProfiler;
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 /// wasmCloud Host configuration
 pub struct Host {
@@ -22,6 +27,32 @@ pub struct Host {
     pub provider_shutdown_delay: Option<std::time::Duration>,
     /// Configuration for downloading artifacts from OCI registries
     pub oci_opts: OciConfig,
+    /// Whether actor logs should be published on `wasmbus.log.{lattice_prefix}.{actor_id}` NATS subjects
+    pub publish_actor_logs: bool,
+    /// Directory, to which guest profiles of actor invocations are written. If not set, guest profiling is disabled
+    pub profile_dir: Option<PathBuf>,
+    /// Interval at which profiled actor invocations are sampled
+    pub profile_interval: Option<Duration>,
+    /// Whether invocations of all actors are profiled. If `false`, actors are only profiled once enabled via
+    /// the control interface
+    pub profile_all_actors: bool,
+    /// Native profiler integration, which allows external profilers to attribute samples to actor code
+    pub native_profiler: Option<NativeProfiler>,
+    /// Retention of lattice events captured in a JetStream stream. If not set, event history is disabled
+    pub event_history: Option<EventHistory>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+/// Retention limits of the lattice event history stream, which is shared by all hosts in the lattice.
+/// Limits, which are not set, are unbounded if the host creates the stream. A host fails to start if
+/// the limits it sets differ from those of an existing stream
+pub struct EventHistory {
+    /// Maximum age of retained events
+    pub max_age: Option<Duration>,
+    /// Maximum number of retained events
+    pub max_messages: Option<i64>,
+    /// Maximum total size of retained events in bytes
+    pub max_bytes: Option<i64>,
 }
 
 impl Default for Host {
@@ -36,6 +67,12 @@ impl Default for Host {
             cluster_issuers: None,
             provider_shutdown_delay: None,
             oci_opts: OciConfig::default(),
+            publish_actor_logs: false,
+            profile_dir: None,
+            profile_interval: None,
+            profile_all_actors: false,
+            native_profiler: None,
+            event_history: None,
         }
     }
 }
@@ -1,212 +1,903 @@
+//! Typed `com.wasmcloud.lattice.*` events published by the host.
+//!
+//! Every event is published as CloudEvent data carrying a `schema_version` field, see [`Versioned`].
+//! The wire format is compatible with events published by the OTP host.
+
 use core::num::NonZeroUsize;
+use core::time::Duration;
 
 use std::collections::{BTreeMap, HashMap};
 
-use serde_json::json;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 use uuid::Uuid;
 use wascap::jwt;
 
-fn format_actor_claims(claims: &jwt::Claims<jwt::Actor>) -> serde_json::Value {
-    let issuer = &claims.issuer;
-    let not_before_human = "TODO";
-    let expires_human = "TODO";
-    if let Some(actor) = &claims.metadata {
-        json!({
-            "call_alias": actor.call_alias,
-            "caps": actor.caps,
-            "issuer": issuer,
-            "tags": actor.tags,
-            "name": actor.name,
-            "version": actor.ver,
-            "revision": actor.rev,
-            "not_before_human": not_before_human,
-            "expires_human": expires_human,
-        })
+/// Version of the event schema defined in this module
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Lattice event, which is published as a `com.wasmcloud.lattice.{NAME}` CloudEvent
+pub trait Event: Serialize + DeserializeOwned {
+    /// Name of the event
+    const NAME: &'static str;
+
+    /// CloudEvent type of the event
+    #[must_use]
+    fn ty() -> String {
+        format!("com.wasmcloud.lattice.{}", Self::NAME)
+    }
+}
+
+/// Event data along with the version of the schema it conforms to
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Versioned<T> {
+    /// Event schema version, see [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// Event data
+    #[serde(flatten)]
+    pub data: T,
+}
+
+impl<T> From<T> for Versioned<T> {
+    fn from(data: T) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            data,
+        }
+    }
+}
+
+/// Formats optional Unix timestamp `secs` in a human-readable form, using `none` if not set
+fn format_timestamp(secs: Option<u64>, none: &str) -> String {
+    secs.and_then(|secs| i64::try_from(secs).ok())
+        .and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+        .and_then(|t| t.format(&Rfc3339).ok())
+        .unwrap_or_else(|| none.into())
+}
+
+/// Formats `duration` in a human-readable form, e.g. `1 day, 2 hours, 3 minutes, 4 seconds`
+#[must_use]
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let parts: Vec<_> = [
+        (secs / 86400, "day"),
+        (secs / 3600 % 24, "hour"),
+        (secs / 60 % 60, "minute"),
+        (secs % 60, "second"),
+    ]
+    .into_iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, unit)| {
+        if n == 1 {
+            format!("{n} {unit}")
+        } else {
+            format!("{n} {unit}s")
+        }
+    })
+    .collect();
+    if parts.is_empty() {
+        "0 seconds".into()
     } else {
-        json!({
-            "issuer": issuer,
-            "not_before_human": not_before_human,
-            "expires_human": expires_human,
-        })
+        parts.join(", ")
     }
 }
 
-pub fn actor_started(
+/// Summary of actor claims included in actor events
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ActorClaims {
+    /// Actor call alias
+    pub call_alias: Option<String>,
+    /// Capabilities the actor is authorized to use
+    pub caps: Option<Vec<String>>,
+    /// Public key of the claims issuer
+    pub issuer: String,
+    /// Actor tags
+    pub tags: Option<Vec<String>>,
+    /// Actor name
+    pub name: Option<String>,
+    /// Human-readable actor version
+    pub version: Option<String>,
+    /// Actor revision
+    pub revision: Option<i32>,
+    /// Time the claims are valid from, `immediately` if not restricted
+    pub not_before_human: String,
+    /// Time the claims expire at, `never` if not restricted
+    pub expires_human: String,
+}
+
+impl From<&jwt::Claims<jwt::Actor>> for ActorClaims {
+    fn from(claims: &jwt::Claims<jwt::Actor>) -> Self {
+        let mut summary = Self {
+            issuer: claims.issuer.clone(),
+            not_before_human: format_timestamp(claims.not_before, "immediately"),
+            expires_human: format_timestamp(claims.expires, "never"),
+            ..Self::default()
+        };
+        if let Some(actor) = &claims.metadata {
+            summary.call_alias = actor.call_alias.clone();
+            summary.caps = actor.caps.clone();
+            summary.tags = actor.tags.clone();
+            summary.name = actor.name.clone();
+            summary.version = actor.ver.clone();
+            summary.revision = actor.rev;
+        }
+        summary
+    }
+}
+
+/// Summary of capability provider claims included in provider events
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ProviderClaims {
+    /// Public key of the claims issuer
+    pub issuer: String,
+    /// Provider tags, present in OTP, but always `null`
+    pub tags: Option<Vec<String>>,
+    /// Provider name
+    pub name: Option<String>,
+    /// Human-readable provider version
+    pub version: Option<String>,
+    /// Time the claims are valid from, `immediately` if not restricted
+    pub not_before_human: String,
+    /// Time the claims expire at, `never` if not restricted
+    pub expires_human: String,
+}
+
+impl From<&jwt::Claims<jwt::CapabilityProvider>> for ProviderClaims {
+    fn from(claims: &jwt::Claims<jwt::CapabilityProvider>) -> Self {
+        let metadata = claims.metadata.as_ref();
+        Self {
+            issuer: claims.issuer.clone(),
+            tags: None,
+            name: metadata.and_then(|jwt::CapabilityProvider { name, .. }| name.clone()),
+            version: metadata.and_then(|jwt::CapabilityProvider { ver, .. }| ver.clone()),
+            not_before_human: format_timestamp(claims.not_before, "immediately"),
+            expires_human: format_timestamp(claims.expires, "never"),
+        }
+    }
+}
+
+/// Host started
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HostStarted {
+    /// Human-friendly name of the host
+    pub friendly_name: String,
+    /// Host labels
+    pub labels: HashMap<String, String>,
+    /// Host uptime in seconds
+    pub uptime_seconds: u64,
+    /// Host version
+    pub version: String,
+}
+
+/// Provider instance description included in [`HostHeartbeat`]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProviderDescription {
+    /// Provider public key
+    pub public_key: String,
+    /// Link name the provider is running under
+    pub link_name: String,
+    /// Provider contract ID, `n/a` if unknown
+    pub contract_id: String,
+}
+
+/// Periodic host heartbeat
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HostHeartbeat {
+    /// Number of running instances keyed by actor public key
+    pub actors: HashMap<String, usize>,
+    /// Human-friendly name of the host
+    pub friendly_name: String,
+    /// Host labels
+    pub labels: HashMap<String, String>,
+    /// Running providers
+    pub providers: Vec<ProviderDescription>,
+    /// Human-readable host uptime
+    pub uptime_human: String,
+    /// Host uptime in seconds
+    pub uptime_seconds: u64,
+    /// Host version
+    pub version: String,
+}
+
+/// Host stopped
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HostStopped {
+    /// Host labels
+    pub labels: HashMap<String, String>,
+}
+
+/// Single actor instance started
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActorStarted {
+    /// Actor public key
+    pub public_key: String,
+    /// Actor image reference
+    pub image_ref: String,
+    /// API version, always `n/a`
+    pub api_version: String,
+    /// Actor instance ID
+    pub instance_id: Uuid,
+    /// Actor annotations
+    pub annotations: Option<BTreeMap<String, String>>,
+    /// Actor claims
+    pub claims: ActorClaims,
+}
+
+/// Actor failed to start
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActorStartFailed {
+    /// Actor reference the start was attempted from
+    pub actor_ref: String,
+    /// Error message
+    pub error: String,
+}
+
+/// Single actor instance stopped
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActorStopped {
+    /// Actor public key
+    pub public_key: String,
+    /// Actor instance ID
+    pub instance_id: Uuid,
+    /// Actor annotations
+    pub annotations: Option<BTreeMap<String, String>>,
+}
+
+/// Actor instances started
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActorsStarted {
+    /// Actor public key
+    pub public_key: String,
+    /// Actor image reference
+    pub image_ref: String,
+    /// Actor annotations
+    pub annotations: Option<BTreeMap<String, String>>,
+    /// ID of the host the actor was started on
+    pub host_id: String,
+    /// Actor claims
+    pub claims: jwt::Claims<jwt::Actor>,
+    /// Number of instances started
+    pub count: usize,
+}
+
+/// Actor instances failed to start
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActorsStartFailed {
+    /// Actor public key
+    pub public_key: String,
+    /// Actor image reference
+    pub image_ref: String,
+    /// Actor annotations
+    pub annotations: Option<BTreeMap<String, String>>,
+    /// ID of the host the actor failed to start on
+    pub host_id: String,
+    /// Error message
+    pub error: String,
+}
+
+/// Actor instances stopped
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActorsStopped {
+    /// ID of the host the actor was stopped on
+    pub host_id: String,
+    /// Actor public key
+    pub public_key: String,
+    /// Number of instances stopped
+    pub count: NonZeroUsize,
+    /// Number of instances remaining
+    pub remaining: usize,
+    /// Actor annotations
+    pub annotations: Option<BTreeMap<String, String>>,
+}
+
+/// Link definition, which is the data of [`LinkdefSet`] and [`LinkdefDeleted`]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Linkdef {
+    /// Link definition ID
+    pub id: String,
+    /// Actor public key
+    pub actor_id: String,
+    /// Provider public key
+    pub provider_id: String,
+    /// Link name
+    pub link_name: String,
+    /// Contract ID
+    pub contract_id: String,
+    /// Link values
+    pub values: HashMap<String, String>,
+}
+
+/// Link definition set
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct LinkdefSet(pub Linkdef);
+
+/// Link definition deleted
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct LinkdefDeleted(pub Linkdef);
+
+/// Provider started
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProviderStarted {
+    /// ID of the host the provider was started on
+    pub host_id: String,
+    /// Provider public key
+    pub public_key: String,
+    /// Provider image reference
+    pub image_ref: String,
+    /// Link name the provider is running under
+    pub link_name: String,
+    /// Provider contract ID
+    pub contract_id: Option<String>,
+    /// Provider instance ID
+    pub instance_id: Uuid,
+    /// Provider annotations
+    pub annotations: Option<BTreeMap<String, String>>,
+    /// Provider claims
+    pub claims: ProviderClaims,
+}
+
+/// Provider failed to start
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProviderStartFailed {
+    /// Provider reference the start was attempted from
+    pub provider_ref: String,
+    /// Link name the provider was to run under
+    pub link_name: String,
+    /// Error message
+    pub error: String,
+}
+
+/// Provider stopped
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProviderStopped {
+    /// ID of the host the provider was stopped on
+    pub host_id: String,
+    /// Provider public key
+    pub public_key: String,
+    /// Link name the provider was running under
+    pub link_name: String,
+    /// Provider contract ID
+    pub contract_id: Option<String>,
+    /// Provider instance ID
+    pub instance_id: Uuid,
+    /// Provider annotations
+    pub annotations: Option<BTreeMap<String, String>>,
+    /// Reason the provider was stopped
+    pub reason: String,
+}
+
+impl Event for HostStarted {
+    const NAME: &'static str = "host_started";
+}
+
+impl Event for HostHeartbeat {
+    const NAME: &'static str = "host_heartbeat";
+}
+
+impl Event for HostStopped {
+    const NAME: &'static str = "host_stopped";
+}
+
+impl Event for ActorStarted {
+    const NAME: &'static str = "actor_started";
+}
+
+impl Event for ActorStartFailed {
+    const NAME: &'static str = "actor_start_failed";
+}
+
+impl Event for ActorStopped {
+    const NAME: &'static str = "actor_stopped";
+}
+
+impl Event for ActorsStarted {
+    const NAME: &'static str = "actors_started";
+}
+
+impl Event for ActorsStartFailed {
+    const NAME: &'static str = "actors_start_failed";
+}
+
+impl Event for ActorsStopped {
+    const NAME: &'static str = "actors_stopped";
+}
+
+impl Event for LinkdefSet {
+    const NAME: &'static str = "linkdef_set";
+}
+
+impl Event for LinkdefDeleted {
+    const NAME: &'static str = "linkdef_deleted";
+}
+
+impl Event for ProviderStarted {
+    const NAME: &'static str = "provider_started";
+}
+
+impl Event for ProviderStartFailed {
+    const NAME: &'static str = "provider_start_failed";
+}
+
+impl Event for ProviderStopped {
+    const NAME: &'static str = "provider_stopped";
+}
+
+/// Maximum number of events returned in a single [`HistoryRequest`] response
+pub const MAX_HISTORY_BATCH: usize = 1000;
+
+/// Control interface request for events retained in the lattice event history, which is handled on
+/// `wasmbus.ctl.{prefix}.get.events` by a single host with event history enabled. At most one of
+/// `since_sequence` and `since_time` may be set, if neither is, events are returned from the start
+/// of the history.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct HistoryRequest {
+    /// Stream sequence number of the first event to return. Sequence numbers start at 1, so 0 returns
+    /// events from the start of the history
+    #[serde(default)]
+    pub since_sequence: Option<u64>,
+    /// RFC 3339 timestamp of the earliest event to return
+    #[serde(default)]
+    pub since_time: Option<String>,
+    /// Maximum number of events to return, capped at and defaulting to [`MAX_HISTORY_BATCH`]
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Event retained in the lattice event history
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HistoricalEvent {
+    /// Stream sequence number of the event
+    pub sequence: u64,
+    /// CloudEvent as published by the host
+    pub event: serde_json::Value,
+}
+
+/// Response to a [`HistoryRequest`]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HistoryResponse {
+    /// Events in order of publication
+    pub events: Vec<HistoricalEvent>,
+    /// Sequence number of the last event in the history at the time of the request.
+    /// Observers have caught up once they received the event with this sequence number
+    pub last_sequence: u64,
+}
+
+pub(crate) fn actor_started(
     claims: &jwt::Claims<jwt::Actor>,
     annotations: &Option<BTreeMap<String, String>>,
     instance_id: Uuid,
     image_ref: impl AsRef<str>,
-) -> serde_json::Value {
-    json!({
-        "public_key": claims.subject,
-        "image_ref": image_ref.as_ref(),
-        "api_version": "n/a",
-        "instance_id": instance_id,
-        "annotations": annotations,
-        "claims": format_actor_claims(claims),
-    })
+) -> ActorStarted {
+    ActorStarted {
+        public_key: claims.subject.clone(),
+        image_ref: image_ref.as_ref().into(),
+        api_version: "n/a".into(),
+        instance_id,
+        annotations: annotations.clone(),
+        claims: claims.into(),
+    }
 }
 
-pub fn actor_start_failed(actor_ref: impl AsRef<str>, error: &anyhow::Error) -> serde_json::Value {
-    json!({
-        "actor_ref": actor_ref.as_ref(),
-        "error": format!("{error:#}"),
-    })
+pub(crate) fn actor_start_failed(
+    actor_ref: impl AsRef<str>,
+    error: &anyhow::Error,
+) -> ActorStartFailed {
+    ActorStartFailed {
+        actor_ref: actor_ref.as_ref().into(),
+        error: format!("{error:#}"),
+    }
 }
 
-pub fn actor_stopped(
+pub(crate) fn actor_stopped(
     claims: &jwt::Claims<jwt::Actor>,
     annotations: &Option<BTreeMap<String, String>>,
     instance_id: Uuid,
-) -> serde_json::Value {
-    json!({
-        "public_key": claims.subject,
-        "instance_id": instance_id,
-        "annotations": annotations,
-    })
+) -> ActorStopped {
+    ActorStopped {
+        public_key: claims.subject.clone(),
+        instance_id,
+        annotations: annotations.clone(),
+    }
 }
 
-pub fn actors_started(
+pub(crate) fn actors_started(
     claims: &jwt::Claims<jwt::Actor>,
     annotations: &Option<BTreeMap<String, String>>,
     host_id: impl AsRef<str>,
     count: impl Into<usize>,
     image_ref: impl AsRef<str>,
-) -> serde_json::Value {
-    json!({
-        "public_key": claims.subject,
-        "image_ref": image_ref.as_ref(),
-        "annotations": annotations,
-        "host_id": host_id.as_ref(),
-        "claims": claims,
-        "count": count.into(),
-    })
+) -> ActorsStarted {
+    ActorsStarted {
+        public_key: claims.subject.clone(),
+        image_ref: image_ref.as_ref().into(),
+        annotations: annotations.clone(),
+        host_id: host_id.as_ref().into(),
+        claims: claims.clone(),
+        count: count.into(),
+    }
 }
 
-pub fn actors_start_failed(
+pub(crate) fn actors_start_failed(
     claims: &jwt::Claims<jwt::Actor>,
     annotations: &Option<BTreeMap<String, String>>,
     host_id: impl AsRef<str>,
     image_ref: impl AsRef<str>,
     error: &anyhow::Error,
-) -> serde_json::Value {
-    json!({
-        "public_key": claims.subject,
-        "image_ref": image_ref.as_ref(),
-        "annotations": annotations,
-        "host_id": host_id.as_ref(),
-        "error": format!("{error:#}"),
-    })
+) -> ActorsStartFailed {
+    ActorsStartFailed {
+        public_key: claims.subject.clone(),
+        image_ref: image_ref.as_ref().into(),
+        annotations: annotations.clone(),
+        host_id: host_id.as_ref().into(),
+        error: format!("{error:#}"),
+    }
 }
 
-pub fn actors_stopped(
+pub(crate) fn actors_stopped(
     claims: &jwt::Claims<jwt::Actor>,
     annotations: &Option<BTreeMap<String, String>>,
     host_id: impl AsRef<str>,
     count: NonZeroUsize,
     remaining: usize,
-) -> serde_json::Value {
-    json!({
-        "host_id": host_id.as_ref(),
-        "public_key": claims.subject,
-        "count": count,
-        "remaining": remaining,
-        "annotations": annotations,
-    })
+) -> ActorsStopped {
+    ActorsStopped {
+        host_id: host_id.as_ref().into(),
+        public_key: claims.subject.clone(),
+        count,
+        remaining,
+        annotations: annotations.clone(),
+    }
 }
 
-pub fn linkdef_set(
+fn linkdef(
     id: impl AsRef<str>,
     actor_id: impl AsRef<str>,
     provider_id: impl AsRef<str>,
     link_name: impl AsRef<str>,
     contract_id: impl AsRef<str>,
     values: &HashMap<String, String>,
-) -> serde_json::Value {
-    json!({
-        "id": id.as_ref(),
-        "actor_id": actor_id.as_ref(),
-        "provider_id": provider_id.as_ref(),
-        "link_name": link_name.as_ref(),
-        "contract_id": contract_id.as_ref(),
-        "values": values,
-    })
+) -> Linkdef {
+    Linkdef {
+        id: id.as_ref().into(),
+        actor_id: actor_id.as_ref().into(),
+        provider_id: provider_id.as_ref().into(),
+        link_name: link_name.as_ref().into(),
+        contract_id: contract_id.as_ref().into(),
+        values: values.clone(),
+    }
 }
 
-pub fn linkdef_deleted(
+pub(crate) fn linkdef_set(
     id: impl AsRef<str>,
     actor_id: impl AsRef<str>,
     provider_id: impl AsRef<str>,
     link_name: impl AsRef<str>,
     contract_id: impl AsRef<str>,
     values: &HashMap<String, String>,
-) -> serde_json::Value {
-    json!({
-        "id": id.as_ref(),
-        "actor_id": actor_id.as_ref(),
-        "provider_id": provider_id.as_ref(),
-        "link_name": link_name.as_ref(),
-        "contract_id": contract_id.as_ref(),
-        "values": values,
-    })
+) -> LinkdefSet {
+    LinkdefSet(linkdef(
+        id,
+        actor_id,
+        provider_id,
+        link_name,
+        contract_id,
+        values,
+    ))
+}
+
+pub(crate) fn linkdef_deleted(
+    id: impl AsRef<str>,
+    actor_id: impl AsRef<str>,
+    provider_id: impl AsRef<str>,
+    link_name: impl AsRef<str>,
+    contract_id: impl AsRef<str>,
+    values: &HashMap<String, String>,
+) -> LinkdefDeleted {
+    LinkdefDeleted(linkdef(
+        id,
+        actor_id,
+        provider_id,
+        link_name,
+        contract_id,
+        values,
+    ))
 }
 
-pub fn provider_started(
+pub(crate) fn provider_started(
     claims: &jwt::Claims<jwt::CapabilityProvider>,
     annotations: &Option<BTreeMap<String, String>>,
     instance_id: Uuid,
     host_id: impl AsRef<str>,
     image_ref: impl AsRef<str>,
     link_name: impl AsRef<str>,
-) -> serde_json::Value {
+) -> ProviderStarted {
     let metadata = claims.metadata.as_ref();
-    json!({
-        "host_id": host_id.as_ref(),
-        "public_key": claims.subject,
-        "image_ref": image_ref.as_ref(),
-        "link_name": link_name.as_ref(),
-        "contract_id": metadata.map(|jwt::CapabilityProvider { capid, .. }| capid),
-        "instance_id": instance_id,
-        "annotations": annotations,
-        "claims": {
-            "issuer": &claims.issuer,
-            "tags": None::<Vec<()>>, // present in OTP, but hardcoded to `None`
-            "name": metadata.map(|jwt::CapabilityProvider { name, .. }| name),
-            "version": metadata.map(|jwt::CapabilityProvider { ver, .. }| ver),
-            "not_before_human": "TODO",
-            "expires_human": "TODO",
-        },
-    })
+    ProviderStarted {
+        host_id: host_id.as_ref().into(),
+        public_key: claims.subject.clone(),
+        image_ref: image_ref.as_ref().into(),
+        link_name: link_name.as_ref().into(),
+        contract_id: metadata.map(|jwt::CapabilityProvider { capid, .. }| capid.clone()),
+        instance_id,
+        annotations: annotations.clone(),
+        claims: claims.into(),
+    }
 }
 
-pub fn provider_start_failed(
+pub(crate) fn provider_start_failed(
     provider_ref: impl AsRef<str>,
     link_name: impl AsRef<str>,
     error: &anyhow::Error,
-) -> serde_json::Value {
-    json!({
-        "provider_ref": provider_ref.as_ref(),
-        "link_name": link_name.as_ref(),
-        "error": format!("{error:#}"),
-    })
+) -> ProviderStartFailed {
+    ProviderStartFailed {
+        provider_ref: provider_ref.as_ref().into(),
+        link_name: link_name.as_ref().into(),
+        error: format!("{error:#}"),
+    }
 }
 
-pub fn provider_stopped(
+pub(crate) fn provider_stopped(
     claims: &jwt::Claims<jwt::CapabilityProvider>,
     annotations: &Option<BTreeMap<String, String>>,
     instance_id: Uuid,
     host_id: impl AsRef<str>,
     link_name: impl AsRef<str>,
     reason: impl AsRef<str>,
-) -> serde_json::Value {
+) -> ProviderStopped {
     let metadata = claims.metadata.as_ref();
-    json!({
-        "host_id": host_id.as_ref(),
-        "public_key": claims.subject,
-        "link_name": link_name.as_ref(),
-        "contract_id": metadata.map(|jwt::CapabilityProvider { capid, .. }| capid),
-        "instance_id": instance_id,
-        "annotations": annotations,
-        "reason": reason.as_ref(),
-    })
+    ProviderStopped {
+        host_id: host_id.as_ref().into(),
+        public_key: claims.subject.clone(),
+        link_name: link_name.as_ref().into(),
+        contract_id: metadata.map(|jwt::CapabilityProvider { capid, .. }| capid.clone()),
+        instance_id,
+        annotations: annotations.clone(),
+        reason: reason.as_ref().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::anyhow;
+    use serde_json::json;
+
+    const HOST_ID: &str = "NACZRF2XTDCXWQ6CT5TEOYUQGA3OINW3S6ICOI7SW7VDKD5YVRGZ4Q7C";
+    const ACTOR_ID: &str = "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5";
+    const ACTOR_REF: &str = "wasmcloud.azurecr.io/echo:0.3.8";
+    const PROVIDER_ID: &str = "VAG3QITQQ2ODAOWB5TTQSDJ53XK3SHBEIFNK4AYJ5RKAX2UNSCAPHA5M";
+    const PROVIDER_REF: &str = "wasmcloud.azurecr.io/httpserver:0.17.0";
+    const INSTANCE_ID: &str = "2a2f9cf6-4a1f-4a3c-9c1e-8f3c2a7d5b10";
+    const LINKDEF_ID: &str = "2e6b6b51-6a47-4b5e-9a3a-0c1c0c7f3e5d";
+
+    /// Asserts that `event` is encoded as `fixture`, which decodes back into a versioned `T`
+    fn assert_wire_format<T: Event>(event: T, fixture: &str) {
+        let expected: serde_json::Value =
+            serde_json::from_str(fixture).expect("failed to parse fixture");
+        let got = serde_json::to_value(Versioned::from(event)).expect("failed to encode event");
+        assert_eq!(got, expected, "`{}` wire format changed", T::NAME);
+        let event: Versioned<T> = serde_json::from_value(expected).expect("failed to decode event");
+        assert_eq!(event.schema_version, SCHEMA_VERSION);
+    }
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_str!(concat!("../../tests/fixtures/events/", $name, ".json"))
+        };
+    }
+
+    fn echo_claims() -> jwt::Claims<jwt::Actor> {
+        serde_json::from_value(json!({
+            "exp": 1_893_456_000,
+            "jti": "GTvdPYzhyhvzu5sNDfa8cP",
+            "iat": 1_652_386_567,
+            "iss": "ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW",
+            "sub": ACTOR_ID,
+            "nbf": 1_652_386_567,
+            "wascap": {
+                "name": "Echo",
+                "hash": "4D1E0CB0F1CD7F9F8F12E5B3E2C2DC7D1C4F1A0DB8E5E9C1A2D48E31A6B27C63",
+                "tags": [],
+                "caps": ["wasmcloud:httpserver"],
+                "rev": 4,
+                "ver": "0.3.8",
+                "call_alias": "echo",
+                "prov": false,
+            },
+            "wascap_revision": 3,
+        }))
+        .expect("failed to decode actor claims")
+    }
+
+    fn httpserver_claims() -> jwt::Claims<jwt::CapabilityProvider> {
+        serde_json::from_value(json!({
+            "jti": "ZxTQhCQYIQ7Lh6nJ5NkB4V",
+            "iat": 1_652_386_567,
+            "iss": "ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW",
+            "sub": PROVIDER_ID,
+            "nbf": 1_652_386_567,
+            "wascap": {
+                "name": "HTTP Server",
+                "capid": "wasmcloud:httpserver",
+                "vendor": "wasmCloud",
+                "ver": "0.17.0",
+                "target_hashes": {},
+            },
+            "wascap_revision": 3,
+        }))
+        .expect("failed to decode provider claims")
+    }
+
+    #[test]
+    fn wire_format() {
+        let labels = HashMap::from([
+            ("hostcore.arch".to_string(), "x86_64".to_string()),
+            ("hostcore.os".to_string(), "linux".to_string()),
+            ("hostcore.osfamily".to_string(), "unix".to_string()),
+        ]);
+        let annotations = Some(BTreeMap::from([(
+            "wasmcloud.dev/appspec".to_string(),
+            "echo".to_string(),
+        )]));
+        let instance_id = Uuid::parse_str(INSTANCE_ID).expect("invalid instance ID");
+        let values = HashMap::from([("address".to_string(), "0.0.0.0:8080".to_string())]);
+        let actor_claims = echo_claims();
+        let provider_claims = httpserver_claims();
+
+        assert_wire_format(
+            HostStarted {
+                friendly_name: "wispy-field-4215".into(),
+                labels: labels.clone(),
+                uptime_seconds: 0,
+                version: "0.1.0".into(),
+            },
+            fixture!("host_started"),
+        );
+        assert_wire_format(
+            HostHeartbeat {
+                actors: HashMap::from([(ACTOR_ID.to_string(), 2)]),
+                friendly_name: "wispy-field-4215".into(),
+                labels: labels.clone(),
+                providers: vec![ProviderDescription {
+                    public_key: PROVIDER_ID.into(),
+                    link_name: "default".into(),
+                    contract_id: "wasmcloud:httpserver".into(),
+                }],
+                uptime_human: format_duration(Duration::from_secs(3725)),
+                uptime_seconds: 3725,
+                version: "0.1.0".into(),
+            },
+            fixture!("host_heartbeat"),
+        );
+        assert_wire_format(HostStopped { labels }, fixture!("host_stopped"));
+        assert_wire_format(
+            actor_started(&actor_claims, &annotations, instance_id, ACTOR_REF),
+            fixture!("actor_started"),
+        );
+        assert_wire_format(
+            actor_start_failed(
+                ACTOR_REF,
+                &anyhow!("not found").context("failed to fetch actor"),
+            ),
+            fixture!("actor_start_failed"),
+        );
+        assert_wire_format(
+            actor_stopped(&actor_claims, &None, instance_id),
+            fixture!("actor_stopped"),
+        );
+        assert_wire_format(
+            actors_started(&actor_claims, &annotations, HOST_ID, 2usize, ACTOR_REF),
+            fixture!("actors_started"),
+        );
+        assert_wire_format(
+            actors_start_failed(
+                &actor_claims,
+                &None,
+                HOST_ID,
+                ACTOR_REF,
+                &anyhow!("out of memory").context("failed to instantiate actor"),
+            ),
+            fixture!("actors_start_failed"),
+        );
+        assert_wire_format(
+            actors_stopped(
+                &actor_claims,
+                &annotations,
+                HOST_ID,
+                NonZeroUsize::new(1).expect("zero count"),
+                1,
+            ),
+            fixture!("actors_stopped"),
+        );
+        assert_wire_format(
+            linkdef_set(
+                LINKDEF_ID,
+                ACTOR_ID,
+                PROVIDER_ID,
+                "default",
+                "wasmcloud:httpserver",
+                &values,
+            ),
+            fixture!("linkdef_set"),
+        );
+        assert_wire_format(
+            linkdef_deleted(
+                LINKDEF_ID,
+                ACTOR_ID,
+                PROVIDER_ID,
+                "default",
+                "wasmcloud:httpserver",
+                &values,
+            ),
+            fixture!("linkdef_deleted"),
+        );
+        assert_wire_format(
+            provider_started(
+                &provider_claims,
+                &None,
+                instance_id,
+                HOST_ID,
+                PROVIDER_REF,
+                "default",
+            ),
+            fixture!("provider_started"),
+        );
+        assert_wire_format(
+            provider_start_failed(
+                PROVIDER_REF,
+                "default",
+                &anyhow!("not found").context("failed to fetch provider"),
+            ),
+            fixture!("provider_start_failed"),
+        );
+        assert_wire_format(
+            provider_stopped(
+                &provider_claims,
+                &None,
+                instance_id,
+                HOST_ID,
+                "default",
+                "normal",
+            ),
+            fixture!("provider_stopped"),
+        );
+    }
+
+    #[test]
+    fn actor_claims() {
+        let claims: jwt::Claims<jwt::Actor> = serde_json::from_value(serde_json::json!({
+            "jti": "id",
+            "iat": 0,
+            "iss": "issuer",
+            "sub": "subject",
+            "exp": 1_700_000_000,
+            "wascap": {
+                "name": "echo",
+                "hash": "",
+                "prov": false,
+            },
+        }))
+        .expect("failed to decode claims");
+        let summary = ActorClaims::from(&claims);
+        assert_eq!(summary.issuer, "issuer");
+        assert_eq!(summary.name.as_deref(), Some("echo"));
+        assert_eq!(summary.not_before_human, "immediately");
+        assert_eq!(summary.expires_human, "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::ZERO), "0 seconds");
+        assert_eq!(format_duration(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1 hour");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 86400 + 3600 + 2 * 60 + 5)),
+            "2 days, 1 hour, 2 minutes, 5 seconds"
+        );
+    }
 }
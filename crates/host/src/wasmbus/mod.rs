@@ -3,8 +3,9 @@ pub mod config;
 
 pub use config::Host as HostConfig;
 
-mod event;
+pub mod event;
 
+use crate::logging::ActorLogger;
 use crate::{fetch_actor, socket_pair};
 
 use core::fmt;
@@ -36,7 +37,7 @@ use serde_json::json;
 use sha2::{Digest, Sha256};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
-use tokio::io::{stderr, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::{watch, RwLock};
 use tokio::task::JoinHandle;
 use tokio::time::{interval_at, Instant};
@@ -52,8 +53,16 @@ use wasmcloud_control_interface::{
     StartActorCommand, StartProviderCommand, StopActorCommand, StopHostCommand,
     StopProviderCommand, UpdateActorCommand,
 };
-use wasmcloud_runtime::capability::{messaging, Bus, KeyValueReadWrite, Messaging};
-use wasmcloud_runtime::{ActorInstancePool, Runtime};
+use wasmcloud_runtime::capability::compat::{
+    self, BLOBSTORE_RECEIVE_CHUNK, HTTP_CLIENT_REQUEST, HTTP_SERVER_HANDLE_REQUEST,
+    MESSAGING_HANDLE_MESSAGE,
+};
+use wasmcloud_runtime::capability::http::types::RequestOptions;
+use wasmcloud_runtime::capability::{
+    blobstore, messaging, Blobstore, BlobstoreHandler, Bus, KeyValueAtomic, KeyValueBatch,
+    KeyValueReadWrite, Messaging, MessagingHandler, OutgoingHttp,
+};
+use wasmcloud_runtime::{ActorInstancePool, PooledActorInstance, Profiling, Runtime};
 
 const SUCCESS: &str = r#"{"accepted":true,"error":""}"#;
 
@@ -66,6 +75,7 @@ struct Queue {
     links: async_nats::Subscriber,
     queries: async_nats::Subscriber,
     registries: async_nats::Subscriber,
+    events: Option<async_nats::Subscriber>,
 }
 
 impl Stream for Queue {
@@ -83,10 +93,25 @@ impl Stream for Queue {
             Poll::Ready(None) => {}
             Poll::Pending => pending = true,
         }
-        match Pin::new(&mut self.queries).poll_next(cx) {
-            Poll::Ready(Some(msg)) => return Poll::Ready(Some(msg)),
-            Poll::Ready(None) => {}
-            Poll::Pending => pending = true,
+        loop {
+            match Pin::new(&mut self.queries).poll_next(cx) {
+                // Event history requests are received via the `events` queue group, so that only a
+                // single host responds
+                Poll::Ready(Some(msg)) if msg.subject.ends_with(".get.events") => {}
+                Poll::Ready(Some(msg)) => return Poll::Ready(Some(msg)),
+                Poll::Ready(None) => break,
+                Poll::Pending => {
+                    pending = true;
+                    break;
+                }
+            }
+        }
+        if let Some(events) = self.events.as_mut() {
+            match Pin::new(events).poll_next(cx) {
+                Poll::Ready(Some(msg)) => return Poll::Ready(Some(msg)),
+                Poll::Ready(None) => {}
+                Poll::Pending => pending = true,
+            }
         }
         match Pin::new(&mut self.registries).poll_next(cx) {
             Poll::Ready(Some(msg)) => return Poll::Ready(Some(msg)),
@@ -164,6 +189,7 @@ impl Queue {
         lattice_prefix: &str,
         cluster_key: &KeyPair,
         host_key: &KeyPair,
+        event_history: bool,
     ) -> anyhow::Result<Self> {
         let host_id = host_key.public_key();
         let (registries, pings, links, queries, auction, commands, inventory) = try_join!(
@@ -176,7 +202,18 @@ impl Queue {
             nats.subscribe(format!("wasmbus.ctl.{lattice_prefix}.get.{host_id}.inv",)),
         )
         .context("failed to subscribe to queues")?;
+        let events = if event_history {
+            let subject = format!("wasmbus.ctl.{lattice_prefix}.get.events");
+            let events = nats
+                .queue_subscribe(subject.clone(), subject)
+                .await
+                .context("failed to subscribe to event history queue")?;
+            Some(events)
+        } else {
+            None
+        };
         Ok(Self {
+            events,
             auction,
             commands,
             pings,
@@ -196,6 +233,7 @@ struct ActorInstance {
     calls: AbortHandle,
     runtime: Runtime,
     handler: Handler,
+    logger: ActorLogger,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -295,6 +333,13 @@ impl Invocation {
     }
 }
 
+/// Control interface command enabling or disabling guest profiling of an actor
+#[derive(Debug, Deserialize, Serialize)]
+struct ProfileActorCommand {
+    actor_id: String,
+    enable: bool,
+}
+
 #[derive(Default, Deserialize, Serialize)]
 struct InvocationResponse {
     #[serde(with = "serde_bytes")]
@@ -306,18 +351,39 @@ struct InvocationResponse {
     content_length: Option<u64>,
 }
 
+/// Link targets keyed by contract ID and link name
+type LinkTargets = HashMap<String, BTreeMap<String, WasmCloudEntity>>;
+
 #[derive(Clone, Debug)]
 struct Handler {
     nats: async_nats::Client,
     lattice_prefix: String,
     cluster_key: Arc<KeyPair>,
     origin: WasmCloudEntity,
-    interfaces: Arc<RwLock<HashMap<String, WasmCloudEntity>>>,
+    interfaces: Arc<RwLock<LinkTargets>>,
 }
 
 impl Handler {
+    /// Returns the target of a link for `contract_id`. If `link_name` is not specified,
+    /// `default` link is used if it exists and the first link in lexicographical order otherwise.
+    async fn link_target(
+        &self,
+        contract_id: &str,
+        link_name: Option<&str>,
+    ) -> anyhow::Result<WasmCloudEntity> {
+        let interfaces = self.interfaces.read().await;
+        let links = interfaces.get(contract_id).context("link not found")?;
+        match link_name {
+            Some(link_name) => links.get(link_name),
+            None => links.get("default").or_else(|| links.values().next()),
+        }
+        .cloned()
+        .context("link not found")
+    }
+
     async fn invocation(
         &self,
+        link_name: Option<&str>,
         operation: impl AsRef<str>,
         request: Vec<u8>,
     ) -> anyhow::Result<Invocation> {
@@ -325,13 +391,11 @@ impl Handler {
             .as_ref()
             .split_once('/')
             .context("failed to parse operation")?;
-        let interfaces = self.interfaces.read().await;
-        let target = interfaces.get(package).context("link not found")?;
-        // TODO: Support per-interface links
+        let target = self.link_target(package, link_name).await?;
         Invocation::new(
             &self.cluster_key,
             self.origin.clone(),
-            target.clone(),
+            target,
             interface_method.into(),
             request,
         )
@@ -340,11 +404,12 @@ impl Handler {
     #[instrument(skip(operation, request))]
     async fn call_provider_with_payload(
         &self,
+        link_name: Option<&str>,
         operation: impl AsRef<str>,
         request: Vec<u8>,
     ) -> anyhow::Result<Result<Vec<u8>, String>> {
         let operation = operation.as_ref();
-        let invocation = self.invocation(operation, request).await?;
+        let invocation = self.invocation(link_name, operation, request).await?;
         let request =
             rmp_serde::to_vec_named(&invocation).context("failed to encode invocation")?;
         let lattice_prefix = &self.lattice_prefix;
@@ -382,14 +447,144 @@ impl Handler {
         &self,
         operation: impl AsRef<str>,
         request: &impl Serialize,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.call_provider_on_link(None, operation, request).await
+    }
+
+    #[instrument(skip(operation, request))]
+    async fn call_provider_on_link(
+        &self,
+        link_name: Option<&str>,
+        operation: impl AsRef<str>,
+        request: &impl Serialize,
     ) -> anyhow::Result<Vec<u8>> {
         let operation = operation.as_ref();
         let request = rmp_serde::to_vec_named(request).context("failed to encode request")?;
-        self.call_provider_with_payload(operation, request)
+        self.call_provider_with_payload(link_name, operation, request)
             .await
             .context("failed to call linked provider")?
             .map_err(|err| anyhow!(err).context(format!("`{operation}` call failed")))
     }
+
+    /// Fetches the chunk of a `wasmcloud:blobstore` object of `size` bytes starting at `offset`
+    #[instrument]
+    async fn get_object_chunk(
+        &self,
+        container: &str,
+        object: &str,
+        offset: u64,
+        size: u64,
+    ) -> anyhow::Result<compat::Chunk> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct GetObjectRequest<'a> {
+            object_id: &'a str,
+            container_id: &'a str,
+            range_start: Option<u64>,
+            range_end: Option<u64>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct GetObjectResponse {
+            success: bool,
+            #[serde(default)]
+            error: Option<String>,
+            #[serde(default)]
+            initial_chunk: Option<compat::Chunk>,
+        }
+
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.GetObject";
+
+        let chunk_size: u64 = BLOBSTORE_CHUNK_SIZE
+            .try_into()
+            .context("chunk size does not fit in `u64`")?;
+        let end = offset.saturating_add(chunk_size).min(size);
+        let (range_start, range_end) = if end > offset {
+            (Some(offset), Some(end - 1))
+        } else {
+            (None, None)
+        };
+        let res = self
+            .call_provider(
+                METHOD,
+                &GetObjectRequest {
+                    object_id: object,
+                    container_id: container,
+                    range_start,
+                    range_end,
+                },
+            )
+            .await?;
+        let GetObjectResponse {
+            success,
+            error,
+            initial_chunk,
+        } = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        if !success {
+            let error = error.unwrap_or_else(|| "unknown error".into());
+            bail!("failed to get object: {error}")
+        }
+        let mut chunk = initial_chunk.context("response does not contain a chunk")?;
+        chunk.offset = offset;
+        chunk.is_last = end >= size;
+        Ok(chunk)
+    }
+
+    /// Delivers the chunks of a `wasmcloud:blobstore` object of `size` bytes to the actor in order,
+    /// starting with `chunk`, on behalf of `provider`. Each chunk is only fetched after the
+    /// previous one was handled by the actor, which may cancel the download.
+    #[instrument(skip(chunk))]
+    async fn deliver_object_chunks(
+        self,
+        provider: WasmCloudEntity,
+        mut chunk: compat::Chunk,
+        size: u64,
+    ) -> anyhow::Result<()> {
+        let subject = format!(
+            "wasmbus.rpc.{}.{}",
+            self.lattice_prefix, self.origin.public_key
+        );
+        loop {
+            let n: u64 = chunk
+                .bytes
+                .len()
+                .try_into()
+                .context("chunk size does not fit in `u64`")?;
+            ensure!(n > 0 || chunk.is_last, "provider returned an empty chunk");
+            let offset = chunk.offset + n;
+            let is_last = chunk.is_last;
+            let object = chunk.object_id.clone();
+            let container = chunk.container_id.clone();
+            let chunk = rmp_serde::to_vec_named(&chunk).context("failed to encode chunk")?;
+            let invocation = Invocation::new(
+                &self.cluster_key,
+                provider.clone(),
+                self.origin.clone(),
+                BLOBSTORE_RECEIVE_CHUNK.into(),
+                chunk,
+            )?;
+            let invocation =
+                rmp_serde::to_vec_named(&invocation).context("failed to encode invocation")?;
+            let res = self
+                .nats
+                .request(subject.clone(), invocation.into())
+                .await
+                .context("failed to publish on NATS topic")?;
+            let InvocationResponse { msg, error, .. } = rmp_serde::from_slice(&res.payload)
+                .context("failed to decode invocation response")?;
+            if let Some(error) = error {
+                bail!("actor failed to receive chunk: {error}")
+            }
+            let compat::ChunkResponse { cancel_download } =
+                rmp_serde::from_slice(&msg).context("failed to decode chunk response")?;
+            if is_last || cancel_download {
+                return Ok(());
+            }
+            chunk = self
+                .get_object_chunk(&container, &object, offset, size)
+                .await?;
+        }
+    }
 }
 
 #[async_trait]
@@ -406,16 +601,13 @@ impl Bus for Handler {
         let (package, interface_method) = operation
             .split_once('/')
             .context("failed to parse operation")?;
-        let interfaces = self.interfaces.read().await;
-        let target = interfaces.get(package).context("link not found")?;
-        // TODO: Support per-interface links
+        let target = self.link_target(package, None).await?;
         let (mut req_r, req_w) = socket_pair()?;
         let (res_r, mut res_w) = socket_pair()?;
 
         let nats = self.nats.clone();
         let lattice_prefix = self.lattice_prefix.clone();
         let origin = self.origin.clone();
-        let target = target.clone();
         let cluster_key = self.cluster_key.clone();
         let interface_method = interface_method.to_string();
         Ok((
@@ -485,7 +677,7 @@ impl Bus for Handler {
         request: Vec<u8>,
     ) -> anyhow::Result<Result<(), String>> {
         match self
-            .call_provider_with_payload(&operation, request)
+            .call_provider_with_payload(None, &operation, request)
             .await
             .context("failed to call linked provider")?
         {
@@ -506,7 +698,7 @@ impl Bus for Handler {
         response: &mut Vec<u8>,
     ) -> anyhow::Result<Result<usize, String>> {
         match self
-            .call_provider_with_payload(operation, request)
+            .call_provider_with_payload(None, operation, request)
             .await
             .context("failed to call linked provider")?
         {
@@ -521,6 +713,381 @@ impl Bus for Handler {
     }
 }
 
+/// Maximum size of a single blobstore chunk, as recommended by the `wasmcloud:blobstore` contract
+/// to avoid exceeding the default NATS message size
+const BLOBSTORE_CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobstoreContainerObject {
+    container_id: String,
+    object_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobstoreTimestamp {
+    sec: i64,
+}
+
+impl BlobstoreTimestamp {
+    fn seconds(timestamp: Option<Self>) -> u64 {
+        timestamp
+            .and_then(|Self { sec }| sec.try_into().ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BlobstoreItemResult {
+    key: String,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl Handler {
+    /// Remove objects or containers using `method`, failing if any of the items failed to be removed
+    async fn blobstore_remove(&self, method: &str, request: &impl Serialize) -> anyhow::Result<()> {
+        let res = self.call_provider(method, request).await?;
+        let errors: Vec<BlobstoreItemResult> =
+            rmp_serde::from_slice(&res).context("failed to decode response")?;
+        if let Some(BlobstoreItemResult { key, error }) = errors.into_iter().next() {
+            let error = error.unwrap_or_else(|| "unknown error".into());
+            bail!("failed to remove `{key}`: {error}")
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Blobstore for Handler {
+    #[instrument]
+    async fn container_exists(&self, container: &str) -> anyhow::Result<bool> {
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.ContainerExists";
+        let res = self.call_provider(METHOD, &container).await?;
+        rmp_serde::from_slice(&res).context("failed to decode response")
+    }
+
+    #[instrument]
+    async fn create_container(&self, container: &str) -> anyhow::Result<()> {
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.CreateContainer";
+        self.call_provider(METHOD, &container).await?;
+        Ok(())
+    }
+
+    #[instrument]
+    async fn remove_container(&self, container: &str) -> anyhow::Result<()> {
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.RemoveContainers";
+        self.blobstore_remove(METHOD, &[container]).await
+    }
+
+    #[instrument]
+    async fn get_container_info(
+        &self,
+        container: &str,
+    ) -> anyhow::Result<Option<blobstore::types::ContainerInfo>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ContainerMetadata {
+            container_id: String,
+            #[serde(default)]
+            created_at: Option<BlobstoreTimestamp>,
+        }
+
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.GetContainerInfo";
+        if !self.container_exists(container).await? {
+            return Ok(None);
+        }
+        let res = self.call_provider(METHOD, &container).await?;
+        let ContainerMetadata {
+            container_id,
+            created_at,
+        } = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        Ok(Some(blobstore::types::ContainerInfo {
+            container_id,
+            created_at: BlobstoreTimestamp::seconds(created_at),
+        }))
+    }
+
+    #[instrument]
+    async fn get_object_info(
+        &self,
+        container: &str,
+        object: String,
+    ) -> anyhow::Result<Option<blobstore::types::ObjectInfo>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ObjectMetadata {
+            object_id: String,
+            container_id: String,
+            content_length: u64,
+            #[serde(default)]
+            last_modified: Option<BlobstoreTimestamp>,
+            #[serde(default)]
+            content_type: Option<String>,
+            #[serde(default)]
+            content_encoding: Option<String>,
+        }
+
+        const EXISTS_METHOD: &str = "wasmcloud:blobstore/Blobstore.ObjectExists";
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.GetObjectInfo";
+        let request = BlobstoreContainerObject {
+            container_id: container.into(),
+            object_id: object,
+        };
+        let res = self.call_provider(EXISTS_METHOD, &request).await?;
+        let exists: bool = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        if !exists {
+            return Ok(None);
+        }
+        let res = self.call_provider(METHOD, &request).await?;
+        let ObjectMetadata {
+            object_id,
+            container_id,
+            content_length,
+            last_modified,
+            content_type,
+            content_encoding,
+        } = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        Ok(Some(blobstore::types::ObjectInfo {
+            container_id,
+            object_id,
+            content_length,
+            last_modified: BlobstoreTimestamp::seconds(last_modified),
+            content_type,
+            content_encoding,
+        }))
+    }
+
+    #[instrument]
+    async fn remove_object(&self, container: &str, object: String) -> anyhow::Result<bool> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RemoveObjectsRequest<'a> {
+            container_id: &'a str,
+            objects: Vec<String>,
+        }
+
+        const EXISTS_METHOD: &str = "wasmcloud:blobstore/Blobstore.ObjectExists";
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.RemoveObjects";
+        let res = self
+            .call_provider(
+                EXISTS_METHOD,
+                &BlobstoreContainerObject {
+                    container_id: container.into(),
+                    object_id: object.clone(),
+                },
+            )
+            .await?;
+        let exists: bool = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        if !exists {
+            return Ok(false);
+        }
+        self.blobstore_remove(
+            METHOD,
+            &RemoveObjectsRequest {
+                container_id: container,
+                objects: vec![object],
+            },
+        )
+        .await?;
+        Ok(true)
+    }
+
+    #[instrument(skip(value))]
+    async fn put_object(
+        &self,
+        container: &str,
+        object: String,
+        mut value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+        content_type: Option<String>,
+        content_encoding: Option<String>,
+    ) -> anyhow::Result<()> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PutObjectRequest {
+            chunk: compat::Chunk,
+            content_type: Option<String>,
+            content_encoding: Option<String>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PutObjectResponse {
+            #[serde(default)]
+            stream_id: Option<String>,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PutChunkRequest {
+            chunk: compat::Chunk,
+            stream_id: Option<String>,
+            cancel_and_remove: bool,
+        }
+
+        const METHOD: &str = "wasmcloud:blobstore/Blobstore.PutObject";
+        const CHUNK_METHOD: &str = "wasmcloud:blobstore/Blobstore.PutChunk";
+
+        let chunk_size: u64 = BLOBSTORE_CHUNK_SIZE
+            .try_into()
+            .context("chunk size does not fit in `u64`")?;
+        // read one chunk ahead to determine whether the chunk being sent is the last one
+        let mut next = Vec::with_capacity(BLOBSTORE_CHUNK_SIZE);
+        (&mut value)
+            .take(chunk_size)
+            .read_to_end(&mut next)
+            .await
+            .context("failed to read value")?;
+        let mut offset = 0;
+        let mut stream_id = None;
+        let res: anyhow::Result<()> = async {
+            loop {
+                let bytes = next;
+                next = Vec::with_capacity(BLOBSTORE_CHUNK_SIZE);
+                (&mut value)
+                    .take(chunk_size)
+                    .read_to_end(&mut next)
+                    .await
+                    .context("failed to read value")?;
+                let n: u64 = bytes
+                    .len()
+                    .try_into()
+                    .context("chunk size does not fit in `u64`")?;
+                let chunk = compat::Chunk {
+                    object_id: object.clone(),
+                    container_id: container.into(),
+                    bytes,
+                    offset,
+                    is_last: next.is_empty(),
+                };
+                if offset == 0 {
+                    let res = self
+                        .call_provider(
+                            METHOD,
+                            &PutObjectRequest {
+                                chunk,
+                                content_type: content_type.clone(),
+                                content_encoding: content_encoding.clone(),
+                            },
+                        )
+                        .await?;
+                    let PutObjectResponse { stream_id: id } =
+                        rmp_serde::from_slice(&res).context("failed to decode response")?;
+                    stream_id = id;
+                } else {
+                    self.call_provider(
+                        CHUNK_METHOD,
+                        &PutChunkRequest {
+                            chunk,
+                            stream_id: stream_id.clone(),
+                            cancel_and_remove: false,
+                        },
+                    )
+                    .await?;
+                }
+                offset += n;
+                if next.is_empty() {
+                    return Ok(());
+                }
+            }
+        }
+        .await;
+        if res.is_err() && offset > 0 {
+            // Cancel the upload, so that no partially written object is left behind
+            if let Err(err) = self
+                .call_provider(
+                    CHUNK_METHOD,
+                    &PutChunkRequest {
+                        chunk: compat::Chunk {
+                            object_id: object.clone(),
+                            container_id: container.into(),
+                            offset,
+                            ..Default::default()
+                        },
+                        stream_id,
+                        cancel_and_remove: true,
+                    },
+                )
+                .await
+            {
+                warn!(?err, "failed to cancel object upload");
+            }
+        }
+        res
+    }
+
+    #[instrument]
+    async fn stream_object(&self, container: &str, object: String) -> anyhow::Result<()> {
+        let size = self
+            .get_object_info(container, object.clone())
+            .await?
+            .context("object not found")?
+            .content_length;
+        let provider = self.link_target("wasmcloud:blobstore", None).await?;
+        let chunk = self.get_object_chunk(container, &object, 0, size).await?;
+        let handler = self.clone();
+        spawn(async move {
+            if let Err(e) = handler.deliver_object_chunks(provider, chunk, size).await {
+                error!("failed to deliver object chunks to actor: {e:?}");
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Key of the `wasmcloud:keyvalue` set, which tracks the keys written to a bucket, since the
+/// contract has no operation for listing keys. It is namespaced like all other keys in the bucket
+const KEYVALUE_INDEX_KEY: &str = "wasmcloud:keys";
+
+impl Handler {
+    /// Adds `key` to or removes it from the key index of `bucket`.
+    async fn keyvalue_index(&self, bucket: &str, key: String, add: bool) -> anyhow::Result<()> {
+        #[derive(Serialize)]
+        struct SetRequest {
+            #[serde(rename = "setName")]
+            set_name: String,
+            value: String,
+        }
+
+        let method = if add {
+            "wasmcloud:keyvalue/KeyValue.SetAdd"
+        } else {
+            "wasmcloud:keyvalue/KeyValue.SetDel"
+        };
+        let (link_name, set_name) = self.keyvalue_key(bucket, KEYVALUE_INDEX_KEY.into()).await;
+        let res = self
+            .call_provider_on_link(
+                link_name,
+                method,
+                &SetRequest {
+                    set_name,
+                    value: key,
+                },
+            )
+            .await?;
+        let _: u32 = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        Ok(())
+    }
+
+    /// Resolves the `wasmcloud:keyvalue` link name and key to use for `key` in `bucket`.
+    /// Buckets named after a link are mapped to that link, other named buckets are
+    /// namespaced within the default link by prefixing keys with `{len}:{bucket}:`,
+    /// where `len` is the length of `bucket` in bytes, so that bucket names containing `:`
+    /// cannot collide.
+    async fn keyvalue_key<'a>(&self, bucket: &'a str, key: String) -> (Option<&'a str>, String) {
+        if bucket.is_empty() {
+            return (None, key);
+        }
+        let interfaces = self.interfaces.read().await;
+        if interfaces
+            .get("wasmcloud:keyvalue")
+            .map_or(false, |links| links.contains_key(bucket))
+        {
+            (Some(bucket), key)
+        } else {
+            (None, format!("{}:{bucket}:{key}", bucket.len()))
+        }
+    }
+}
+
 #[async_trait]
 impl KeyValueReadWrite for Handler {
     #[instrument]
@@ -531,17 +1098,16 @@ impl KeyValueReadWrite for Handler {
     ) -> anyhow::Result<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)> {
         #[derive(Deserialize)]
         struct GetResponse {
+            #[serde(with = "serde_bytes")]
             #[serde(default)]
-            value: String,
+            value: Vec<u8>,
             #[serde(default)]
             exists: bool,
         }
 
         const METHOD: &str = "wasmcloud:keyvalue/KeyValue.Get";
-        if !bucket.is_empty() {
-            bail!("buckets not currently supported")
-        }
-        let res = self.call_provider(METHOD, &key).await?;
+        let (link_name, key) = self.keyvalue_key(bucket, key).await;
+        let res = self.call_provider_on_link(link_name, METHOD, &key).await?;
         let GetResponse { value, exists } =
             rmp_serde::from_slice(&res).context("failed to decode response")?;
         if !exists {
@@ -564,24 +1130,28 @@ impl KeyValueReadWrite for Handler {
         #[derive(Serialize)]
         struct SetRequest {
             key: String,
-            value: String,
+            #[serde(with = "serde_bytes")]
+            value: Vec<u8>,
             expires: u32,
         }
 
         const METHOD: &str = "wasmcloud:keyvalue/KeyValue.Set";
-        if !bucket.is_empty() {
-            bail!("buckets not currently supported")
-        }
-        let mut buf = String::new();
+        ensure!(
+            key != KEYVALUE_INDEX_KEY,
+            "key `{KEYVALUE_INDEX_KEY}` is reserved"
+        );
+        let (link_name, bucket_key) = self.keyvalue_key(bucket, key.clone()).await;
+        let mut buf = vec![];
         value
-            .read_to_string(&mut buf)
+            .read_to_end(&mut buf)
             .await
             .context("failed to read value")?;
         let res = self
-            .call_provider(
+            .call_provider_on_link(
+                link_name,
                 METHOD,
                 &SetRequest {
-                    key,
+                    key: bucket_key,
                     value: buf,
                     expires: 0,
                 },
@@ -590,17 +1160,22 @@ impl KeyValueReadWrite for Handler {
         if !res.is_empty() {
             error!("unexpected response returned for `{METHOD}` call");
         }
-        Ok(())
+        self.keyvalue_index(bucket, key, true)
+            .await
+            .context("failed to add key to index")
     }
 
     #[instrument]
     async fn delete(&self, bucket: &str, key: String) -> anyhow::Result<()> {
         const METHOD: &str = "wasmcloud:keyvalue/KeyValue.Del";
-        if !bucket.is_empty() {
-            bail!("buckets not currently supported")
-        }
-        let res = self.call_provider(METHOD, &key).await?;
+        let (link_name, bucket_key) = self.keyvalue_key(bucket, key.clone()).await;
+        let res = self
+            .call_provider_on_link(link_name, METHOD, &bucket_key)
+            .await?;
         let deleted: bool = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        self.keyvalue_index(bucket, key, false)
+            .await
+            .context("failed to remove key from index")?;
         ensure!(deleted, "key not found");
         Ok(())
     }
@@ -608,12 +1183,106 @@ impl KeyValueReadWrite for Handler {
     #[instrument]
     async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool> {
         const METHOD: &str = "wasmcloud:keyvalue/KeyValue.Contains";
-        if !bucket.is_empty() {
-            bail!("buckets not currently supported")
+        let (link_name, key) = self.keyvalue_key(bucket, key).await;
+        let res = self.call_provider_on_link(link_name, METHOD, &key).await?;
+        rmp_serde::from_slice(&res).context("failed to decode response")
+    }
+}
+
+#[async_trait]
+impl KeyValueAtomic for Handler {
+    #[instrument]
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
+        #[derive(Serialize)]
+        struct IncrementRequest {
+            key: String,
+            value: i32,
         }
-        let res = self.call_provider(METHOD, &key).await?;
+
+        const METHOD: &str = "wasmcloud:keyvalue/KeyValue.Increment";
+        ensure!(
+            key != KEYVALUE_INDEX_KEY,
+            "key `{KEYVALUE_INDEX_KEY}` is reserved"
+        );
+        let value = delta.try_into().context("delta does not fit in `i32`")?;
+        let (link_name, bucket_key) = self.keyvalue_key(bucket, key.clone()).await;
+        let res = self
+            .call_provider_on_link(
+                link_name,
+                METHOD,
+                &IncrementRequest {
+                    key: bucket_key,
+                    value,
+                },
+            )
+            .await?;
+        let value: i32 = rmp_serde::from_slice(&res).context("failed to decode response")?;
+        self.keyvalue_index(bucket, key, true)
+            .await
+            .context("failed to add key to index")?;
+        value.try_into().context("value does not fit in `u64`")
+    }
+
+    /// `wasmcloud:keyvalue` has no atomic compare-and-swap operation, so this returns an error
+    /// to the caller, which components receive as a `wasi:keyvalue` error
+    #[instrument]
+    async fn compare_and_swap(
+        &self,
+        _bucket: &str,
+        _key: String,
+        _old: u64,
+        _new: u64,
+    ) -> anyhow::Result<bool> {
+        bail!("`compare-and-swap` is not supported by `wasmcloud:keyvalue`")
+    }
+}
+
+#[async_trait]
+impl KeyValueBatch for Handler {
+    #[instrument]
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(bucket, key).await?);
+        }
+        Ok(values)
+    }
+
+    /// Returns the keys tracked in the key index of `bucket`, which only contains keys written
+    /// by actors through this interface
+    #[instrument]
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        const METHOD: &str = "wasmcloud:keyvalue/KeyValue.SetQuery";
+        let (link_name, set_name) = self.keyvalue_key(bucket, KEYVALUE_INDEX_KEY.into()).await;
+        let res = self
+            .call_provider_on_link(link_name, METHOD, &set_name)
+            .await?;
         rmp_serde::from_slice(&res).context("failed to decode response")
     }
+
+    #[instrument(skip(entries))]
+    async fn set_many(
+        &self,
+        bucket: &str,
+        entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        for (key, value) in entries {
+            self.set(bucket, key, value).await?;
+        }
+        Ok(())
+    }
+
+    #[instrument]
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        for key in keys {
+            self.delete(bucket, key).await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -672,6 +1341,9 @@ impl Messaging for Handler {
         })
     }
 
+    /// Collects up to `max_results` replies received within `timeout`. A `max_results` of `0`
+    /// collects a single reply, same as `1`, following `maxResults` of the
+    /// `wasmcloud:messaging` provider contract. Both are performed as a single request
     #[instrument]
     async fn request_multi(
         &self,
@@ -680,13 +1352,65 @@ impl Messaging for Handler {
         timeout: Duration,
         max_results: u32,
     ) -> anyhow::Result<Vec<messaging::types::BrokerMessage>> {
-        match max_results {
-            0..=1 => {
-                let res = self.request(subject, body, timeout).await?;
-                Ok(vec![res])
-            }
-            2.. => bail!("at most 1 result can be requested at the time"),
+        #[derive(Serialize)]
+        struct RequestMultiMessage {
+            subject: String,
+            #[serde(with = "serde_bytes")]
+            body: Vec<u8>,
+            #[serde(rename = "timeoutMs")]
+            timeout_ms: u32,
+            #[serde(rename = "maxResults")]
+            max_results: u32,
+        }
+        #[derive(Deserialize)]
+        struct ReplyMessage {
+            #[serde(default)]
+            subject: String,
+            #[serde(rename = "replyTo")]
+            #[serde(default)]
+            reply_to: Option<String>,
+            #[serde(with = "serde_bytes")]
+            #[serde(default)]
+            body: Vec<u8>,
+        }
+        const METHOD: &str = "wasmcloud:messaging/Messaging.RequestMulti";
+
+        // NOTE: `0` has always meant a single reply, collecting multiple replies is opt-in
+        if max_results <= 1 {
+            let res = self.request(subject, body, timeout).await?;
+            return Ok(vec![res]);
         }
+        let timeout_ms = timeout
+            .as_millis()
+            .try_into()
+            .context("timeout milliseconds do not fit in `u32`")?;
+        let res = self
+            .call_provider(
+                METHOD,
+                &RequestMultiMessage {
+                    subject,
+                    body: body.unwrap_or_default(),
+                    timeout_ms,
+                    max_results,
+                },
+            )
+            .await?;
+        let replies: Vec<ReplyMessage> =
+            rmp_serde::from_slice(&res).context("failed to decode response")?;
+        Ok(replies
+            .into_iter()
+            .map(
+                |ReplyMessage {
+                     subject,
+                     reply_to,
+                     body,
+                 }| messaging::types::BrokerMessage {
+                    subject,
+                    reply_to,
+                    body: Some(body),
+                },
+            )
+            .collect())
     }
 
     #[instrument]
@@ -726,7 +1450,78 @@ impl Messaging for Handler {
     }
 }
 
+#[async_trait]
+impl OutgoingHttp for Handler {
+    #[instrument(skip(request))]
+    async fn handle(
+        &self,
+        request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+        options: Option<RequestOptions>,
+    ) -> anyhow::Result<http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>> {
+        if options.is_some() {
+            debug!("request options are not supported by `wasmcloud:httpclient`, ignoring");
+        }
+        let request = compat::HttpClientRequest::from_http(request).await?;
+        let res = self.call_provider(HTTP_CLIENT_REQUEST, &request).await?;
+        let response: compat::HttpResponse =
+            rmp_serde::from_slice(&res).context("failed to decode response")?;
+        response.try_into().context("invalid HTTP response")
+    }
+}
+
 impl ActorInstance {
+    /// Delivers a `wasmcloud:httpserver` request to the actor via `wasi:http/incoming-handler`,
+    /// falling back to `HttpServer.HandleRequest` for actors not exporting the interface
+    #[instrument(skip_all)]
+    async fn handle_incoming_http(
+        instance: PooledActorInstance,
+        request: Vec<u8>,
+    ) -> anyhow::Result<Vec<u8>> {
+        wasmcloud_runtime::ActorInstance::from(instance)
+            .into_incoming_http()
+            .await
+            .context("failed to instantiate incoming HTTP handler")?
+            .handle_compat(request)
+            .await
+    }
+
+    /// Delivers a `wasmcloud:messaging` subscription message to the actor via `wasmcloud:messaging/handler`,
+    /// falling back to `MessageSubscriber.HandleMessage` for actors not exporting the interface
+    #[instrument(skip_all)]
+    async fn handle_messaging_message(
+        instance: PooledActorInstance,
+        msg: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        let msg: compat::SubMessage =
+            rmp_serde::from_slice(msg).context("failed to decode message")?;
+        wasmcloud_runtime::ActorInstance::from(instance)
+            .into_messaging_handler()
+            .await
+            .context("failed to instantiate messaging handler")?
+            .handle_message(msg.into())
+            .await?;
+        Ok(vec![])
+    }
+
+    /// Delivers a `wasmcloud:blobstore` object chunk to the actor via `wasmcloud:blobstore/handler`,
+    /// falling back to `ChunkReceiver.ReceiveChunk` for actors not exporting the interface
+    #[instrument(skip_all)]
+    async fn handle_blobstore_chunk(
+        instance: PooledActorInstance,
+        chunk: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        let chunk: compat::Chunk =
+            rmp_serde::from_slice(chunk).context("failed to decode chunk")?;
+        let cancel_download = wasmcloud_runtime::ActorInstance::from(instance)
+            .into_blobstore_handler()
+            .await
+            .context("failed to instantiate blobstore handler")?
+            .receive_chunk(chunk.into())
+            .await?;
+        rmp_serde::to_vec_named(&compat::ChunkResponse { cancel_download })
+            .context("failed to encode chunk response")
+    }
+
     #[instrument(skip(self, payload))]
     async fn handle_call(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
         let Invocation {
@@ -745,21 +1540,44 @@ impl ActorInstance {
             .instantiate(self.runtime.clone())
             .await
             .context("failed to instantiate actor")?;
+        let logger = self.logger.clone().invocation_id(invocation_id.clone());
         instance
-            .stderr(stderr())
+            .stderr(logger.stderr())
             .await
             .context("failed to set stderr")?
+            .logging(Arc::new(logger))
+            .blobstore(Arc::new(self.handler.clone()))
             .bus(Arc::new(self.handler.clone()))
+            .keyvalue_atomic(Arc::new(self.handler.clone()))
+            .keyvalue_batch(Arc::new(self.handler.clone()))
             .keyvalue_readwrite(Arc::new(self.handler.clone()))
-            .messaging(Arc::new(self.handler.clone()));
-        let res = AsyncBytesMut::default();
-        let res = match instance
-            .call(operation, Cursor::new(msg), res.clone())
-            .await
-            .context("failed to call actor")?
-        {
-            Ok(()) => {
-                let msg: Vec<_> = res.try_into()?;
+            .messaging(Arc::new(self.handler.clone()))
+            .outgoing_http(Arc::new(self.handler.clone()));
+        let res = if operation == HTTP_SERVER_HANDLE_REQUEST {
+            Self::handle_incoming_http(instance, msg)
+                .await
+                .map_err(|e| format!("{e:#}"))
+        } else if operation == MESSAGING_HANDLE_MESSAGE {
+            Self::handle_messaging_message(instance, &msg)
+                .await
+                .map_err(|e| format!("{e:#}"))
+        } else if operation == BLOBSTORE_RECEIVE_CHUNK {
+            Self::handle_blobstore_chunk(instance, &msg)
+                .await
+                .map_err(|e| format!("{e:#}"))
+        } else {
+            let res = AsyncBytesMut::default();
+            match instance
+                .call(operation, Cursor::new(msg), res.clone())
+                .await
+                .context("failed to call actor")?
+            {
+                Ok(()) => Ok(res.try_into()?),
+                Err(e) => Err(e),
+            }
+        };
+        let res = match res {
+            Ok(msg) => {
                 let content_length = msg.len().try_into().ok();
                 InvocationResponse {
                     msg,
@@ -843,6 +1661,7 @@ pub struct Host {
     nats: async_nats::Client,
     data: kv::Store,
     data_watch: AbortHandle,
+    events: Option<async_nats::jetstream::stream::Stream>,
     providers: RwLock<HashMap<String, Provider>>,
     runtime: Runtime,
     start_at: Instant,
@@ -892,6 +1711,60 @@ async fn create_lattice_metadata_bucket(
     }
 }
 
+#[instrument(skip(jetstream))]
+async fn ensure_event_history_stream(
+    jetstream: &JetstreamContext,
+    lattice_prefix: &str,
+    config::EventHistory {
+        max_age,
+        max_messages,
+        max_bytes,
+    }: &config::EventHistory,
+) -> anyhow::Result<async_nats::jetstream::stream::Stream> {
+    let name = format!("LATTICEEVENTS_{lattice_prefix}");
+    let config = async_nats::jetstream::stream::Config {
+        name: name.clone(),
+        subjects: vec![format!("wasmbus.evt.{lattice_prefix}")],
+        max_age: max_age.unwrap_or_default(),
+        max_messages: max_messages.unwrap_or(-1),
+        max_bytes: max_bytes.unwrap_or(-1),
+        ..Default::default()
+    };
+    // All hosts in the lattice share the stream, so only create it if it does not exist yet
+    let mut stream = if let Ok(stream) = jetstream.get_stream(&name).await {
+        stream
+    } else {
+        match jetstream.create_stream(config).await {
+            Ok(stream) => {
+                info!("created lattice event stream {name}");
+                return Ok(stream);
+            }
+            // Another host may have created the stream concurrently
+            Err(err) => jetstream.get_stream(&name).await.map_err(|_| {
+                anyhow!(err).context(format!("failed to create event stream '{name}'"))
+            })?,
+        }
+    };
+    let existing = &stream
+        .info()
+        .await
+        .map_err(|e| anyhow!(e).context(format!("failed to get event stream '{name}' info")))?
+        .config;
+    if max_age.is_some_and(|max_age| max_age != existing.max_age)
+        || max_messages.is_some_and(|max_messages| max_messages != existing.max_messages)
+        || max_bytes.is_some_and(|max_bytes| max_bytes != existing.max_bytes)
+    {
+        bail!(
+            "retention limits of existing event stream '{name}' (max_age: {:?}, max_messages: {}, max_bytes: {}) conflict with configured limits",
+            existing.max_age,
+            existing.max_messages,
+            existing.max_bytes,
+        );
+    }
+    info!("using existing lattice event stream {name}");
+    Ok(stream)
+}
+
 impl Host {
     const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
 
@@ -936,12 +1809,12 @@ impl Host {
             .next()
             .context("failed to generate friendly name")?;
 
-        let start_evt = json!({
-            "friendly_name": friendly_name,
-            "labels": labels,
-            "uptime_seconds": 0,
-            "version": env!("CARGO_PKG_VERSION"),
-        });
+        let start_evt = event::HostStarted {
+            friendly_name: friendly_name.clone(),
+            labels: labels.clone(),
+            uptime_seconds: 0,
+            version: env!("CARGO_PKG_VERSION").into(),
+        };
 
         debug!(
             ctl_nats_url = config.ctl_nats_url.as_str(),
@@ -951,9 +1824,15 @@ impl Host {
             .await
             .context("failed to connect to NATS control server")?;
 
-        let queue = Queue::new(&nats, &config.lattice_prefix, &cluster_key, &host_key)
-            .await
-            .context("failed to initialize queue")?;
+        let queue = Queue::new(
+            &nats,
+            &config.lattice_prefix,
+            &cluster_key,
+            &host_key,
+            config.event_history.is_some(),
+        )
+        .await
+        .context("failed to initialize queue")?;
         nats.flush().await.context("failed to flush")?;
 
         let start_at = Instant::now();
@@ -967,12 +1846,22 @@ impl Host {
         let (stop_tx, stop_rx) = watch::channel(None);
 
         // TODO: Configure
-        let runtime = Runtime::builder()
-            .actor_config(wasmcloud_runtime::ActorConfig {
-                require_signature: true,
-            })
-            .build()
-            .context("failed to build runtime")?;
+        let mut runtime = Runtime::builder().actor_config(wasmcloud_runtime::ActorConfig {
+            require_signature: true,
+            ..Default::default()
+        });
+        if let Some(dir) = &config.profile_dir {
+            let mut profiling = Profiling::new(dir);
+            if let Some(interval) = config.profile_interval {
+                profiling.interval = interval;
+            }
+            profiling.all = config.profile_all_actors;
+            runtime = runtime.profiling(profiling);
+        }
+        if let Some(profiler) = config.native_profiler {
+            runtime = runtime.native_profiler(profiler);
+        }
+        let runtime = runtime.build().context("failed to build runtime")?;
         let event_builder = EventBuilderV10::new().source(host_key.public_key());
 
         let jetstream = if let Some(domain) = config.js_domain.as_ref() {
@@ -988,6 +1877,14 @@ impl Host {
             .await
             .map_err(|e| anyhow!(e).context("failed to acquire data bucket"))?;
 
+        let events = if let Some(history) = config.event_history.as_ref() {
+            let stream =
+                ensure_event_history_stream(&jetstream, &config.lattice_prefix, history).await?;
+            Some(stream)
+        } else {
+            None
+        };
+
         let (queue_abort, queue_abort_reg) = AbortHandle::new_pair();
         let (heartbeat_abort, heartbeat_abort_reg) = AbortHandle::new_pair();
         let (data_watch_abort, data_watch_abort_reg) = AbortHandle::new_pair();
@@ -1004,6 +1901,7 @@ impl Host {
             nats,
             data: data.clone(),
             data_watch: data_watch_abort.clone(),
+            events,
             providers: RwLock::default(),
             runtime,
             start_at,
@@ -1012,7 +1910,7 @@ impl Host {
             queue: queue_abort.clone(),
             links: RwLock::default(),
         };
-        host.publish_event("host_started", start_evt)
+        host.publish_event(start_evt)
             .await
             .context("failed to publish start event")?;
         info!("host {} started", host.host_key.public_key());
@@ -1058,7 +1956,7 @@ impl Host {
                 let host = Arc::clone(&host);
                 async move {
                     let heartbeat = host.heartbeat().await;
-                    if let Err(e) = host.publish_event("host_heartbeat", heartbeat).await {
+                    if let Err(e) = host.publish_event(heartbeat).await {
                         error!("failed to publish heartbeat: {e}");
                     }
                 }
@@ -1069,12 +1967,9 @@ impl Host {
             queue_abort.abort();
             data_watch_abort.abort();
             let _ = try_join!(queue, data_watch, heartbeat).context("failed to await tasks")?;
-            host.publish_event(
-                "host_stopped",
-                json!({
-                    "labels": host.labels,
-                }),
-            )
+            host.publish_event(event::HostStopped {
+                labels: host.labels.clone(),
+            })
             .await
             .context("failed to publish stop event")
         }))
@@ -1097,16 +1992,16 @@ impl Host {
     }
 
     #[instrument(skip(self))]
-    async fn heartbeat(&self) -> serde_json::Value {
+    async fn heartbeat(&self) -> event::HostHeartbeat {
         let actors = self.actors.read().await;
-        let actors: HashMap<&String, usize> = stream::iter(actors.iter())
+        let actors: HashMap<String, usize> = stream::iter(actors.iter())
             .filter_map(|(id, actor)| async move {
                 let instances = actor.instances.read().await;
                 let count = instances.values().map(Vec::len).sum();
                 if count == 0 {
                     None
                 } else {
-                    Some((id, count))
+                    Some((id.clone(), count))
                 }
             })
             .collect()
@@ -1127,35 +2022,32 @@ impl Host {
                         let metadata = claims.metadata.as_ref();
                         let contract_id =
                             metadata.map(|jwt::CapabilityProvider { capid, .. }| capid.as_str());
-                        json!({
-                            "public_key": public_key,
-                            "link_name": link_name,
-                            "contract_id": contract_id.unwrap_or("n/a"),
-                        })
+                        event::ProviderDescription {
+                            public_key: public_key.clone(),
+                            link_name: link_name.clone(),
+                            contract_id: contract_id.unwrap_or("n/a").into(),
+                        }
                     })
                 },
             )
             .collect();
         let uptime = self.start_at.elapsed();
-        json!({
-            "actors": actors,
-            "friendly_name": self.friendly_name,
-            "labels": self.labels,
-            "providers": providers,
-            "uptime_human": "TODO", // TODO
-            "uptime_seconds": uptime.as_secs(),
-            "version": env!("CARGO_PKG_VERSION"),
-        })
+        event::HostHeartbeat {
+            actors,
+            friendly_name: self.friendly_name.clone(),
+            labels: self.labels.clone(),
+            providers,
+            uptime_human: event::format_duration(uptime),
+            uptime_seconds: uptime.as_secs(),
+            version: env!("CARGO_PKG_VERSION").into(),
+        }
     }
 
-    #[instrument(skip(self, name))]
-    async fn publish_event(
-        &self,
-        name: impl AsRef<str>,
-        data: serde_json::Value,
-    ) -> anyhow::Result<()> {
-        let name = name.as_ref();
-        let name = format!("com.wasmcloud.lattice.{name}");
+    #[instrument(skip_all, fields(name = E::NAME))]
+    async fn publish_event<E: event::Event>(&self, data: E) -> anyhow::Result<()> {
+        let name = E::ty();
+        let data = serde_json::to_value(event::Versioned::from(data))
+            .context("failed to serialize event data")?;
         let now = OffsetDateTime::now_utc()
             .format(&Rfc3339)
             .context("failed to format current time")?;
@@ -1212,6 +2104,19 @@ impl Host {
 
                 let (calls_abort, calls_abort_reg) = AbortHandle::new_pair();
                 let id = Ulid::new();
+                let mut logger = ActorLogger::new(&claims.subject)
+                    .actor_name(claims.metadata.as_ref().and_then(|md| md.name.clone()))
+                    .instance_id(Uuid::from_u128(id.into()).to_string());
+                if self.host_config.publish_actor_logs {
+                    logger = logger.nats(
+                        self.nats.clone(),
+                        format!(
+                            "wasmbus.log.{lattice_prefix}.{subject}",
+                            lattice_prefix = self.host_config.lattice_prefix,
+                            subject = claims.subject
+                        ),
+                    );
+                }
                 let instance = Arc::new(ActorInstance {
                     nats: self.nats.clone(),
                     pool,
@@ -1219,6 +2124,7 @@ impl Host {
                     calls: calls_abort,
                     runtime: self.runtime.clone(),
                     handler: handler.clone(),
+                    logger,
                 });
 
                 let _calls = spawn({
@@ -1229,15 +2135,12 @@ impl Host {
                     })
                 });
 
-                self.publish_event(
-                    "actor_started",
-                    event::actor_started(
-                        claims,
-                        annotations,
-                        Uuid::from_u128(id.into()),
-                        actor_ref,
-                    ),
-                )
+                self.publish_event(event::actor_started(
+                    claims,
+                    annotations,
+                    Uuid::from_u128(id.into()),
+                    actor_ref,
+                ))
                 .await?;
                 anyhow::Result::<_>::Ok(instance)
             }
@@ -1245,10 +2148,13 @@ impl Host {
         .try_collect()
         .await
         .context("failed to instantiate actor")?;
-        self.publish_event(
-            "actors_started",
-            event::actors_started(claims, annotations, host_id, count, actor_ref),
-        )
+        self.publish_event(event::actors_started(
+            claims,
+            annotations,
+            host_id,
+            count,
+            actor_ref,
+        ))
         .await?;
         Ok(instances)
     }
@@ -1276,22 +2182,22 @@ impl Host {
             .try_for_each_concurrent(None, |instance| {
                 instance.calls.abort();
                 async move {
-                    self.publish_event(
-                        "actor_stopped",
-                        event::actor_stopped(
-                            claims,
-                            annotations,
-                            Uuid::from_u128(instance.id.into()),
-                        ),
-                    )
+                    self.publish_event(event::actor_stopped(
+                        claims,
+                        annotations,
+                        Uuid::from_u128(instance.id.into()),
+                    ))
                     .await
                 }
             })
             .await?;
-        self.publish_event(
-            "actors_stopped",
-            event::actors_stopped(claims, annotations, host_id, count, remaining),
-        )
+        self.publish_event(event::actors_stopped(
+            claims,
+            annotations,
+            host_id,
+            count,
+            remaining,
+        ))
         .await
     }
 
@@ -1312,19 +2218,21 @@ impl Host {
         let links = self.links.read().await;
         let interfaces = links
             .values()
-            .filter_map(|ld| {
-                (ld.actor_id == claims.subject).then(|| {
-                    (
-                        ld.contract_id.clone(),
+            .filter(|ld| ld.actor_id == claims.subject)
+            .fold(LinkTargets::default(), |mut interfaces, ld| {
+                interfaces
+                    .entry(ld.contract_id.clone())
+                    .or_default()
+                    .insert(
+                        ld.link_name.clone(),
                         WasmCloudEntity {
                             link_name: ld.link_name.clone(),
                             contract_id: ld.contract_id.clone(),
                             public_key: ld.provider_id.clone(),
                         },
-                    )
-                })
-            })
-            .collect();
+                    );
+                interfaces
+            });
         let origin = WasmCloudEntity {
             public_key: claims.subject.clone(),
             ..Default::default()
@@ -1375,7 +2283,7 @@ impl Host {
                 remaining,
                 |remaining, (annotations, mut instances)| async move {
                     let Some(count) = NonZeroUsize::new(instances.len()) else {
-                        return Ok(remaining)
+                        return Ok(remaining);
                     };
                     let remaining = remaining
                         .checked_sub(count.into())
@@ -1491,9 +2399,10 @@ impl Host {
             let actor = fetch_actor(&actor_ref, &self.host_config.oci_opts)
                 .await
                 .context("failed to fetch actor")?;
-            let actor = wasmcloud_runtime::Actor::new(&self.runtime, actor)
-                .context("failed to initialize actor")?;
-            actor.claims().context("claims missing")?.subject.clone()
+            let claims = wasmcloud_runtime::actor::claims(actor)
+                .context("failed to extract actor claims")?
+                .context("claims missing")?;
+            claims.subject
         } else {
             actor_id
         };
@@ -1585,27 +2494,34 @@ impl Host {
         let actor = fetch_actor(&actor_ref, &self.host_config.oci_opts)
             .await
             .context("failed to fetch actor")?;
-        let actor = wasmcloud_runtime::Actor::new(&self.runtime, actor)
-            .context("failed to initialize actor")?;
-        let claims = actor.claims().context("claims missing")?;
+        // NOTE: Claims are validated before compilation, so that invalid actors are rejected early
+        // and actors, which are already running, are not compiled again
+        let claims = wasmcloud_runtime::actor::claims(&actor)
+            .context("failed to extract actor claims")?
+            .context("claims missing")?;
 
         let annotations = annotations.map(|annotations| annotations.into_iter().collect());
         let Some(count) = NonZeroUsize::new(count.into()) else {
             // NOTE: This mimics OTP behavior
-            self.publish_event(
-                "actors_started",
-                event::actors_started(claims, &annotations, host_id, 0usize, actor_ref),
-            )
+            self.publish_event(event::actors_started(
+                &claims,
+                &annotations,
+                host_id,
+                0usize,
+                actor_ref,
+            ))
             .await?;
-            return Ok(())
+            return Ok(());
         };
 
         match self.actors.write().await.entry(claims.subject.clone()) {
             hash_map::Entry::Vacant(entry) => {
+                let actor = wasmcloud_runtime::Actor::new(&self.runtime, actor)
+                    .context("failed to initialize actor")?;
                 if let Err(err) = self
                     .start_actor(
                         entry,
-                        actor.clone(),
+                        actor,
                         actor_ref.clone(),
                         count,
                         host_id,
@@ -1613,10 +2529,13 @@ impl Host {
                     )
                     .await
                 {
-                    self.publish_event(
-                        "actors_start_failed",
-                        event::actors_start_failed(claims, &annotations, host_id, actor_ref, &err),
-                    )
+                    self.publish_event(event::actors_start_failed(
+                        &claims,
+                        &annotations,
+                        host_id,
+                        actor_ref,
+                        &err,
+                    ))
                     .await?;
                 }
             }
@@ -1663,10 +2582,7 @@ impl Host {
                 .await
             {
                 if let Err(err) = self
-                    .publish_event(
-                        "actor_start_failed",
-                        event::actor_start_failed(actor_ref, &err),
-                    )
+                    .publish_event(event::actor_start_failed(actor_ref, &err))
                     .await
                 {
                     error!("{err:#}");
@@ -1734,6 +2650,19 @@ impl Host {
         Ok(SUCCESS.into())
     }
 
+    #[instrument(skip(self, payload))]
+    fn handle_profile_actor(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
+        let ProfileActorCommand { actor_id, enable } = serde_json::from_slice(payload.as_ref())
+            .context("failed to deserialize actor profile command")?;
+
+        debug!(actor_id, enable, "profile actor");
+
+        self.runtime
+            .set_actor_profiling(actor_id, enable)
+            .context("failed to configure actor profiling")?;
+        Ok(SUCCESS.into())
+    }
+
     #[instrument(skip(self, payload))]
     async fn handle_update_actor(
         &self,
@@ -1894,17 +2823,14 @@ impl Host {
                     }
                 }
             });
-            self.publish_event(
-                "provider_started",
-                event::provider_started(
-                    &claims,
-                    &annotations,
-                    Uuid::from_u128(id.into()),
-                    host_id,
-                    provider_ref,
-                    link_name,
-                ),
-            )
+            self.publish_event(event::provider_started(
+                &claims,
+                &annotations,
+                Uuid::from_u128(id.into()),
+                host_id,
+                provider_ref,
+                link_name,
+            ))
             .await?;
             entry.insert(ProviderInstance {
                 child,
@@ -1944,10 +2870,7 @@ impl Host {
                 .await
             {
                 if let Err(err) = self
-                    .publish_event(
-                        "provider_start_failed",
-                        event::provider_start_failed(provider_ref, link_name, &err),
-                    )
+                    .publish_event(event::provider_start_failed(provider_ref, link_name, &err))
                     .await
                 {
                     error!("{err:#}");
@@ -2011,17 +2934,14 @@ impl Host {
                 }
 
                 child.abort();
-                self.publish_event(
-                    "provider_stopped",
-                    event::provider_stopped(
-                        &provider.claims,
-                        &annotations,
-                        Uuid::from_u128(id.into()),
-                        host_id,
-                        link_name,
-                        "stop",
-                    ),
-                )
+                self.publish_event(event::provider_stopped(
+                    &provider.claims,
+                    &annotations,
+                    Uuid::from_u128(id.into()),
+                    host_id,
+                    link_name,
+                    "stop",
+                ))
                 .await?;
             }
         }
@@ -2202,6 +3122,84 @@ impl Host {
         bail!("TODO")
     }
 
+    #[instrument(skip(self, payload))]
+    async fn handle_events(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Option<Bytes>> {
+        let Some(events) = self.events.as_ref() else {
+            // Only hosts with event history enabled join the `get.events` queue group
+            return Ok(None);
+        };
+        let payload = payload.as_ref();
+        let event::HistoryRequest {
+            since_sequence,
+            since_time,
+            limit,
+        } = if payload.is_empty() {
+            event::HistoryRequest::default()
+        } else {
+            serde_json::from_slice(payload)
+                .context("failed to deserialize event history request")?
+        };
+        let deliver_policy = match (since_sequence, since_time) {
+            (Some(_), Some(_)) => {
+                bail!("only one of `since_sequence` and `since_time` may be specified")
+            }
+            // Stream sequence numbers start at 1
+            (Some(0) | None, None) => async_nats::jetstream::consumer::DeliverPolicy::All,
+            (Some(start_sequence), None) => {
+                async_nats::jetstream::consumer::DeliverPolicy::ByStartSequence { start_sequence }
+            }
+            (None, Some(start_time)) => {
+                let start_time = OffsetDateTime::parse(&start_time, &Rfc3339)
+                    .context("failed to parse `since_time`")?;
+                async_nats::jetstream::consumer::DeliverPolicy::ByStartTime { start_time }
+            }
+        };
+        let limit = limit
+            .unwrap_or(event::MAX_HISTORY_BATCH)
+            .clamp(1, event::MAX_HISTORY_BATCH);
+
+        let mut stream = events.clone();
+        let last_sequence = stream
+            .info()
+            .await
+            .map_err(|e| anyhow!(e).context("failed to get event stream info"))?
+            .state
+            .last_sequence;
+        let consumer = stream
+            .create_consumer(async_nats::jetstream::consumer::pull::Config {
+                deliver_policy,
+                ack_policy: async_nats::jetstream::consumer::AckPolicy::None,
+                inactive_threshold: Duration::from_secs(5),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| anyhow!(e).context("failed to create event stream consumer"))?;
+        let events: Vec<_> = consumer
+            .fetch()
+            .max_messages(limit)
+            .messages()
+            .await
+            .map_err(|e| anyhow!(e).context("failed to fetch events"))?
+            .map(|msg| {
+                let msg = msg.map_err(|e| anyhow!(e).context("failed to receive event"))?;
+                let sequence = msg
+                    .info()
+                    .map_err(|e| anyhow!(e).context("failed to parse event metadata"))?
+                    .stream_sequence;
+                let event =
+                    serde_json::from_slice(&msg.payload).context("failed to decode event")?;
+                anyhow::Ok(event::HistoricalEvent { sequence, event })
+            })
+            .try_collect()
+            .await?;
+        let buf = serde_json::to_vec(&event::HistoryResponse {
+            events,
+            last_sequence,
+        })
+        .context("failed to encode reply")?;
+        Ok(Some(buf.into()))
+    }
+
     #[instrument(skip(self, _payload))]
     async fn handle_ping_hosts(&self, _payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
         let uptime = self.start_at.elapsed();
@@ -2275,6 +3273,9 @@ impl Host {
             (Some("cmd"), Some(host_id), Some("upd"), None) => {
                 self.handle_update_actor(payload, host_id).await.map(Some)
             }
+            (Some("cmd"), Some(_), Some("profile"), None) => {
+                self.handle_profile_actor(payload).map(Some)
+            }
             (Some("get"), Some(host_id), Some("inv"), None) => {
                 self.handle_inventory(payload, host_id).await.map(Some)
             }
@@ -2282,6 +3283,7 @@ impl Host {
                 self.handle_claims(payload).await.map(Some)
             }
             (Some("get"), Some("links"), None, None) => self.handle_links(payload).await.map(Some),
+            (Some("get"), Some("events"), None, None) => self.handle_events(payload).await,
             (Some("linkdefs"), Some("put"), None, None) => {
                 self.handle_linkdef_put(payload).await.map(Some)
             }
@@ -2359,8 +3361,8 @@ impl Host {
         links.insert(id.to_string(), ld.clone());
         if let Some(actor) = self.actors.write().await.get_mut(actor_id) {
             let mut interfaces = actor.handler.interfaces.write().await;
-            interfaces.insert(
-                contract_id.clone(),
+            interfaces.entry(contract_id.clone()).or_default().insert(
+                link_name.clone(),
                 WasmCloudEntity {
                     link_name: ld.link_name.clone(),
                     contract_id: ld.contract_id.clone(),
@@ -2369,10 +3371,14 @@ impl Host {
             );
         }
 
-        self.publish_event(
-            "linkdef_set",
-            event::linkdef_set(id, actor_id, provider_id, link_name, contract_id, values),
-        )
+        self.publish_event(event::linkdef_set(
+            id,
+            actor_id,
+            provider_id,
+            link_name,
+            contract_id,
+            values,
+        ))
         .await?;
 
         let msgp = rmp_serde::to_vec(ld).context("failed to encode link definition")?;
@@ -2411,11 +3417,24 @@ impl Host {
         } = links
             .remove(id)
             .context("attempt to remove a non-existent link")?;
+        if let Some(actor) = self.actors.read().await.get(actor_id) {
+            let mut interfaces = actor.handler.interfaces.write().await;
+            if let Some(links) = interfaces.get_mut(contract_id) {
+                links.remove(link_name);
+                if links.is_empty() {
+                    interfaces.remove(contract_id);
+                }
+            }
+        }
 
-        self.publish_event(
-            "linkdef_deleted",
-            event::linkdef_deleted(id, actor_id, provider_id, link_name, contract_id, values),
-        )
+        self.publish_event(event::linkdef_deleted(
+            id,
+            actor_id,
+            provider_id,
+            link_name,
+            contract_id,
+            values,
+        ))
         .await?;
 
         // TODO: Broadcast `linkdef_removed`
@@ -194,7 +194,7 @@ impl KeyValue for KvRedisProvider {
     #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.to_string()))]
     async fn get(&self, ctx: Context, arg: String) -> Result<GetResponse, String> {
         let mut cmd = redis::Cmd::get(arg.to_string());
-        let val: Option<String> = self.exec(&ctx, &mut cmd).await?;
+        let val: Option<Vec<u8>> = self.exec(&ctx, &mut cmd).await?;
         let resp = match val {
             Some(s) => GetResponse {
                 exists: true,
@@ -252,7 +252,7 @@ impl KeyValue for KvRedisProvider {
             0 => redis::Cmd::set(&arg.key, &arg.value),
             _ => redis::Cmd::set_ex(&arg.key, &arg.value, arg.expires as usize),
         };
-        let _value: Option<String> = self.exec(&ctx, &mut cmd).await?;
+        let _value: Option<Vec<u8>> = self.exec(&ctx, &mut cmd).await?;
         Ok(())
     }
 
@@ -500,6 +500,9 @@ impl wasmcloud_provider_sdk::Provider for KvRedisProvider {}
 mod test {
     use super::{get_redis_url, KvRedisConfig};
 
+    use serde::Serialize;
+    use wasmcloud_provider_kvredis::wasmcloud_interface_keyvalue::SetRequest;
+
     const PROPER_URL: &str = "redis://127.0.0.1:6379";
 
     #[test]
@@ -550,4 +553,40 @@ mod test {
             PROPER_URL
         );
     }
+
+    #[test]
+    fn can_deserialize_binary_and_string_values() {
+        #[derive(Serialize)]
+        struct StringSetRequest {
+            key: String,
+            value: String,
+            expires: u32,
+        }
+
+        let binary = SetRequest {
+            key: "key".into(),
+            value: vec![0xff, 0x00, 0xfe],
+            expires: 0,
+        };
+        let buf = wasmcloud_provider_sdk::serialize(&binary).unwrap();
+        assert_eq!(
+            wasmcloud_provider_sdk::deserialize::<SetRequest>(&buf).unwrap(),
+            binary
+        );
+
+        let buf = wasmcloud_provider_sdk::serialize(&StringSetRequest {
+            key: "key".into(),
+            value: "value".into(),
+            expires: 42,
+        })
+        .unwrap();
+        assert_eq!(
+            wasmcloud_provider_sdk::deserialize::<SetRequest>(&buf).unwrap(),
+            SetRequest {
+                key: "key".into(),
+                value: "value".into(),
+                expires: 42,
+            }
+        );
+    }
 }
@@ -8,9 +8,11 @@ pub const SMITHY_VERSION: &str = "1.0";
 /// Response to get request
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct GetResponse {
-    /// the value, if it existed
+    /// the value, if it existed.
+    /// Encoded as binary, but string-encoded values are accepted for compatibility
+    #[serde(with = "serde_bytes")]
     #[serde(default)]
-    pub value: String,
+    pub value: Vec<u8>,
     /// whether or not the value existed
     #[serde(default)]
     pub exists: bool,
@@ -90,9 +92,11 @@ pub struct SetRequest {
     /// the key name to change (or create)
     #[serde(default)]
     pub key: String,
-    /// the new value
+    /// the new value.
+    /// Encoded as binary, but string-encoded values are accepted for compatibility
+    #[serde(with = "serde_bytes")]
     #[serde(default)]
-    pub value: String,
+    pub value: Vec<u8>,
     /// expiration time in seconds 0 for no expiration
     #[serde(default)]
     pub expires: u32,
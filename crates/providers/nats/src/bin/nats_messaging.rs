@@ -6,7 +6,7 @@ use std::time::Duration;
 
 use anyhow::Context as _;
 use base64::Engine;
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
 use tokio::task::JoinHandle;
@@ -22,7 +22,7 @@ use wasmcloud_provider_sdk::{
 
 use wasmcloud_provider_nats::otel;
 use wasmcloud_provider_nats::wasmcloud_interface_messaging::{
-    Handler, Messaging, PubMessage, ReplyMessage, RequestMessage, SubMessage,
+    Handler, Messaging, PubMessage, ReplyMessage, RequestMessage, RequestMultiMessage, SubMessage,
 };
 
 const DEFAULT_NATS_URI: &str = "0.0.0.0:4222";
@@ -30,6 +30,8 @@ const ENV_NATS_SUBSCRIPTION: &str = "SUBSCRIPTION";
 const ENV_NATS_URI: &str = "URI";
 const ENV_NATS_CLIENT_JWT: &str = "CLIENT_JWT";
 const ENV_NATS_CLIENT_SEED: &str = "CLIENT_SEED";
+/// Timeout used by `Messaging.RequestMulti` if none is specified by the caller
+const DEFAULT_REQUEST_MULTI_TIMEOUT: Duration = Duration::from_secs(2);
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // handle lattice control messages and forward rpc to the provider dispatch
@@ -463,6 +465,98 @@ impl Messaging for NatsMessagingProvider {
             }),
         }
     }
+
+    #[instrument(level = "debug", skip(self, ctx, msg), fields(actor_id = ?ctx.actor, subject = %msg.subject, max_results = %msg.max_results))]
+    async fn request_multi(
+        &self,
+        ctx: Context,
+        msg: RequestMultiMessage,
+    ) -> Result<Vec<ReplyMessage>, String> {
+        let actor_id = ctx
+            .actor
+            .as_ref()
+            .ok_or_else(|| "no actor in request".to_string())?;
+
+        let nats_client = {
+            let rd = self.actors.read().await;
+            let nats_bundle = rd
+                .get(actor_id)
+                .ok_or_else(|| format!("actor not linked:{}", actor_id))?;
+            nats_bundle.client.clone()
+        }; // early release of actor-client map
+
+        // Subscribe to a temporary inbox before publishing to not miss any replies
+        let inbox = nats_client.new_inbox();
+        let mut sub = nats_client
+            .subscribe(inbox.clone())
+            .await
+            .map_err(|e| format!("nats subscribe error: {}", e))?;
+
+        let res = if should_strip_headers(&msg.subject) {
+            nats_client
+                .publish_with_reply(msg.subject.to_string(), inbox, msg.body.into())
+                .await
+        } else {
+            let headers = otel::OtelHeaderInjector::default_with_span().into();
+            nats_client
+                .publish_with_reply_and_headers(
+                    msg.subject.to_string(),
+                    inbox,
+                    headers,
+                    msg.body.into(),
+                )
+                .await
+        };
+        res.map_err(|e| format!("nats send error: {}", e))?;
+        nats_client
+            .flush()
+            .await
+            .map_err(|e| format!("nats flush error: {}", e))?;
+
+        let timeout = match msg.timeout_ms {
+            0 => DEFAULT_REQUEST_MULTI_TIMEOUT,
+            ms => Duration::from_millis(ms.into()),
+        };
+        // NOTE: `0` collects a single reply, see `RequestMultiMessage::max_results`
+        let max_results = match msg.max_results {
+            0 => 1,
+            n => n as usize,
+        };
+        let replies = collect_replies(
+            sub.by_ref().map(|resp| ReplyMessage {
+                body: resp.payload.to_vec(),
+                reply_to: resp.reply,
+                subject: resp.subject,
+            }),
+            timeout,
+            max_results,
+        )
+        .await;
+        if let Err(e) = sub.unsubscribe().await {
+            warn!("failed to unsubscribe from temporary inbox: {e}");
+        }
+        Ok(replies)
+    }
+}
+
+/// Collects `replies` until either `timeout` elapses or `max_results` replies are received
+async fn collect_replies(
+    mut replies: impl Stream<Item = ReplyMessage> + Unpin,
+    timeout: Duration,
+    max_results: usize,
+) -> Vec<ReplyMessage> {
+    let mut collected = vec![];
+    // Elapsed timeout simply terminates the collection
+    let _ = tokio::time::timeout(timeout, async {
+        while collected.len() < max_results {
+            let Some(reply) = replies.next().await else {
+                break;
+            };
+            collected.push(reply);
+        }
+    })
+    .await;
+    collected
 }
 
 // In the current version of the NATS server, using headers on certain $SYS.REQ topics will cause server-side
@@ -498,6 +592,15 @@ impl wasmcloud_provider_sdk::MessageDispatch for NatsMessagingProvider {
                 })?;
                 Ok(::wasmcloud_provider_sdk::serialize(&result)?)
             }
+            "Messaging.RequestMulti" => {
+                let input: RequestMultiMessage = ::wasmcloud_provider_sdk::deserialize(&body)?;
+                let result = self.request_multi(ctx, input).await.map_err(|e| {
+                    ::wasmcloud_provider_sdk::error::ProviderInvocationError::Provider(
+                        e.to_string(),
+                    )
+                })?;
+                Ok(::wasmcloud_provider_sdk::serialize(&result)?)
+            }
             _ => Err(
                 ::wasmcloud_provider_sdk::error::InvocationError::Malformed(format!(
                     "Invalid method name {method}",
@@ -512,7 +615,14 @@ impl wasmcloud_provider_sdk::Provider for NatsMessagingProvider {}
 
 #[cfg(test)]
 mod test {
-    use crate::{generate_provider, ConnectionConfig, NatsMessagingProvider};
+    use std::time::Duration;
+
+    use futures::{stream, StreamExt};
+
+    use crate::{collect_replies, generate_provider, ConnectionConfig, NatsMessagingProvider};
+    use wasmcloud_provider_nats::wasmcloud_interface_messaging::{
+        ReplyMessage, RequestMultiMessage,
+    };
     use wasmcloud_provider_sdk::{
         core::{HostData, LinkDefinition},
         ProviderHandler,
@@ -573,6 +683,52 @@ mod test {
         assert_eq!(cc3.auth_jwt, Some("jawty".to_string()))
     }
 
+    fn reply(body: &str) -> ReplyMessage {
+        ReplyMessage {
+            body: body.into(),
+            reply_to: None,
+            subject: "inbox".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_replies() {
+        // all replies are collected until the timeout elapses
+        let replies = stream::iter([reply("a"), reply("b"), reply("c")]).chain(stream::pending());
+        let collected = collect_replies(replies, Duration::from_millis(100), usize::MAX).await;
+        assert_eq!(collected, [reply("a"), reply("b"), reply("c")]);
+
+        // collection stops at the timeout, even if more replies arrive afterwards
+        let replies = stream::iter([reply("a")])
+            .chain(stream::once(async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                reply("b")
+            }))
+            .chain(stream::pending());
+        let collected = collect_replies(replies, Duration::from_millis(100), usize::MAX).await;
+        assert_eq!(collected, [reply("a")]);
+
+        // collection stops as soon as `max_results` replies are received
+        let replies = stream::iter([reply("a"), reply("b"), reply("c")]).chain(stream::pending());
+        let collected = tokio::time::timeout(
+            Duration::from_secs(10),
+            collect_replies(replies, Duration::from_secs(3600), 2),
+        )
+        .await
+        .expect("collection did not stop at `max_results`");
+        assert_eq!(collected, [reply("a"), reply("b")]);
+    }
+
+    #[test]
+    fn test_request_multi_message_deserialize() {
+        let input = r#"{"subject":"test","body":[1,2,3],"timeoutMs":100}"#;
+        let msg: RequestMultiMessage = serde_json::from_str(input).unwrap();
+        assert_eq!(msg.subject, "test");
+        assert_eq!(msg.body, [1, 2, 3]);
+        assert_eq!(msg.timeout_ms, 100);
+        assert_eq!(msg.max_results, 0);
+    }
+
     /// Ensure that unlink triggers subscription removal
     /// https://github.com/wasmCloud/capability-providers/issues/196
     ///
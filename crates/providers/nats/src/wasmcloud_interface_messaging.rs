@@ -54,6 +54,27 @@ pub struct RequestMessage {
     pub timeout_ms: u32,
 }
 
+/// Message sent as part of a request expecting multiple replies, with timeout
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RequestMultiMessage {
+    /// The subject, or topic, of the message
+    #[serde(default)]
+    pub subject: String,
+    /// The message payload
+    #[serde(with = "serde_bytes")]
+    #[serde(default)]
+    pub body: Vec<u8>,
+    /// A timeout, in milliseconds. If 0, the provider chooses when to stop collecting replies
+    #[serde(rename = "timeoutMs")]
+    #[serde(default)]
+    pub timeout_ms: u32,
+    /// Maximum number of replies to collect before the timeout elapses. If 0, a single reply is
+    /// collected, same as for 1
+    #[serde(rename = "maxResults")]
+    #[serde(default)]
+    pub max_results: u32,
+}
+
 /// Message received as part of a subscription
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct SubMessage {
@@ -126,4 +147,12 @@ pub trait Messaging {
     /// Request - send a message in a request/reply pattern,
     /// waiting for a response.
     async fn request(&self, ctx: Context, arg: RequestMessage) -> Result<ReplyMessage, String>;
+    /// RequestMulti - send a message in a request/reply pattern,
+    /// collecting replies until either the timeout elapses or
+    /// the maximum number of replies is received.
+    async fn request_multi(
+        &self,
+        ctx: Context,
+        arg: RequestMultiMessage,
+    ) -> Result<Vec<ReplyMessage>, String>;
 }
@@ -1,13 +1,32 @@
-use super::{Ctx, Instance};
+use super::{Ctx, Instance, InterfaceBindings, InterfaceInstance, TableResult};
 
 use crate::capability::blobstore::{consumer, types};
-use crate::capability::Blobstore;
+use crate::capability::compat::{self, BLOBSTORE_RECEIVE_CHUNK};
+use crate::capability::{builtin, Blobstore, BlobstoreHandler};
 
+use std::io::Cursor;
 use std::sync::Arc;
 
-use anyhow::bail;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
-use tracing::instrument;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{instrument, trace, warn};
+use wasmtime_wasi::preview2;
+
+/// Capacity of the in-memory pipe used to stream chunks of objects being uploaded
+const UPLOAD_PIPE_CAPACITY: usize = 64 * 1024;
+
+pub mod blobstore_handler_bindings {
+    wasmtime::component::bindgen!({
+        world: "blobstore-handler",
+        async: true,
+        with: {
+           "wasmcloud:blobstore/types": crate::capability::blobstore::types,
+        },
+    });
+}
 
 impl Instance {
     /// Set [`Blobstore`] handler for this [Instance].
@@ -15,17 +34,122 @@ impl Instance {
         self.handler_mut().replace_blobstore(blobstore);
         self
     }
+
+    /// Instantiates and returns an [`InterfaceInstance<blobstore_handler_bindings::BlobstoreHandler>`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if blobstore handler bindings are not exported by the [`Instance`]
+    pub async fn into_blobstore_handler(
+        mut self,
+    ) -> anyhow::Result<InterfaceInstance<blobstore_handler_bindings::BlobstoreHandler>> {
+        let bindings = if let Ok((bindings, _)) =
+            blobstore_handler_bindings::BlobstoreHandler::instantiate_async(
+                &mut self.store,
+                &self.component,
+                &self.linker,
+            )
+            .await
+        {
+            InterfaceBindings::Interface(bindings)
+        } else {
+            self.as_guest_bindings()
+                .await
+                .map(InterfaceBindings::Guest)
+                .context("failed to instantiate `wasmcloud:blobstore/handler` interface")?
+        };
+        Ok(InterfaceInstance {
+            store: Arc::new(Mutex::new(self.store)),
+            bindings: Arc::new(bindings),
+        })
+    }
+}
+
+/// An object upload started by `put-object`, which expects more chunks via `put-chunk`.
+/// Uploads, which are neither finished nor cancelled by the time the actor instance is dropped,
+/// are aborted and the object is removed.
+struct ObjectUpload {
+    container: String,
+    object: String,
+    offset: u64,
+    value: Option<DuplexStream>,
+    task: Option<JoinHandle<anyhow::Result<()>>>,
+    handler: builtin::Handler,
+}
+
+impl ObjectUpload {
+    /// Close the object stream and wait for the upload to complete
+    async fn finish(mut self) -> anyhow::Result<()> {
+        drop(self.value.take());
+        let task = self
+            .task
+            .take()
+            .context("object upload already completed")?;
+        task.await.context("object upload task failed")?
+    }
+
+    /// Abort the upload and remove the object
+    async fn cancel(mut self) -> anyhow::Result<bool> {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.handler
+            .remove_object(&self.container, self.object.clone())
+            .await
+    }
+}
+
+impl Drop for ObjectUpload {
+    fn drop(&mut self) {
+        let Some(task) = self.task.take() else { return };
+        task.abort();
+        let Ok(rt) = tokio::runtime::Handle::try_current() else {
+            warn!(
+                container = self.container,
+                object = self.object,
+                "no runtime available, unfinished object upload not removed"
+            );
+            return;
+        };
+        let handler = self.handler.clone();
+        let container = self.container.clone();
+        let object = self.object.clone();
+        rt.spawn(async move {
+            if let Err(err) = handler.remove_object(&container, object).await {
+                warn!(?err, "failed to remove unfinished object upload");
+            }
+        });
+    }
+}
+
+trait TableBlobstoreExt {
+    fn push_object_upload(&mut self, upload: ObjectUpload) -> TableResult<u32>;
+    fn get_object_upload_mut(&mut self, upload: u32) -> TableResult<&mut ObjectUpload>;
+    fn delete_object_upload(&mut self, upload: u32) -> TableResult<ObjectUpload>;
+}
+
+impl TableBlobstoreExt for preview2::Table {
+    fn push_object_upload(&mut self, upload: ObjectUpload) -> TableResult<u32> {
+        self.push(Box::new(upload))
+    }
+
+    fn get_object_upload_mut(&mut self, upload: u32) -> TableResult<&mut ObjectUpload> {
+        self.get_mut(upload)
+    }
+
+    fn delete_object_upload(&mut self, upload: u32) -> TableResult<ObjectUpload> {
+        self.delete(upload)
+    }
 }
 
 #[async_trait]
 impl types::Host for Ctx {}
 
-#[allow(unused)] // TODO: Implement and remove
 #[async_trait]
 impl consumer::Host for Ctx {
     #[instrument]
     async fn container_exists(&mut self, container_id: String) -> anyhow::Result<bool> {
-        bail!("unsupported")
+        self.handler.container_exists(&container_id).await
     }
 
     #[instrument]
@@ -33,7 +157,10 @@ impl consumer::Host for Ctx {
         &mut self,
         container_id: String,
     ) -> anyhow::Result<Result<(), String>> {
-        bail!("unsupported")
+        match self.handler.create_container(&container_id).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
     }
 
     #[instrument]
@@ -41,7 +168,10 @@ impl consumer::Host for Ctx {
         &mut self,
         container_id: String,
     ) -> anyhow::Result<Result<(), String>> {
-        bail!("unsupported")
+        match self.handler.remove_container(&container_id).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
     }
 
     #[instrument]
@@ -49,7 +179,10 @@ impl consumer::Host for Ctx {
         &mut self,
         container_id: String,
     ) -> anyhow::Result<Result<Option<types::ContainerInfo>, String>> {
-        bail!("unsupported")
+        match self.handler.get_container_info(&container_id).await {
+            Ok(info) => Ok(Ok(info)),
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
     }
 
     #[instrument]
@@ -58,7 +191,10 @@ impl consumer::Host for Ctx {
         container_id: String,
         object_id: String,
     ) -> anyhow::Result<Result<Option<types::ObjectInfo>, String>> {
-        bail!("unsupported")
+        match self.handler.get_object_info(&container_id, object_id).await {
+            Ok(info) => Ok(Ok(info)),
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
     }
 
     #[instrument]
@@ -67,27 +203,163 @@ impl consumer::Host for Ctx {
         container_id: String,
         object_id: String,
     ) -> anyhow::Result<Result<bool, String>> {
-        bail!("unsupported")
+        match self.handler.remove_object(&container_id, object_id).await {
+            Ok(removed) => Ok(Ok(removed)),
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
     }
 
-    #[instrument]
+    #[instrument(skip(chunk))]
     async fn put_object(
         &mut self,
         chunk: types::Chunk,
         content_type: String,
         content_encoding: String,
     ) -> anyhow::Result<Result<String, String>> {
-        bail!("unsupported")
+        let types::Chunk {
+            object_id,
+            container_id,
+            bytes,
+            offset,
+            is_last,
+        } = chunk;
+        if offset != 0 {
+            return Ok(Err(format!(
+                "first chunk of an object must start at offset 0, got {offset}"
+            )));
+        }
+        let content_type = (!content_type.is_empty()).then_some(content_type);
+        let content_encoding = (!content_encoding.is_empty()).then_some(content_encoding);
+        if is_last {
+            return match self
+                .handler
+                .put_object(
+                    &container_id,
+                    object_id,
+                    Box::new(Cursor::new(bytes)),
+                    content_type,
+                    content_encoding,
+                )
+                .await
+            {
+                Ok(()) => Ok(Ok(String::new())),
+                Err(err) => Ok(Err(format!("{err:#}"))),
+            };
+        }
+
+        let (mut value, rx) = tokio::io::duplex(UPLOAD_PIPE_CAPACITY);
+        let task = tokio::spawn({
+            let handler = self.handler.clone();
+            let container_id = container_id.clone();
+            let object_id = object_id.clone();
+            async move {
+                handler
+                    .put_object(
+                        &container_id,
+                        object_id,
+                        Box::new(rx),
+                        content_type,
+                        content_encoding,
+                    )
+                    .await
+            }
+        });
+        if let Err(err) = value.write_all(&bytes).await {
+            drop(value);
+            return match task.await.context("object upload task failed")? {
+                Ok(()) => Ok(Err(format!("failed to write chunk: {err}"))),
+                Err(err) => Ok(Err(format!("{err:#}"))),
+            };
+        }
+        let offset = bytes
+            .len()
+            .try_into()
+            .context("chunk size does not fit in `u64`")?;
+        let upload = self
+            .table
+            .push_object_upload(ObjectUpload {
+                container: container_id,
+                object: object_id,
+                offset,
+                value: Some(value),
+                task: Some(task),
+                handler: self.handler.clone(),
+            })
+            .context("failed to push object upload to table")?;
+        Ok(Ok(upload.to_string()))
     }
 
-    #[instrument]
+    #[instrument(skip(chunk))]
     async fn put_chunk(
         &mut self,
         stream_id: String,
         chunk: types::Chunk,
         cancel: bool,
     ) -> anyhow::Result<Result<(), String>> {
-        bail!("unsupported")
+        let types::Chunk {
+            object_id,
+            container_id,
+            bytes,
+            offset,
+            is_last,
+        } = chunk;
+        let Ok(id) = stream_id.parse() else {
+            return Ok(Err(format!("invalid stream ID `{stream_id}`")))
+        };
+        if cancel {
+            let Ok(upload) = self.table.delete_object_upload(id) else {
+                return Ok(Err(format!("unknown stream ID `{stream_id}`")))
+            };
+            return match upload.cancel().await {
+                Ok(_) => Ok(Ok(())),
+                Err(err) => Ok(Err(format!("{err:#}"))),
+            };
+        }
+
+        let Ok(upload) = self.table.get_object_upload_mut(id) else {
+            return Ok(Err(format!("unknown stream ID `{stream_id}`")))
+        };
+        if upload.container != container_id || upload.object != object_id {
+            return Ok(Err(format!(
+                "stream `{stream_id}` does not belong to object `{object_id}` in container `{container_id}`"
+            )));
+        }
+        if upload.offset != offset {
+            return Ok(Err(format!(
+                "unexpected chunk offset {offset}, expected {}",
+                upload.offset
+            )));
+        }
+        let Some(value) = upload.value.as_mut() else {
+            return Ok(Err(format!("stream `{stream_id}` is already closed")))
+        };
+        if let Err(err) = value.write_all(&bytes).await {
+            warn!(?err, "failed to write chunk, aborting upload");
+            let upload = self
+                .table
+                .delete_object_upload(id)
+                .context("failed to delete object upload")?;
+            return match upload.finish().await {
+                Ok(()) => Ok(Err(format!("failed to write chunk: {err}"))),
+                Err(err) => Ok(Err(format!("{err:#}"))),
+            };
+        }
+        let n: u64 = bytes
+            .len()
+            .try_into()
+            .context("chunk size does not fit in `u64`")?;
+        upload.offset += n;
+        if !is_last {
+            return Ok(Ok(()));
+        }
+        let upload = self
+            .table
+            .delete_object_upload(id)
+            .context("failed to delete object upload")?;
+        match upload.finish().await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
     }
 
     #[instrument]
@@ -96,6 +368,56 @@ impl consumer::Host for Ctx {
         container_id: String,
         object_id: String,
     ) -> anyhow::Result<Result<(), String>> {
-        bail!("unsupported")
+        match self.handler.stream_object(&container_id, object_id).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
+    }
+}
+
+#[async_trait]
+impl BlobstoreHandler for InterfaceInstance<blobstore_handler_bindings::BlobstoreHandler> {
+    #[instrument(skip(self, chunk))]
+    async fn receive_chunk(&self, chunk: types::Chunk) -> anyhow::Result<bool> {
+        let mut store = self.store.lock().await;
+        match self.bindings.as_ref() {
+            InterfaceBindings::Guest(guest) => {
+                let request = rmp_serde::to_vec_named(&compat::Chunk::from(chunk))
+                    .context("failed to encode request")?;
+                let (response_tx, mut response_rx) = tokio::io::duplex(UPLOAD_PIPE_CAPACITY);
+                let mut response = vec![];
+                let (res, _) = tokio::try_join!(
+                    async {
+                        guest
+                            .call(
+                                &mut store,
+                                BLOBSTORE_RECEIVE_CHUNK,
+                                Cursor::new(request),
+                                response_tx,
+                            )
+                            .await
+                            .context("failed to call actor")
+                    },
+                    async {
+                        response_rx
+                            .read_to_end(&mut response)
+                            .await
+                            .context("failed to read response")
+                    },
+                )?;
+                res.map_err(|e| anyhow!(e))?;
+                let compat::ChunkResponse { cancel_download } =
+                    rmp_serde::from_slice(&response).context("failed to decode response")?;
+                Ok(cancel_download)
+            }
+            InterfaceBindings::Interface(bindings) => {
+                trace!("call `wasmcloud:blobstore/handler.receive-chunk`");
+                bindings
+                    .wasmcloud_blobstore_handler()
+                    .call_receive_chunk(&mut *store, &chunk)
+                    .await?
+                    .map_err(|e| anyhow!(e))
+            }
+        }
     }
 }
@@ -3,18 +3,19 @@ use super::{AsyncStream, Ctx, Instance, TableResult};
 use crate::capability::bus::host;
 use crate::capability::Bus;
 
+use core::any::Any;
 use core::future::Future;
 use core::pin::Pin;
 
 use std::sync::Arc;
 
-use anyhow::{bail, Context as _};
+use anyhow::Context as _;
 use async_trait::async_trait;
 use futures::future::Shared;
 use futures::FutureExt;
 use tracing::instrument;
-use wasmtime_wasi::preview2;
 use wasmtime_wasi::preview2::stream::TableStreamExt;
+use wasmtime_wasi::preview2::{self, HostPollable, PollableFuture, TablePollableExt};
 
 impl Instance {
     /// Set [`Bus`] handler for this [Instance].
@@ -28,22 +29,33 @@ type FutureResult = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
 
 trait TableFutureResultExt {
     fn push_future_result(&mut self, res: FutureResult) -> TableResult<u32>;
-    fn get_future_result(&mut self, res: u32) -> TableResult<Box<Shared<FutureResult>>>;
-    fn delete_future_result(&mut self, res: u32) -> TableResult<Box<Shared<FutureResult>>>;
+    fn get_future_result(&mut self, res: u32) -> TableResult<Shared<FutureResult>>;
+    fn delete_future_result(&mut self, res: u32) -> TableResult<Shared<FutureResult>>;
 }
 
 impl TableFutureResultExt for preview2::Table {
     fn push_future_result(&mut self, res: FutureResult) -> TableResult<u32> {
         self.push(Box::new(res.shared()))
     }
-    fn get_future_result(&mut self, res: u32) -> TableResult<Box<Shared<FutureResult>>> {
+    fn get_future_result(&mut self, res: u32) -> TableResult<Shared<FutureResult>> {
         self.get(res).cloned()
     }
-    fn delete_future_result(&mut self, res: u32) -> TableResult<Box<Shared<FutureResult>>> {
+    fn delete_future_result(&mut self, res: u32) -> TableResult<Shared<FutureResult>> {
         self.delete(res)
     }
 }
 
+/// Constructs a future, which resolves once the bus call result is available
+fn await_future_result(res: &mut dyn Any) -> PollableFuture<'_> {
+    let res = res.downcast_mut::<Shared<FutureResult>>();
+    Box::pin(async move {
+        let res = res.context("invalid future result")?;
+        // NOTE: The result is retained by `Shared` and returned by `future-result-get`
+        res.clone().await;
+        Ok(())
+    })
+}
+
 #[async_trait]
 impl host::Host for Ctx {
     #[instrument]
@@ -62,9 +74,14 @@ impl host::Host for Ctx {
     > {
         match self.handler.call(operation).await {
             Ok((result, stdin, stdout)) => {
+                // NOTE: The result is driven by a separate task, so that concurrent calls make progress
+                // independent of the guest polling them
+                let result = tokio::spawn(result).map(|res| {
+                    res.unwrap_or_else(|err| Err(format!("bus call task failed: {err}")))
+                });
                 let result = self
                     .table
-                    .push_future_result(result)
+                    .push_future_result(Box::pin(result))
                     .context("failed to push result to table")?;
                 let stdin = self
                     .table
@@ -74,15 +91,22 @@ impl host::Host for Ctx {
                     .table
                     .push_input_stream(Box::new(AsyncStream(stdout)))
                     .context("failed to push stdout stream")?;
-                Ok(Ok((result, stdin, stdout)))
+                // NOTE: The order matches `wasmcloud:bus/host.call`, which returns the `input-stream`
+                // of the callee's stdout before the `output-stream` of its stdin
+                Ok(Ok((result, stdout, stdin)))
             }
             Err(err) => Ok(Err(format!("{err:#}"))),
         }
     }
 
     #[instrument]
-    async fn listen_to_future_result(&mut self, _res: u32) -> anyhow::Result<u32> {
-        bail!("unsupported") // TODO: Support
+    async fn listen_to_future_result(&mut self, res: u32) -> anyhow::Result<u32> {
+        self.table
+            .push_host_pollable(HostPollable::TableEntry {
+                index: res,
+                make_future: await_future_result,
+            })
+            .context("failed to push pollable to table")
     }
 
     #[instrument]
@@ -1,22 +1,48 @@
-use super::{Instance, InterfaceBindings, InterfaceInstance};
+use super::{AsyncStream, Ctx, Instance, InterfaceBindings, InterfaceInstance, TableResult};
 
-use crate::capability::IncomingHttp;
+use crate::capability::compat::{HttpRequest, HttpResponse, HTTP_SERVER_HANDLE_REQUEST};
+use crate::capability::http::{outgoing_handler, types};
+use crate::capability::{HttpTrailers, IncomingHttp, OutgoingHttp};
 
+use core::any::Any;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+
+use std::io::Cursor;
 use std::sync::Arc;
 
-use anyhow::{bail, Context as _};
+use anyhow::{anyhow, bail, Context as _};
 use async_trait::async_trait;
-use tokio::io::AsyncRead;
-use tokio::sync::Mutex;
-use tracing::instrument;
+use futures::{try_join, FutureExt};
+use tokio::io::{AsyncRead, AsyncReadExt, DuplexStream, ReadBuf};
+use tokio::sync::oneshot::error::TryRecvError;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::task::{JoinError, JoinHandle};
+use tracing::{instrument, trace, warn};
+use wasmtime_wasi::preview2::stream::TableStreamExt;
+use wasmtime_wasi::preview2::{
+    self, HostPollable, InputStream, OutputStream, PollableFuture, TablePollableExt,
+};
 
 pub mod incoming_http_bindings {
     wasmtime::component::bindgen!({
         world: "incoming-http",
         async: true,
+        with: {
+           "wasi:http/types": crate::capability::http::types,
+           "wasi:io/streams": wasmtime_wasi::preview2::wasi::io::streams,
+           "wasi:poll/poll": wasmtime_wasi::preview2::wasi::poll::poll,
+        },
     });
 }
 
+/// Capacity of the in-memory pipe used to stream outgoing response bodies
+const BODY_PIPE_CAPACITY: usize = 64 * 1024;
+
+type Body = Box<dyn AsyncRead + Sync + Send + Unpin>;
+
+type ResponseSender = oneshot::Sender<anyhow::Result<http::Response<Body>>>;
+
 impl Instance {
     /// Set [`IncomingHttp`] handler for this [Instance].
     pub fn incoming_http(
@@ -27,6 +53,15 @@ impl Instance {
         self
     }
 
+    /// Set [`OutgoingHttp`] handler for this [Instance].
+    pub fn outgoing_http(
+        &mut self,
+        outgoing_http: Arc<dyn OutgoingHttp + Send + Sync>,
+    ) -> &mut Self {
+        self.handler_mut().replace_outgoing_http(outgoing_http);
+        self
+    }
+
     /// Instantiates and returns a [`InterfaceInstance<incoming_http_bindings::IncomingHttp>`] if exported by the [`Instance`].
     ///
     /// # Errors
@@ -51,33 +86,1359 @@ impl Instance {
                 .context("failed to instantiate `wasi:http/incoming-handler` interface")?
         };
         Ok(InterfaceInstance {
-            store: Mutex::new(self.store),
-            bindings,
+            store: Arc::new(Mutex::new(self.store)),
+            bindings: Arc::new(bindings),
         })
     }
 }
 
+/// Request received by the host and passed to the guest
+struct IncomingRequest {
+    method: http::Method,
+    uri: http::Uri,
+    headers: types::Headers,
+    body: Option<Body>,
+    trailers: Option<HttpTrailers>,
+}
+
+/// Request constructed by the guest
+struct OutgoingRequest {
+    method: http::Method,
+    uri: http::Uri,
+    headers: types::Headers,
+    body: Option<(ChannelWriter, oneshot::Sender<http::HeaderMap>)>,
+    request_body: ChannelReader,
+    request_trailers: HttpTrailers,
+}
+
+/// Response received by the host and passed to the guest
+struct IncomingResponse {
+    status: http::StatusCode,
+    headers: types::Headers,
+    body: Option<Body>,
+    trailers: Option<HttpTrailers>,
+}
+
+/// Response to an outgoing request, which is handled asynchronously
+enum FutureIncomingResponse {
+    Pending(JoinHandle<Result<http::Response<Body>, types::Error>>),
+    Received(Option<Result<http::Response<Body>, types::Error>>),
+    Ready(Result<types::IncomingResponse, types::Error>),
+}
+
+/// Response constructed by the guest
+struct OutgoingResponse {
+    status: http::StatusCode,
+    headers: types::Headers,
+    body: Option<(DuplexStream, oneshot::Sender<http::HeaderMap>)>,
+    response_body: DuplexStream,
+    response_trailers: HttpTrailers,
+}
+
+/// Outparam the guest sets the response on
+struct ResponseOutparam(ResponseSender);
+
+/// Trailers of an incoming body, which are received asynchronously once the body is consumed
+enum FutureTrailers {
+    Pending(HttpTrailers),
+    Ready(Option<http::HeaderMap>),
+}
+
+/// Result of sending trailers of an outgoing body
+struct FutureWriteTrailersResult(Option<types::Error>);
+
+/// Incoming body stream, which carries trailers to be returned by `finish-incoming-stream`
+struct IncomingBody {
+    body: AsyncStream<Body>,
+    trailers: std::sync::Mutex<Option<HttpTrailers>>,
+}
+
+impl IncomingBody {
+    fn new(body: Body, trailers: Option<HttpTrailers>) -> Self {
+        Self {
+            body: AsyncStream(body),
+            trailers: std::sync::Mutex::new(trailers),
+        }
+    }
+}
+
 #[async_trait]
-impl IncomingHttp for InterfaceInstance<incoming_http_bindings::IncomingHttp> {
-    #[allow(unused)] // TODO: Remove
-    #[instrument(skip_all)]
-    async fn handle(
+impl InputStream for IncomingBody {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[instrument(skip(self))]
+    async fn read(&mut self, buf: &mut [u8]) -> anyhow::Result<(u64, bool)> {
+        self.body.read(buf).await
+    }
+
+    #[instrument(skip(self))]
+    async fn read_vectored<'a>(
+        &mut self,
+        bufs: &mut [std::io::IoSliceMut<'a>],
+    ) -> anyhow::Result<(u64, bool)> {
+        self.body.read_vectored(bufs).await
+    }
+
+    fn is_read_vectored(&self) -> bool {
+        self.body.is_read_vectored()
+    }
+
+    async fn readable(&self) -> anyhow::Result<()> {
+        self.body.readable().await
+    }
+}
+
+/// Outgoing body stream, which carries the sender of trailers for `finish-outgoing-stream-with-trailers`
+struct OutgoingBody {
+    body: Box<dyn OutputStream>,
+    trailers: std::sync::Mutex<Option<oneshot::Sender<http::HeaderMap>>>,
+}
+
+impl OutgoingBody {
+    fn new(body: Box<dyn OutputStream>, trailers: oneshot::Sender<http::HeaderMap>) -> Self {
+        Self {
+            body,
+            trailers: std::sync::Mutex::new(Some(trailers)),
+        }
+    }
+}
+
+#[async_trait]
+impl OutputStream for OutgoingBody {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[instrument(skip(self))]
+    async fn write(&mut self, buf: &[u8]) -> anyhow::Result<u64> {
+        self.body.write(buf).await
+    }
+
+    #[instrument(skip(self))]
+    async fn write_vectored<'a>(&mut self, bufs: &[std::io::IoSlice<'a>]) -> anyhow::Result<u64> {
+        self.body.write_vectored(bufs).await
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.body.is_write_vectored()
+    }
+
+    async fn writable(&self) -> anyhow::Result<()> {
+        self.body.writable().await
+    }
+}
+
+/// Writing end of an unbounded in-memory pipe used for outgoing request bodies.
+/// The pipe is unbounded, since the guest may write the whole body before the request is sent.
+struct ChannelWriter(mpsc::UnboundedSender<Vec<u8>>);
+
+#[async_trait]
+impl OutputStream for ChannelWriter {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[instrument(skip(self))]
+    async fn write(&mut self, buf: &[u8]) -> anyhow::Result<u64> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| anyhow!("body receiver dropped"))?;
+        let n = buf.len().try_into().context("overflow")?;
+        Ok(n)
+    }
+
+    async fn writable(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reading end of an unbounded in-memory pipe used for outgoing request bodies
+struct ChannelReader {
+    rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    buf: Vec<u8>,
+}
+
+impl AsyncRead for ChannelReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let Self { rx, buf: chunk } = self.get_mut();
+        while chunk.is_empty() {
+            match ready!(rx.poll_recv(cx)) {
+                Some(next) => *chunk = next,
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+        let n = chunk.len().min(buf.remaining());
+        buf.put_slice(&chunk[..n]);
+        chunk.drain(..n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn channel_pipe() -> (ChannelWriter, ChannelReader) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (
+        ChannelWriter(tx),
+        ChannelReader {
+            rx,
+            buf: Vec::default(),
+        },
+    )
+}
+
+trait TableHttpExt {
+    fn push_fields(&mut self, fields: http::HeaderMap) -> TableResult<u32>;
+    fn get_fields(&self, fields: u32) -> TableResult<&http::HeaderMap>;
+    fn get_fields_mut(&mut self, fields: u32) -> TableResult<&mut http::HeaderMap>;
+    fn delete_fields(&mut self, fields: u32) -> TableResult<http::HeaderMap>;
+
+    fn push_incoming_request(&mut self, request: IncomingRequest) -> TableResult<u32>;
+    fn get_incoming_request(&self, request: u32) -> TableResult<&IncomingRequest>;
+    fn get_incoming_request_mut(&mut self, request: u32) -> TableResult<&mut IncomingRequest>;
+    fn delete_incoming_request(&mut self, request: u32) -> TableResult<IncomingRequest>;
+
+    fn push_outgoing_request(&mut self, request: OutgoingRequest) -> TableResult<u32>;
+    fn get_outgoing_request_mut(&mut self, request: u32) -> TableResult<&mut OutgoingRequest>;
+    fn delete_outgoing_request(&mut self, request: u32) -> TableResult<OutgoingRequest>;
+
+    fn push_incoming_response(&mut self, response: IncomingResponse) -> TableResult<u32>;
+    fn get_incoming_response(&self, response: u32) -> TableResult<&IncomingResponse>;
+    fn get_incoming_response_mut(&mut self, response: u32) -> TableResult<&mut IncomingResponse>;
+    fn delete_incoming_response(&mut self, response: u32) -> TableResult<IncomingResponse>;
+
+    fn push_future_incoming_response(
+        &mut self,
+        response: FutureIncomingResponse,
+    ) -> TableResult<u32>;
+    fn get_future_incoming_response_mut(
+        &mut self,
+        response: u32,
+    ) -> TableResult<&mut FutureIncomingResponse>;
+    fn delete_future_incoming_response(
+        &mut self,
+        response: u32,
+    ) -> TableResult<FutureIncomingResponse>;
+
+    fn push_outgoing_response(&mut self, response: OutgoingResponse) -> TableResult<u32>;
+    fn get_outgoing_response_mut(&mut self, response: u32) -> TableResult<&mut OutgoingResponse>;
+    fn delete_outgoing_response(&mut self, response: u32) -> TableResult<OutgoingResponse>;
+
+    fn push_response_outparam(&mut self, outparam: ResponseOutparam) -> TableResult<u32>;
+    fn delete_response_outparam(&mut self, outparam: u32) -> TableResult<ResponseOutparam>;
+
+    fn push_future_trailers(&mut self, trailers: FutureTrailers) -> TableResult<u32>;
+    fn get_future_trailers_mut(&mut self, trailers: u32) -> TableResult<&mut FutureTrailers>;
+    fn delete_future_trailers(&mut self, trailers: u32) -> TableResult<FutureTrailers>;
+
+    fn push_future_write_trailers_result(
+        &mut self,
+        res: FutureWriteTrailersResult,
+    ) -> TableResult<u32>;
+    fn get_future_write_trailers_result(&self, res: u32)
+        -> TableResult<&FutureWriteTrailersResult>;
+    fn delete_future_write_trailers_result(
+        &mut self,
+        res: u32,
+    ) -> TableResult<FutureWriteTrailersResult>;
+}
+
+impl TableHttpExt for preview2::Table {
+    fn push_fields(&mut self, fields: http::HeaderMap) -> TableResult<u32> {
+        self.push(Box::new(fields))
+    }
+
+    fn get_fields(&self, fields: u32) -> TableResult<&http::HeaderMap> {
+        self.get(fields)
+    }
+
+    fn get_fields_mut(&mut self, fields: u32) -> TableResult<&mut http::HeaderMap> {
+        self.get_mut(fields)
+    }
+
+    fn delete_fields(&mut self, fields: u32) -> TableResult<http::HeaderMap> {
+        self.delete(fields)
+    }
+
+    fn push_incoming_request(&mut self, request: IncomingRequest) -> TableResult<u32> {
+        self.push(Box::new(request))
+    }
+
+    fn get_incoming_request(&self, request: u32) -> TableResult<&IncomingRequest> {
+        self.get(request)
+    }
+
+    fn get_incoming_request_mut(&mut self, request: u32) -> TableResult<&mut IncomingRequest> {
+        self.get_mut(request)
+    }
+
+    fn delete_incoming_request(&mut self, request: u32) -> TableResult<IncomingRequest> {
+        self.delete(request)
+    }
+
+    fn push_outgoing_request(&mut self, request: OutgoingRequest) -> TableResult<u32> {
+        self.push(Box::new(request))
+    }
+
+    fn get_outgoing_request_mut(&mut self, request: u32) -> TableResult<&mut OutgoingRequest> {
+        self.get_mut(request)
+    }
+
+    fn delete_outgoing_request(&mut self, request: u32) -> TableResult<OutgoingRequest> {
+        self.delete(request)
+    }
+
+    fn push_incoming_response(&mut self, response: IncomingResponse) -> TableResult<u32> {
+        self.push(Box::new(response))
+    }
+
+    fn get_incoming_response(&self, response: u32) -> TableResult<&IncomingResponse> {
+        self.get(response)
+    }
+
+    fn get_incoming_response_mut(&mut self, response: u32) -> TableResult<&mut IncomingResponse> {
+        self.get_mut(response)
+    }
+
+    fn delete_incoming_response(&mut self, response: u32) -> TableResult<IncomingResponse> {
+        self.delete(response)
+    }
+
+    fn push_future_incoming_response(
+        &mut self,
+        response: FutureIncomingResponse,
+    ) -> TableResult<u32> {
+        self.push(Box::new(response))
+    }
+
+    fn get_future_incoming_response_mut(
+        &mut self,
+        response: u32,
+    ) -> TableResult<&mut FutureIncomingResponse> {
+        self.get_mut(response)
+    }
+
+    fn delete_future_incoming_response(
+        &mut self,
+        response: u32,
+    ) -> TableResult<FutureIncomingResponse> {
+        self.delete(response)
+    }
+
+    fn push_outgoing_response(&mut self, response: OutgoingResponse) -> TableResult<u32> {
+        self.push(Box::new(response))
+    }
+
+    fn get_outgoing_response_mut(&mut self, response: u32) -> TableResult<&mut OutgoingResponse> {
+        self.get_mut(response)
+    }
+
+    fn delete_outgoing_response(&mut self, response: u32) -> TableResult<OutgoingResponse> {
+        self.delete(response)
+    }
+
+    fn push_response_outparam(&mut self, outparam: ResponseOutparam) -> TableResult<u32> {
+        self.push(Box::new(outparam))
+    }
+
+    fn delete_response_outparam(&mut self, outparam: u32) -> TableResult<ResponseOutparam> {
+        self.delete(outparam)
+    }
+
+    fn push_future_trailers(&mut self, trailers: FutureTrailers) -> TableResult<u32> {
+        self.push(Box::new(trailers))
+    }
+
+    fn get_future_trailers_mut(&mut self, trailers: u32) -> TableResult<&mut FutureTrailers> {
+        self.get_mut(trailers)
+    }
+
+    fn delete_future_trailers(&mut self, trailers: u32) -> TableResult<FutureTrailers> {
+        self.delete(trailers)
+    }
+
+    fn push_future_write_trailers_result(
+        &mut self,
+        res: FutureWriteTrailersResult,
+    ) -> TableResult<u32> {
+        self.push(Box::new(res))
+    }
+
+    fn get_future_write_trailers_result(
         &self,
-        request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
-    ) -> anyhow::Result<http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>> {
-        let (
-            http::request::Parts {
+        res: u32,
+    ) -> TableResult<&FutureWriteTrailersResult> {
+        self.get(res)
+    }
+
+    fn delete_future_write_trailers_result(
+        &mut self,
+        res: u32,
+    ) -> TableResult<FutureWriteTrailersResult> {
+        self.delete(res)
+    }
+}
+
+/// Constructs a future, which resolves once [`FutureTrailers`] are received
+fn await_future_trailers(trailers: &mut dyn Any) -> PollableFuture<'_> {
+    let trailers = trailers.downcast_mut::<FutureTrailers>();
+    Box::pin(async move {
+        let trailers = trailers.context("invalid future trailers")?;
+        if let FutureTrailers::Pending(rx) = trailers {
+            let received = rx.await.ok();
+            *trailers = FutureTrailers::Ready(received);
+        }
+        Ok(())
+    })
+}
+
+/// Constructs a future, which resolves once [`FutureIncomingResponse`] is received
+fn await_future_incoming_response(response: &mut dyn Any) -> PollableFuture<'_> {
+    let response = response.downcast_mut::<FutureIncomingResponse>();
+    Box::pin(async move {
+        let response = response.context("invalid future incoming response")?;
+        if let FutureIncomingResponse::Pending(task) = response {
+            let received = join_response(task.await);
+            *response = FutureIncomingResponse::Received(Some(received));
+        }
+        Ok(())
+    })
+}
+
+fn join_response(
+    res: Result<Result<http::Response<Body>, types::Error>, JoinError>,
+) -> Result<http::Response<Body>, types::Error> {
+    res.unwrap_or_else(|err| {
+        Err(types::Error::UnexpectedError(format!(
+            "outgoing request task failed: {err}"
+        )))
+    })
+}
+
+fn method_from_http(method: &http::Method) -> types::Method {
+    match method.as_str() {
+        "GET" => types::Method::Get,
+        "HEAD" => types::Method::Head,
+        "POST" => types::Method::Post,
+        "PUT" => types::Method::Put,
+        "DELETE" => types::Method::Delete,
+        "CONNECT" => types::Method::Connect,
+        "OPTIONS" => types::Method::Options,
+        "TRACE" => types::Method::Trace,
+        "PATCH" => types::Method::Patch,
+        method => types::Method::Other(method.into()),
+    }
+}
+
+fn scheme_from_http(scheme: &http::uri::Scheme) -> types::Scheme {
+    match scheme.as_str() {
+        "http" => types::Scheme::Http,
+        "https" => types::Scheme::Https,
+        scheme => types::Scheme::Other(scheme.into()),
+    }
+}
+
+fn method_into_http(method: types::Method) -> Result<http::Method, types::Error> {
+    match method {
+        types::Method::Get => Ok(http::Method::GET),
+        types::Method::Head => Ok(http::Method::HEAD),
+        types::Method::Post => Ok(http::Method::POST),
+        types::Method::Put => Ok(http::Method::PUT),
+        types::Method::Delete => Ok(http::Method::DELETE),
+        types::Method::Connect => Ok(http::Method::CONNECT),
+        types::Method::Options => Ok(http::Method::OPTIONS),
+        types::Method::Trace => Ok(http::Method::TRACE),
+        types::Method::Patch => Ok(http::Method::PATCH),
+        types::Method::Other(method) => {
+            http::Method::from_bytes(method.as_bytes()).map_err(|err| {
+                types::Error::UnexpectedError(format!("invalid method `{method}`: {err}"))
+            })
+        }
+    }
+}
+
+/// Constructs an absolute [`http::Uri`] of an outgoing request, scheme defaults to HTTPS
+fn uri_from_parts(
+    path_with_query: Option<String>,
+    scheme: Option<types::Scheme>,
+    authority: Option<String>,
+) -> Result<http::Uri, types::Error> {
+    let Some(authority) = authority else {
+        return Err(types::Error::InvalidUrl("authority missing".into()))
+    };
+    let scheme = match scheme {
+        None | Some(types::Scheme::Https) => http::uri::Scheme::HTTPS,
+        Some(types::Scheme::Http) => http::uri::Scheme::HTTP,
+        Some(types::Scheme::Other(scheme)) => scheme
+            .parse()
+            .map_err(|err| types::Error::InvalidUrl(format!("invalid scheme `{scheme}`: {err}")))?,
+    };
+    http::Uri::builder()
+        .scheme(scheme)
+        .authority(authority.as_str())
+        .path_and_query(path_with_query.as_deref().unwrap_or("/"))
+        .build()
+        .map_err(|err| types::Error::InvalidUrl(err.to_string()))
+}
+
+fn fields_from_entries(entries: Vec<(String, Vec<u8>)>) -> anyhow::Result<http::HeaderMap> {
+    let mut fields = http::HeaderMap::new();
+    for (name, value) in entries {
+        let name = http::HeaderName::try_from(name).context("invalid header name")?;
+        let value = http::HeaderValue::try_from(value).context("invalid header value")?;
+        fields.append(name, value);
+    }
+    Ok(fields)
+}
+
+#[async_trait]
+impl types::Host for Ctx {
+    #[instrument]
+    async fn drop_fields(&mut self, fields: types::Fields) -> anyhow::Result<()> {
+        self.table
+            .delete_fields(fields)
+            .context("failed to delete fields")?;
+        Ok(())
+    }
+
+    #[instrument(skip(entries))]
+    async fn new_fields(
+        &mut self,
+        entries: Vec<(String, Vec<u8>)>,
+    ) -> anyhow::Result<types::Fields> {
+        let fields = fields_from_entries(entries)?;
+        self.table
+            .push_fields(fields)
+            .context("failed to push fields to table")
+    }
+
+    #[instrument]
+    async fn fields_get(
+        &mut self,
+        fields: types::Fields,
+        name: String,
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let fields = self
+            .table
+            .get_fields(fields)
+            .context("failed to get fields")?;
+        Ok(fields
+            .get_all(name)
+            .iter()
+            .map(|value| value.as_bytes().to_vec())
+            .collect())
+    }
+
+    #[instrument(skip(value))]
+    async fn fields_set(
+        &mut self,
+        fields: types::Fields,
+        name: String,
+        value: Vec<Vec<u8>>,
+    ) -> anyhow::Result<()> {
+        let name = http::HeaderName::try_from(name).context("invalid header name")?;
+        let fields = self
+            .table
+            .get_fields_mut(fields)
+            .context("failed to get fields")?;
+        fields.remove(&name);
+        for value in value {
+            let value = http::HeaderValue::try_from(value).context("invalid header value")?;
+            fields.append(&name, value);
+        }
+        Ok(())
+    }
+
+    #[instrument]
+    async fn fields_delete(&mut self, fields: types::Fields, name: String) -> anyhow::Result<()> {
+        self.table
+            .get_fields_mut(fields)
+            .context("failed to get fields")?
+            .remove(name);
+        Ok(())
+    }
+
+    #[instrument(skip(value))]
+    async fn fields_append(
+        &mut self,
+        fields: types::Fields,
+        name: String,
+        value: Vec<u8>,
+    ) -> anyhow::Result<()> {
+        let name = http::HeaderName::try_from(name).context("invalid header name")?;
+        let value = http::HeaderValue::try_from(value).context("invalid header value")?;
+        self.table
+            .get_fields_mut(fields)
+            .context("failed to get fields")?
+            .append(name, value);
+        Ok(())
+    }
+
+    #[instrument]
+    async fn fields_entries(
+        &mut self,
+        fields: types::Fields,
+    ) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+        let fields = self
+            .table
+            .get_fields(fields)
+            .context("failed to get fields")?;
+        Ok(fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+            .collect())
+    }
+
+    #[instrument]
+    async fn fields_clone(&mut self, fields: types::Fields) -> anyhow::Result<types::Fields> {
+        let fields = self
+            .table
+            .get_fields(fields)
+            .context("failed to get fields")?
+            .clone();
+        self.table
+            .push_fields(fields)
+            .context("failed to push fields to table")
+    }
+
+    #[instrument]
+    async fn finish_incoming_stream(
+        &mut self,
+        s: types::IncomingStream,
+    ) -> anyhow::Result<Option<types::FutureTrailers>> {
+        let stream = self
+            .table
+            .delete::<Box<dyn InputStream>>(s)
+            .context("failed to delete incoming stream")?;
+        let Some(IncomingBody { trailers, .. }) = stream.as_any().downcast_ref::<IncomingBody>()
+        else {
+            return Ok(None)
+        };
+        let trailers = trailers
+            .lock()
+            .map_err(|_| anyhow!("trailer lock poisoned"))?
+            .take();
+        let Some(trailers) = trailers else {
+            return Ok(None)
+        };
+        let trailers = self
+            .table
+            .push_future_trailers(FutureTrailers::Pending(trailers))
+            .context("failed to push future trailers to table")?;
+        Ok(Some(trailers))
+    }
+
+    #[instrument]
+    async fn finish_outgoing_stream(&mut self, s: types::OutgoingStream) -> anyhow::Result<()> {
+        self.table
+            .delete::<Box<dyn OutputStream>>(s)
+            .context("failed to delete outgoing stream")?;
+        Ok(())
+    }
+
+    #[instrument]
+    async fn finish_outgoing_stream_with_trailers(
+        &mut self,
+        s: types::OutgoingStream,
+        trailers: types::Trailers,
+    ) -> anyhow::Result<types::FutureWriteTrailersResult> {
+        let stream = self
+            .table
+            .delete::<Box<dyn OutputStream>>(s)
+            .context("failed to delete outgoing stream")?;
+        let trailers = self
+            .table
+            .get_fields(trailers)
+            .context("failed to get trailers")?
+            .clone();
+        let tx = if let Some(OutgoingBody { trailers, .. }) =
+            stream.as_any().downcast_ref::<OutgoingBody>()
+        {
+            trailers
+                .lock()
+                .map_err(|_| anyhow!("trailer lock poisoned"))?
+                .take()
+        } else {
+            None
+        };
+        // Close the body before sending the trailers
+        drop(stream);
+        let res = match tx.map(|tx| tx.send(trailers)) {
+            Some(Ok(())) => None,
+            Some(Err(_)) => Some(types::Error::UnexpectedError(
+                "trailer receiver dropped".into(),
+            )),
+            None => Some(types::Error::UnexpectedError(
+                "stream does not support trailers".into(),
+            )),
+        };
+        self.table
+            .push_future_write_trailers_result(FutureWriteTrailersResult(res))
+            .context("failed to push future write trailers result to table")
+    }
+
+    #[instrument]
+    async fn drop_future_trailers(&mut self, f: types::FutureTrailers) -> anyhow::Result<()> {
+        self.table
+            .delete_future_trailers(f)
+            .context("failed to delete future trailers")?;
+        Ok(())
+    }
+
+    #[instrument]
+    async fn future_trailers_get(
+        &mut self,
+        f: types::FutureTrailers,
+    ) -> anyhow::Result<Option<Result<types::Trailers, types::Error>>> {
+        let trailers = self
+            .table
+            .get_future_trailers_mut(f)
+            .context("failed to get future trailers")?;
+        if let FutureTrailers::Pending(rx) = trailers {
+            match rx.try_recv() {
+                Ok(received) => *trailers = FutureTrailers::Ready(Some(received)),
+                Err(TryRecvError::Empty) => return Ok(None),
+                Err(TryRecvError::Closed) => *trailers = FutureTrailers::Ready(None),
+            }
+        }
+        let FutureTrailers::Ready(trailers) = trailers else {
+            bail!("future trailers not ready")
+        };
+        let trailers = trailers.clone().unwrap_or_default();
+        let trailers = self
+            .table
+            .push_fields(trailers)
+            .context("failed to push trailers to table")?;
+        Ok(Some(Ok(trailers)))
+    }
+
+    #[instrument]
+    async fn listen_to_future_trailers(
+        &mut self,
+        f: types::FutureTrailers,
+    ) -> anyhow::Result<preview2::wasi::poll::poll::Pollable> {
+        self.table
+            .push_host_pollable(HostPollable::TableEntry {
+                index: f,
+                make_future: await_future_trailers,
+            })
+            .context("failed to push pollable to table")
+    }
+
+    #[instrument]
+    async fn drop_future_write_trailers_result(
+        &mut self,
+        f: types::FutureWriteTrailersResult,
+    ) -> anyhow::Result<()> {
+        self.table
+            .delete_future_write_trailers_result(f)
+            .context("failed to delete future write trailers result")?;
+        Ok(())
+    }
+
+    #[instrument]
+    async fn future_write_trailers_result_get(
+        &mut self,
+        f: types::FutureWriteTrailersResult,
+    ) -> anyhow::Result<Option<Result<(), types::Error>>> {
+        let FutureWriteTrailersResult(res) = self
+            .table
+            .get_future_write_trailers_result(f)
+            .context("failed to get future write trailers result")?;
+        match res {
+            None => Ok(Some(Ok(()))),
+            Some(err) => Ok(Some(Err(err.clone()))),
+        }
+    }
+
+    #[instrument]
+    async fn listen_to_future_write_trailers_result(
+        &mut self,
+        _f: types::FutureWriteTrailersResult,
+    ) -> anyhow::Result<preview2::wasi::poll::poll::Pollable> {
+        // Trailers are written synchronously, so the result is always ready
+        self.table
+            .push_host_pollable(HostPollable::Closure(Box::new(|| {
+                Box::pin(async { Ok(()) })
+            })))
+            .context("failed to push pollable to table")
+    }
+
+    #[instrument]
+    async fn drop_incoming_request(
+        &mut self,
+        request: types::IncomingRequest,
+    ) -> anyhow::Result<()> {
+        let IncomingRequest { headers, .. } = self
+            .table
+            .delete_incoming_request(request)
+            .context("failed to delete incoming request")?;
+        // Headers may have already been dropped by the guest
+        _ = self.table.delete_fields(headers);
+        Ok(())
+    }
+
+    #[instrument]
+    async fn drop_outgoing_request(
+        &mut self,
+        request: types::OutgoingRequest,
+    ) -> anyhow::Result<()> {
+        self.table
+            .delete_outgoing_request(request)
+            .context("failed to delete outgoing request")?;
+        Ok(())
+    }
+
+    #[instrument]
+    async fn incoming_request_method(
+        &mut self,
+        request: types::IncomingRequest,
+    ) -> anyhow::Result<types::Method> {
+        let IncomingRequest { method, .. } = self
+            .table
+            .get_incoming_request(request)
+            .context("failed to get incoming request")?;
+        Ok(method_from_http(method))
+    }
+
+    #[instrument]
+    async fn incoming_request_path_with_query(
+        &mut self,
+        request: types::IncomingRequest,
+    ) -> anyhow::Result<Option<String>> {
+        let IncomingRequest { uri, .. } = self
+            .table
+            .get_incoming_request(request)
+            .context("failed to get incoming request")?;
+        Ok(uri.path_and_query().map(|path| path.as_str().into()))
+    }
+
+    #[instrument]
+    async fn incoming_request_scheme(
+        &mut self,
+        request: types::IncomingRequest,
+    ) -> anyhow::Result<Option<types::Scheme>> {
+        let IncomingRequest { uri, .. } = self
+            .table
+            .get_incoming_request(request)
+            .context("failed to get incoming request")?;
+        Ok(uri.scheme().map(scheme_from_http))
+    }
+
+    #[instrument]
+    async fn incoming_request_authority(
+        &mut self,
+        request: types::IncomingRequest,
+    ) -> anyhow::Result<Option<String>> {
+        let IncomingRequest { uri, .. } = self
+            .table
+            .get_incoming_request(request)
+            .context("failed to get incoming request")?;
+        Ok(uri.authority().map(|authority| authority.as_str().into()))
+    }
+
+    #[instrument]
+    async fn incoming_request_headers(
+        &mut self,
+        request: types::IncomingRequest,
+    ) -> anyhow::Result<types::Headers> {
+        let IncomingRequest { headers, .. } = self
+            .table
+            .get_incoming_request(request)
+            .context("failed to get incoming request")?;
+        Ok(*headers)
+    }
+
+    #[instrument]
+    async fn incoming_request_consume(
+        &mut self,
+        request: types::IncomingRequest,
+    ) -> anyhow::Result<Result<types::IncomingStream, ()>> {
+        let IncomingRequest { body, trailers, .. } = self
+            .table
+            .get_incoming_request_mut(request)
+            .context("failed to get incoming request")?;
+        let Some(body) = body.take() else {
+            return Ok(Err(()))
+        };
+        let body = IncomingBody::new(body, trailers.take());
+        let stream = self
+            .table
+            .push_input_stream(Box::new(body))
+            .context("failed to push incoming stream to table")?;
+        Ok(Ok(stream))
+    }
+
+    #[instrument]
+    async fn new_outgoing_request(
+        &mut self,
+        method: types::Method,
+        path_with_query: Option<String>,
+        scheme: Option<types::Scheme>,
+        authority: Option<String>,
+        headers: types::Headers,
+    ) -> anyhow::Result<Result<types::OutgoingRequest, types::Error>> {
+        let method = match method_into_http(method) {
+            Ok(method) => method,
+            Err(err) => return Ok(Err(err)),
+        };
+        let uri = match uri_from_parts(path_with_query, scheme, authority) {
+            Ok(uri) => uri,
+            Err(err) => return Ok(Err(err)),
+        };
+        let (body, request_body) = channel_pipe();
+        let (trailers, request_trailers) = oneshot::channel();
+        let request = self
+            .table
+            .push_outgoing_request(OutgoingRequest {
                 method,
                 uri,
                 headers,
-                ..
-            },
-            body,
-        ) = request.into_parts();
-        let path_with_query = uri.path_and_query().map(http::uri::PathAndQuery::as_str);
-        let scheme = uri.scheme_str();
-        let authority = uri.authority().map(http::uri::Authority::as_str);
+                body: Some((body, trailers)),
+                request_body,
+                request_trailers,
+            })
+            .context("failed to push outgoing request to table")?;
+        Ok(Ok(request))
+    }
+
+    #[instrument]
+    async fn outgoing_request_write(
+        &mut self,
+        request: types::OutgoingRequest,
+    ) -> anyhow::Result<Result<types::OutgoingStream, ()>> {
+        let OutgoingRequest { body, .. } = self
+            .table
+            .get_outgoing_request_mut(request)
+            .context("failed to get outgoing request")?;
+        let Some((body, trailers)) = body.take() else {
+            return Ok(Err(()))
+        };
+        let stream = self
+            .table
+            .push_output_stream(Box::new(OutgoingBody::new(Box::new(body), trailers)))
+            .context("failed to push outgoing stream to table")?;
+        Ok(Ok(stream))
+    }
+
+    #[instrument]
+    async fn drop_response_outparam(
+        &mut self,
+        response: types::ResponseOutparam,
+    ) -> anyhow::Result<()> {
+        self.table
+            .delete_response_outparam(response)
+            .context("failed to delete response outparam")?;
+        Ok(())
+    }
+
+    #[instrument]
+    async fn set_response_outparam(
+        &mut self,
+        param: types::ResponseOutparam,
+        response: Result<types::OutgoingResponse, types::Error>,
+    ) -> anyhow::Result<Result<(), ()>> {
+        let ResponseOutparam(tx) = self
+            .table
+            .delete_response_outparam(param)
+            .context("failed to delete response outparam")?;
+        let response = match response {
+            Ok(response) => {
+                let OutgoingResponse {
+                    status,
+                    headers,
+                    response_body,
+                    response_trailers,
+                    ..
+                } = self
+                    .table
+                    .delete_outgoing_response(response)
+                    .context("failed to delete outgoing response")?;
+                let headers = self
+                    .table
+                    .get_fields(headers)
+                    .context("failed to get headers")?
+                    .clone();
+                let mut response = http::Response::new(Box::new(response_body) as Body);
+                *response.status_mut() = status;
+                *response.headers_mut() = headers;
+                response.extensions_mut().insert(response_trailers);
+                Ok(response)
+            }
+            Err(err) => Err(anyhow!("guest failed to produce a response: {err:?}")),
+        };
+        Ok(tx.send(response).map_err(|_| ()))
+    }
+
+    #[instrument]
+    async fn drop_incoming_response(
+        &mut self,
+        response: types::IncomingResponse,
+    ) -> anyhow::Result<()> {
+        let IncomingResponse { headers, .. } = self
+            .table
+            .delete_incoming_response(response)
+            .context("failed to delete incoming response")?;
+        // Headers may have already been dropped by the guest
+        _ = self.table.delete_fields(headers);
+        Ok(())
+    }
+
+    #[instrument]
+    async fn drop_outgoing_response(
+        &mut self,
+        response: types::OutgoingResponse,
+    ) -> anyhow::Result<()> {
+        self.table
+            .delete_outgoing_response(response)
+            .context("failed to delete outgoing response")?;
+        Ok(())
+    }
+
+    #[instrument]
+    async fn incoming_response_status(
+        &mut self,
+        response: types::IncomingResponse,
+    ) -> anyhow::Result<types::StatusCode> {
+        let IncomingResponse { status, .. } = self
+            .table
+            .get_incoming_response(response)
+            .context("failed to get incoming response")?;
+        Ok(status.as_u16())
+    }
+
+    #[instrument]
+    async fn incoming_response_headers(
+        &mut self,
+        response: types::IncomingResponse,
+    ) -> anyhow::Result<types::Headers> {
+        let IncomingResponse { headers, .. } = self
+            .table
+            .get_incoming_response(response)
+            .context("failed to get incoming response")?;
+        Ok(*headers)
+    }
+
+    #[instrument]
+    async fn incoming_response_consume(
+        &mut self,
+        response: types::IncomingResponse,
+    ) -> anyhow::Result<Result<types::IncomingStream, ()>> {
+        let IncomingResponse { body, trailers, .. } = self
+            .table
+            .get_incoming_response_mut(response)
+            .context("failed to get incoming response")?;
+        let Some(body) = body.take() else {
+            return Ok(Err(()))
+        };
+        let body = IncomingBody::new(body, trailers.take());
+        let stream = self
+            .table
+            .push_input_stream(Box::new(body))
+            .context("failed to push incoming stream to table")?;
+        Ok(Ok(stream))
+    }
+
+    #[instrument]
+    async fn new_outgoing_response(
+        &mut self,
+        status_code: types::StatusCode,
+        headers: types::Headers,
+    ) -> anyhow::Result<Result<types::OutgoingResponse, types::Error>> {
+        let status = match http::StatusCode::from_u16(status_code) {
+            Ok(status) => status,
+            Err(err) => {
+                return Ok(Err(types::Error::UnexpectedError(format!(
+                    "invalid status code `{status_code}`: {err}"
+                ))))
+            }
+        };
+        let (body, response_body) = tokio::io::duplex(BODY_PIPE_CAPACITY);
+        let (trailers, response_trailers) = oneshot::channel();
+        let response = self
+            .table
+            .push_outgoing_response(OutgoingResponse {
+                status,
+                headers,
+                body: Some((body, trailers)),
+                response_body,
+                response_trailers,
+            })
+            .context("failed to push outgoing response to table")?;
+        Ok(Ok(response))
+    }
+
+    #[instrument]
+    async fn outgoing_response_write(
+        &mut self,
+        response: types::OutgoingResponse,
+    ) -> anyhow::Result<Result<types::OutgoingStream, ()>> {
+        let OutgoingResponse { body, .. } = self
+            .table
+            .get_outgoing_response_mut(response)
+            .context("failed to get outgoing response")?;
+        let Some((body, trailers)) = body.take() else {
+            return Ok(Err(()))
+        };
+        let stream = self
+            .table
+            .push_output_stream(Box::new(OutgoingBody::new(
+                Box::new(AsyncStream(body)),
+                trailers,
+            )))
+            .context("failed to push outgoing stream to table")?;
+        Ok(Ok(stream))
+    }
+
+    #[instrument]
+    async fn drop_future_incoming_response(
+        &mut self,
+        f: types::FutureIncomingResponse,
+    ) -> anyhow::Result<()> {
+        let response = self
+            .table
+            .delete_future_incoming_response(f)
+            .context("failed to delete future incoming response")?;
+        if let FutureIncomingResponse::Pending(task) = response {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    #[instrument]
+    async fn future_incoming_response_get(
+        &mut self,
+        f: types::FutureIncomingResponse,
+    ) -> anyhow::Result<Option<Result<types::IncomingResponse, types::Error>>> {
+        let response = self
+            .table
+            .get_future_incoming_response_mut(f)
+            .context("failed to get future incoming response")?;
+        let received = match response {
+            FutureIncomingResponse::Pending(task) => {
+                let Some(received) = task.now_or_never() else {
+                    return Ok(None)
+                };
+                join_response(received)
+            }
+            FutureIncomingResponse::Received(received) => {
+                received.take().context("response already received")?
+            }
+            FutureIncomingResponse::Ready(res) => return Ok(Some(res.clone())),
+        };
+        let res = match received {
+            Ok(response) => {
+                let (
+                    http::response::Parts {
+                        status,
+                        headers,
+                        mut extensions,
+                        ..
+                    },
+                    body,
+                ) = response.into_parts();
+                let headers = self
+                    .table
+                    .push_fields(headers)
+                    .context("failed to push headers to table")?;
+                let response = self
+                    .table
+                    .push_incoming_response(IncomingResponse {
+                        status,
+                        headers,
+                        body: Some(body),
+                        trailers: extensions.remove::<HttpTrailers>(),
+                    })
+                    .context("failed to push incoming response to table")?;
+                Ok(response)
+            }
+            Err(err) => Err(err),
+        };
+        *self
+            .table
+            .get_future_incoming_response_mut(f)
+            .context("failed to get future incoming response")? =
+            FutureIncomingResponse::Ready(res.clone());
+        Ok(Some(res))
+    }
+
+    #[instrument]
+    async fn listen_to_future_incoming_response(
+        &mut self,
+        f: types::FutureIncomingResponse,
+    ) -> anyhow::Result<preview2::wasi::poll::poll::Pollable> {
+        self.table
+            .push_host_pollable(HostPollable::TableEntry {
+                index: f,
+                make_future: await_future_incoming_response,
+            })
+            .context("failed to push pollable to table")
+    }
+}
+
+#[async_trait]
+impl outgoing_handler::Host for Ctx {
+    #[instrument]
+    async fn handle(
+        &mut self,
+        request: types::OutgoingRequest,
+        options: Option<types::RequestOptions>,
+    ) -> anyhow::Result<types::FutureIncomingResponse> {
+        let OutgoingRequest {
+            method,
+            uri,
+            headers,
+            request_body,
+            request_trailers,
+            ..
+        } = self
+            .table
+            .delete_outgoing_request(request)
+            .context("failed to delete outgoing request")?;
+        let headers = self
+            .table
+            .get_fields(headers)
+            .context("failed to get headers")?
+            .clone();
+        let mut request = http::Request::new(Box::new(request_body) as Body);
+        *request.method_mut() = method;
+        *request.uri_mut() = uri;
+        *request.headers_mut() = headers;
+        request.extensions_mut().insert(request_trailers);
+        let handler = self.handler.clone();
+        let task = tokio::spawn(async move {
+            OutgoingHttp::handle(&handler, request, options)
+                .await
+                .map_err(|err| types::Error::UnexpectedError(format!("{err:#}")))
+        });
+        self.table
+            .push_future_incoming_response(FutureIncomingResponse::Pending(task))
+            .context("failed to push future incoming response to table")
+    }
+}
+
+impl InterfaceInstance<incoming_http_bindings::IncomingHttp> {
+    /// Handle a msgpack-encoded `wasmcloud:httpserver` request, returning the msgpack-encoded
+    /// response. Actors not exporting `wasi:http/incoming-handler` receive the request as-is.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the request cannot be decoded, the actor call fails or the actor returns an error
+    #[instrument(skip_all)]
+    pub async fn handle_compat(&self, request: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        let InterfaceBindings::Guest(guest) = self.bindings.as_ref() else {
+            let request: HttpRequest =
+                rmp_serde::from_slice(&request).context("failed to decode request")?;
+            let request = request.try_into().context("invalid request")?;
+            let response = self.handle(request).await?;
+            let response = HttpResponse::from_http(response).await?;
+            return rmp_serde::to_vec_named(&response).context("failed to encode response")
+        };
+        let (response_tx, mut response_rx) = tokio::io::duplex(BODY_PIPE_CAPACITY);
         let mut store = self.store.lock().await;
-        bail!("unsupported"); // TODO: Support
+        let mut response = vec![];
+        let (res, _) = try_join!(
+            async {
+                guest
+                    .call(
+                        &mut store,
+                        HTTP_SERVER_HANDLE_REQUEST,
+                        Cursor::new(request),
+                        response_tx,
+                    )
+                    .await
+                    .context("failed to call actor")
+            },
+            async {
+                response_rx
+                    .read_to_end(&mut response)
+                    .await
+                    .context("failed to read response")
+            },
+        )?;
+        res.map_err(|e| anyhow!(e))?;
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl IncomingHttp for InterfaceInstance<incoming_http_bindings::IncomingHttp> {
+    #[instrument(skip_all)]
+    async fn handle(
+        &self,
+        mut request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+    ) -> anyhow::Result<http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>> {
+        if let InterfaceBindings::Guest(..) = self.bindings.as_ref() {
+            let request = HttpRequest::from_http(request).await?;
+            let request = rmp_serde::to_vec_named(&request).context("failed to encode request")?;
+            let response = self.handle_compat(request).await?;
+            let response: HttpResponse =
+                rmp_serde::from_slice(&response).context("failed to decode response")?;
+            return response.try_into();
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let (request, response) = {
+            let mut store = self.store.lock().await;
+            let table = &mut store.data_mut().table;
+            let trailers = request.extensions_mut().remove::<HttpTrailers>();
+            let (
+                http::request::Parts {
+                    method,
+                    uri,
+                    headers,
+                    ..
+                },
+                body,
+            ) = request.into_parts();
+            let headers = table
+                .push_fields(headers)
+                .context("failed to push headers to table")?;
+            let request = table
+                .push_incoming_request(IncomingRequest {
+                    method,
+                    uri,
+                    headers,
+                    body: Some(body),
+                    trailers,
+                })
+                .context("failed to push incoming request to table")?;
+            let response = table
+                .push_response_outparam(ResponseOutparam(tx))
+                .context("failed to push response outparam to table")?;
+            (request, response)
+        };
+        // The guest may continue executing after the response is set, so the call is spawned
+        // to allow streaming the response body concurrently
+        let store = Arc::clone(&self.store);
+        let bindings = Arc::clone(&self.bindings);
+        tokio::spawn(async move {
+            let InterfaceBindings::Interface(bindings) = bindings.as_ref() else {
+                return
+            };
+            let mut store = store.lock().await;
+            trace!("call `wasi:http/incoming-handler.handle`");
+            let res = bindings
+                .wasi_http_incoming_handler()
+                .call_handle(&mut *store, request, response)
+                .await
+                .context("failed to call `wasi:http/incoming-handler.handle`");
+            if let Ok(ResponseOutparam(tx)) =
+                store.data_mut().table.delete_response_outparam(response)
+            {
+                let err = match res {
+                    Ok(()) => anyhow!("guest did not set the response"),
+                    Err(err) => err,
+                };
+                _ = tx.send(Err(err));
+            } else if let Err(err) = res {
+                warn!(?err, "failed to handle request");
+            }
+        });
+        rx.await.context("response outparam dropped")?
     }
 }
@@ -1,7 +1,7 @@
 use super::{AsyncStream, AsyncVec, Ctx, Instance, TableResult};
 
-use crate::capability::keyvalue::{readwrite, types, wasi_cloud_error};
-use crate::capability::KeyValueReadWrite;
+use crate::capability::keyvalue::{atomic, batch, readwrite, types, wasi_cloud_error};
+use crate::capability::{KeyValueAtomic, KeyValueBatch, KeyValueReadWrite};
 
 use std::sync::Arc;
 
@@ -13,6 +13,24 @@ use wasmtime_wasi::preview2;
 use wasmtime_wasi::preview2::stream::TableStreamExt;
 
 impl Instance {
+    /// Set [`KeyValueAtomic`] handler for this [Instance].
+    pub fn keyvalue_atomic(
+        &mut self,
+        keyvalue_atomic: Arc<dyn KeyValueAtomic + Send + Sync>,
+    ) -> &mut Self {
+        self.handler_mut().replace_keyvalue_atomic(keyvalue_atomic);
+        self
+    }
+
+    /// Set [`KeyValueBatch`] handler for this [Instance].
+    pub fn keyvalue_batch(
+        &mut self,
+        keyvalue_batch: Arc<dyn KeyValueBatch + Send + Sync>,
+    ) -> &mut Self {
+        self.handler_mut().replace_keyvalue_batch(keyvalue_batch);
+        self
+    }
+
     /// Set [`KeyValueReadWrite`] handler for this [Instance].
     pub fn keyvalue_readwrite(
         &mut self,
@@ -215,6 +233,144 @@ impl readwrite::Host for Ctx {
     }
 }
 
+#[async_trait]
+impl atomic::Host for Ctx {
+    #[instrument]
+    async fn increment(
+        &mut self,
+        bucket: types::Bucket,
+        key: types::Key,
+        delta: u64,
+    ) -> anyhow::Result<Result<u64>> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.increment(bucket, key, delta).await {
+            Ok(value) => Ok(Ok(value)),
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+
+    #[instrument]
+    async fn compare_and_swap(
+        &mut self,
+        bucket: types::Bucket,
+        key: types::Key,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<Result<bool>> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.compare_and_swap(bucket, key, old, new).await {
+            Ok(swapped) => Ok(Ok(swapped)),
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl batch::Host for Ctx {
+    #[instrument]
+    async fn get_many(
+        &mut self,
+        bucket: types::Bucket,
+        keys: types::Keys,
+    ) -> anyhow::Result<Result<Vec<types::IncomingValue>>> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.get_many(bucket, keys).await {
+            Ok(values) => {
+                let values = values
+                    .into_iter()
+                    .map(|(stream, size)| self.table.push_incoming_value(stream, size))
+                    .collect::<TableResult<_>>()
+                    .context("failed to push streams and sizes")?;
+                Ok(Ok(values))
+            }
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+
+    #[instrument]
+    async fn get_keys(&mut self, bucket: types::Bucket) -> anyhow::Result<types::Keys> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        self.handler
+            .get_keys(bucket)
+            .await
+            .context("failed to list keys")
+    }
+
+    #[instrument]
+    async fn set_many(
+        &mut self,
+        bucket: types::Bucket,
+        _keys: types::Keys,
+        values: Vec<(types::Key, types::OutgoingValue)>,
+    ) -> anyhow::Result<Result<()>> {
+        // NOTE: `keys` duplicate the keys in `values`, so they are ignored
+        let mut entries = Vec::with_capacity(values.len());
+        for (key, outgoing_value) in values {
+            let mut stream = self
+                .table
+                .get_outgoing_value(outgoing_value)
+                .context("failed to get outgoing value")?
+                .clone();
+            stream.rewind().await.context("failed to rewind stream")?;
+            entries.push((
+                key,
+                Box::new(stream) as Box<dyn AsyncRead + Sync + Send + Unpin>,
+            ));
+        }
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.set_many(bucket, entries).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+
+    #[instrument]
+    async fn delete_many(
+        &mut self,
+        bucket: types::Bucket,
+        keys: types::Keys,
+    ) -> anyhow::Result<Result<()>> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.delete_many(bucket, keys).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl types::Host for Ctx {
     #[instrument]
@@ -53,8 +53,8 @@ impl Instance {
                 .context("failed to instantiate `wasi:logging/logging` interface")?
         };
         Ok(InterfaceInstance {
-            store: Mutex::new(self.store),
-            bindings,
+            store: Arc::new(Mutex::new(self.store)),
+            bindings: Arc::new(bindings),
         })
     }
 }
@@ -82,7 +82,7 @@ impl Logging for InterfaceInstance<logging_bindings::Logging> {
         message: String,
     ) -> anyhow::Result<()> {
         let mut store = self.store.lock().await;
-        match &self.bindings {
+        match self.bindings.as_ref() {
             InterfaceBindings::Guest(guest) => {
                 let level = match level {
                     logging::Level::Trace => "trace",
@@ -1,14 +1,29 @@
-use super::{Ctx, Instance};
+use super::{Ctx, Instance, InterfaceBindings, InterfaceInstance};
 
+use crate::capability::compat::{SubMessage, MESSAGING_HANDLE_MESSAGE};
 use crate::capability::messaging::{consumer, types};
-use crate::capability::Messaging;
+use crate::capability::{Messaging, MessagingHandler};
 
 use core::time::Duration;
 
+use std::io::Cursor;
 use std::sync::Arc;
 
+use anyhow::{anyhow, Context as _};
 use async_trait::async_trait;
-use tracing::instrument;
+use tokio::io::sink;
+use tokio::sync::Mutex;
+use tracing::{instrument, trace};
+
+pub mod messaging_handler_bindings {
+    wasmtime::component::bindgen!({
+        world: "messaging-handler",
+        async: true,
+        with: {
+           "wasmcloud:messaging/types": crate::capability::messaging::types,
+        },
+    });
+}
 
 impl Instance {
     /// Set [`Messaging`] handler for this [Instance].
@@ -16,6 +31,35 @@ impl Instance {
         self.handler_mut().replace_messaging(messaging);
         self
     }
+
+    /// Instantiates and returns an [`InterfaceInstance<messaging_handler_bindings::MessagingHandler>`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if messaging handler bindings are not exported by the [`Instance`]
+    pub async fn into_messaging_handler(
+        mut self,
+    ) -> anyhow::Result<InterfaceInstance<messaging_handler_bindings::MessagingHandler>> {
+        let bindings = if let Ok((bindings, _)) =
+            messaging_handler_bindings::MessagingHandler::instantiate_async(
+                &mut self.store,
+                &self.component,
+                &self.linker,
+            )
+            .await
+        {
+            InterfaceBindings::Interface(bindings)
+        } else {
+            self.as_guest_bindings()
+                .await
+                .map(InterfaceBindings::Guest)
+                .context("failed to instantiate `wasmcloud:messaging/handler` interface")?
+        };
+        Ok(InterfaceInstance {
+            store: Arc::new(Mutex::new(self.store)),
+            bindings: Arc::new(bindings),
+        })
+    }
 }
 
 #[async_trait]
@@ -63,3 +107,35 @@ impl consumer::Host for Ctx {
             .map_err(|err| format!("{err:#}")))
     }
 }
+
+#[async_trait]
+impl MessagingHandler for InterfaceInstance<messaging_handler_bindings::MessagingHandler> {
+    #[instrument(skip(self))]
+    async fn handle_message(&self, msg: types::BrokerMessage) -> anyhow::Result<()> {
+        let mut store = self.store.lock().await;
+        match self.bindings.as_ref() {
+            InterfaceBindings::Guest(guest) => {
+                let request = rmp_serde::to_vec_named(&SubMessage::from(msg))
+                    .context("failed to encode request")?;
+                guest
+                    .call(
+                        &mut store,
+                        MESSAGING_HANDLE_MESSAGE,
+                        Cursor::new(request),
+                        sink(),
+                    )
+                    .await
+                    .context("failed to call actor")?
+                    .map_err(|e| anyhow!(e))
+            }
+            InterfaceBindings::Interface(bindings) => {
+                trace!("call `wasmcloud:messaging/handler.handle-message`");
+                bindings
+                    .wasmcloud_messaging_handler()
+                    .call_handle_message(&mut *store, &msg)
+                    .await?
+                    .map_err(|e| anyhow!(e))
+            }
+        }
+    }
+}
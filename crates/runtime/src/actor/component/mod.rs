@@ -1,6 +1,8 @@
-use crate::actor::claims;
+use crate::actor::{claims, Preopen};
 use crate::capability::{builtin, Interfaces};
-use crate::Runtime;
+use crate::deterministic::SharedRng;
+use crate::profiling::{InstanceProfiler, Profiler};
+use crate::{ActorConfig, Deterministic, Runtime};
 
 use core::fmt::{self, Debug};
 use core::mem::replace;
@@ -27,8 +29,10 @@ mod keyvalue;
 mod logging;
 mod messaging;
 
+pub(crate) use self::blobstore::blobstore_handler_bindings;
 pub(crate) use self::http::incoming_http_bindings;
 pub(crate) use self::logging::logging_bindings;
+pub(crate) use self::messaging::messaging_handler_bindings;
 
 type TableResult<T> = Result<T, preview2::TableError>;
 
@@ -311,6 +315,12 @@ struct Ctx {
     wasi: preview2::WasiCtx,
     table: preview2::Table,
     handler: builtin::Handler,
+    config: ActorConfig,
+    preopens: Vec<PreopenDir>,
+    deterministic: Option<Deterministic>,
+    /// Secure and insecure random number generators used by `wasi`, if deterministic execution is
+    /// enabled
+    rngs: [SharedRng; 2],
     stdin: StdioStream<Box<dyn AsyncRead + Send + Sync + Unpin>>,
     stdout: StdioStream<Box<dyn AsyncWrite + Send + Sync + Unpin>>,
     stderr: StdioStream<Box<dyn AsyncWrite + Send + Sync + Unpin>>,
@@ -347,6 +357,9 @@ pub struct Component {
     engine: wasmtime::Engine,
     claims: Option<jwt::Claims<jwt::Actor>>,
     handler: builtin::HandlerBuilder,
+    config: ActorConfig,
+    deterministic: Option<Deterministic>,
+    profiler: Option<Profiler>,
 }
 
 impl Debug for Component {
@@ -354,15 +367,94 @@ impl Debug for Component {
         f.debug_struct("Component")
             .field("claims", &self.claims)
             .field("handler", &self.handler)
+            .field("config", &self.config)
+            .field("deterministic", &self.deterministic)
+            .field("profiler", &self.profiler)
             .field("runtime", &"wasmtime")
             .finish()
     }
 }
 
+/// Host directory preopened for an [Instance]
+struct PreopenDir {
+    dir: cap_std::fs::Dir,
+    dir_perms: preview2::DirPerms,
+    file_perms: preview2::FilePerms,
+    guest_path: String,
+}
+
+/// Opens all preopened directories specified in [`ActorConfig`].
+fn open_preopens(config: &ActorConfig) -> anyhow::Result<Vec<PreopenDir>> {
+    config
+        .preopens
+        .iter()
+        .map(
+            |Preopen {
+                 host_path,
+                 guest_path,
+                 read_only,
+             }| {
+                let dir =
+                    cap_std::fs::Dir::open_ambient_dir(host_path, cap_std::ambient_authority())
+                        .with_context(|| format!("failed to open `{}`", host_path.display()))?;
+                let (dir_perms, file_perms) = if *read_only {
+                    (preview2::DirPerms::READ, preview2::FilePerms::READ)
+                } else {
+                    (preview2::DirPerms::all(), preview2::FilePerms::all())
+                };
+                Ok(PreopenDir {
+                    dir,
+                    dir_perms,
+                    file_perms,
+                    guest_path: guest_path.clone(),
+                })
+            },
+        )
+        .collect()
+}
+
+/// Returns a new [`preview2::WasiCtxBuilder`] configured according to [`ActorConfig`] with
+/// handles to `preopens` directories.
+/// If `deterministic` is specified, randomness is produced by the secure and insecure `rngs`.
+/// If `operation` is specified, it is passed to the actor as the first argument.
+fn wasi_builder(
+    config: &ActorConfig,
+    preopens: &[PreopenDir],
+    deterministic: Option<&Deterministic>,
+    [secure, insecure]: &[SharedRng; 2],
+    operation: Option<&str>,
+) -> anyhow::Result<preview2::WasiCtxBuilder> {
+    let argv0 = config.argv0.as_deref().unwrap_or("main.wasm");
+    let args: Vec<_> = [argv0].into_iter().chain(operation).collect();
+    let mut wasi = preview2::WasiCtxBuilder::new()
+        .set_args(&args)
+        .set_env(&config.env);
+    for PreopenDir {
+        dir,
+        dir_perms,
+        file_perms,
+        guest_path,
+    } in preopens
+    {
+        let dir = dir
+            .try_clone()
+            .with_context(|| format!("failed to duplicate `{guest_path}` directory handle"))?;
+        wasi = wasi.push_preopened_dir(dir, *dir_perms, *file_perms, guest_path);
+    }
+    if let Some(deterministic) = deterministic {
+        wasi = deterministic.configure_wasi(wasi, secure.clone(), insecure.clone());
+    }
+    Ok(wasi)
+}
+
 fn instantiate(
     engine: &wasmtime::Engine,
     component: wasmtime::component::Component,
     handler: impl Into<builtin::Handler>,
+    config: ActorConfig,
+    deterministic: Option<Deterministic>,
+    profiler: Option<&Profiler>,
+    claims: Option<&jwt::Claims<jwt::Actor>>,
 ) -> anyhow::Result<Instance> {
     let mut linker = wasmtime::component::Linker::new(engine);
 
@@ -378,8 +470,12 @@ fn instantiate(
 
     // NOTE: stdio will be added to table by `build()` below
     let mut table = preview2::Table::new();
-    let wasi = preview2::WasiCtxBuilder::new()
-        .set_args(&["main.wasm"]) // TODO: Configure argv[0]
+    let preopens = open_preopens(&config)?;
+    let rngs = deterministic.as_ref().map_or_else(
+        || [SharedRng::from_entropy(), SharedRng::from_entropy()],
+        |deterministic| [deterministic.rng(), deterministic.rng()],
+    );
+    let wasi = wasi_builder(&config, &preopens, deterministic.as_ref(), &rngs, None)?
         .set_stdin(stdin.clone())
         .set_stdout(stdout.clone())
         .set_stderr(stderr.clone())
@@ -390,15 +486,21 @@ fn instantiate(
         wasi,
         table,
         handler,
+        config,
+        preopens,
+        deterministic,
+        rngs,
         stdin,
         stdout,
         stderr,
     };
-    let store = wasmtime::Store::new(engine, ctx);
+    let mut store = wasmtime::Store::new(engine, ctx);
+    let profiler = profiler.map(|profiler| InstanceProfiler::new(profiler, claims, &mut store));
     Ok(Instance {
         component,
         linker,
         store,
+        profiler,
     })
 }
 
@@ -416,9 +518,18 @@ impl Component {
             engine,
             claims,
             handler: rt.handler.clone(),
+            config: rt.actor_config.clone(),
+            deterministic: rt.deterministic.clone(),
+            profiler: rt.profiler.clone(),
         })
     }
 
+    /// Set [`ActorConfig`] used for all instances of this [Component].
+    pub fn set_config(&mut self, config: ActorConfig) -> &mut Self {
+        self.config = config;
+        self
+    }
+
     /// [Claims](jwt::Claims) associated with this [Component].
     #[instrument]
     pub fn claims(&self) -> Option<&jwt::Claims<jwt::Actor>> {
@@ -436,14 +547,30 @@ impl Component {
     pub fn into_instance_claims(
         self,
     ) -> anyhow::Result<(Instance, Option<jwt::Claims<jwt::Actor>>)> {
-        let instance = instantiate(&self.engine, self.component, self.handler)?;
+        let instance = instantiate(
+            &self.engine,
+            self.component,
+            self.handler,
+            self.config,
+            self.deterministic,
+            self.profiler.as_ref(),
+            self.claims.as_ref(),
+        )?;
         Ok((instance, self.claims))
     }
 
     /// Instantiates a [Component] and returns the resulting [Instance].
     #[instrument]
     pub fn instantiate(&self) -> anyhow::Result<Instance> {
-        instantiate(&self.engine, self.component.clone(), self.handler.clone())
+        instantiate(
+            &self.engine,
+            self.component.clone(),
+            self.handler.clone(),
+            self.config.clone(),
+            self.deterministic.clone(),
+            self.profiler.as_ref(),
+            self.claims.as_ref(),
+        )
     }
 
     /// Instantiates a [Component] producing an [Instance] and invokes an operation on it using [Instance::call]
@@ -472,6 +599,7 @@ pub struct Instance {
     component: wasmtime::component::Component,
     linker: wasmtime::component::Linker<Ctx>,
     store: wasmtime::Store<Ctx>,
+    profiler: Option<InstanceProfiler>,
 }
 
 impl Debug for Instance {
@@ -484,7 +612,7 @@ impl Debug for Instance {
 
 impl Instance {
     /// Returns a mutable reference to embedded [`builtin::Handler`]
-    fn handler_mut(&mut self) -> &mut builtin::Handler {
+    pub(crate) fn handler_mut(&mut self) -> &mut builtin::Handler {
         &mut self.store.data_mut().handler
     }
 
@@ -493,6 +621,17 @@ impl Instance {
         *self.handler_mut() = rt.handler.clone().into();
         let ctx = self.store.data_mut();
         ctx.stderr.take().await;
+        if let Some(Deterministic { seed, .. }) = rt.deterministic {
+            self.reseed(seed);
+        }
+    }
+
+    /// Resets all random number generators of this [Instance] to the state of new generators seeded
+    /// with `seed`
+    pub(crate) fn reseed(&mut self, seed: u64) {
+        for rng in &self.store.data().rngs {
+            rng.reseed(seed);
+        }
     }
 
     /// Set actor stderr stream. If another stderr was set, it is replaced and the old one is flushed and shut down.
@@ -540,10 +679,19 @@ impl Instance {
         request: impl AsyncRead + Send + Sync + Unpin + 'static,
         response: impl AsyncWrite + Send + Sync + Unpin + 'static,
     ) -> anyhow::Result<Result<(), String>> {
-        self.as_guest_bindings()
-            .await?
+        let operation = operation.as_ref();
+        let bindings = self.as_guest_bindings().await?;
+        let sampling = self
+            .profiler
+            .as_ref()
+            .and_then(|profiler| profiler.start(operation));
+        let res = bindings
             .call(&mut self.store, operation, request, response)
-            .await
+            .await;
+        if let Some(sampling) = sampling {
+            sampling.finish().await;
+        }
+        res
     }
 
     /// Instantiates and returns a [`GuestInstance`] if exported by the [`Instance`].
@@ -580,13 +728,18 @@ impl GuestBindings {
         ctx.stdout.replace(Box::new(response)).await;
         let res = match self {
             GuestBindings::Command(bindings) => {
-                let wasi = preview2::WasiCtxBuilder::new()
-                    .set_args(&["main.wasm", operation.as_ref()]) // TODO: Configure argv[0]
-                    .set_stdin(ctx.stdin.clone())
-                    .set_stdout(ctx.stdout.clone())
-                    .set_stderr(ctx.stderr.clone())
-                    .build(&mut ctx.table)
-                    .context("failed to build WASI")?;
+                let wasi = wasi_builder(
+                    &ctx.config,
+                    &ctx.preopens,
+                    ctx.deterministic.as_ref(),
+                    &ctx.rngs,
+                    Some(operation.as_ref()),
+                )?
+                .set_stdin(ctx.stdin.clone())
+                .set_stdout(ctx.stdout.clone())
+                .set_stderr(ctx.stderr.clone())
+                .build(&mut ctx.table)
+                .context("failed to build WASI")?;
                 let wasi = replace(&mut ctx.wasi, wasi);
                 trace!("call `wasi:command/command.run`");
                 let res = bindings
@@ -650,6 +803,6 @@ enum InterfaceBindings<T> {
 
 /// Instance of a guest interface `T`
 pub struct InterfaceInstance<T> {
-    store: Mutex<wasmtime::Store<Ctx>>,
-    bindings: InterfaceBindings<T>,
+    store: Arc<Mutex<wasmtime::Store<Ctx>>>,
+    bindings: Arc<InterfaceBindings<T>>,
 }
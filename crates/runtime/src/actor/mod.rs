@@ -1,5 +1,6 @@
 mod component;
 mod module;
+mod wasm;
 
 pub use component::{
     Component, GuestInstance as ComponentGuestInstance, Instance as ComponentInstance,
@@ -9,15 +10,21 @@ pub use module::{
     Config as ModuleConfig, GuestInstance as ModuleGuestInstance, Instance as ModuleInstance,
     Module,
 };
+pub use wasm::Kind;
 
 use crate::capability::logging::logging;
-use crate::capability::{Bus, IncomingHttp, KeyValueReadWrite, Logging, Messaging};
-use crate::Runtime;
+use crate::capability::messaging::types;
+use crate::capability::{
+    blobstore, builtin, Blobstore, BlobstoreHandler, Bus, IncomingHttp, KeyValueAtomic,
+    KeyValueBatch, KeyValueReadWrite, Logging, Messaging, MessagingHandler, OutgoingHttp,
+};
+use crate::{record, Runtime};
 
 use core::fmt::Debug;
 use core::num::NonZeroUsize;
 use core::ops::{Deref, DerefMut};
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::{ensure, Context, Result};
@@ -28,18 +35,42 @@ use tokio::sync::RwLock;
 use tokio::task;
 use tracing::instrument;
 use wascap::jwt;
-use wascap::wasm::extract_claims;
 
 /// Actor instance configuration
 #[derive(Clone, Debug, Default)]
 pub struct Config {
     /// Whether actors are required to be signed to be executed
     pub require_signature: bool,
+    /// `argv[0]` passed to actor instances, `main.wasm` is used if not set
+    pub argv0: Option<String>,
+    /// Environment variables available to actor instances
+    pub env: Vec<(String, String)>,
+    /// Host directories available to actor instances
+    pub preopens: Vec<Preopen>,
+}
+
+/// Host directory made available to actor instances
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Preopen {
+    /// Path to the directory on the host
+    pub host_path: PathBuf,
+    /// Path the directory is mapped to within the guest
+    pub guest_path: String,
+    /// Whether the guest is only allowed to read the directory contents
+    pub read_only: bool,
 }
 
-/// Extracts and validates claims contained within `WebAssembly` binary, if such are found
-fn claims(wasm: impl AsRef<[u8]>) -> Result<Option<jwt::Claims<jwt::Actor>>> {
-    let Some(claims) = extract_claims(wasm).context("failed to extract module claims")? else {
+/// Extracts and validates claims contained within `WebAssembly` binary, if such are found.
+/// Only section headers of the binary are read to locate the claims, which makes this cheap compared
+/// to [`Actor::new`], so it can be used to reject actors before compiling them.
+///
+/// # Errors
+///
+/// Fails if claims are present, but are not valid for `wasm`
+pub fn claims(wasm: impl AsRef<[u8]>) -> Result<Option<jwt::Claims<jwt::Actor>>> {
+    let Some(claims) =
+        wasm::extract_claims(wasm.as_ref()).context("failed to extract module claims")?
+    else {
         return Ok(None)
     };
     let v = jwt::validate_token::<jwt::Actor>(&claims.jwt)
@@ -72,13 +103,8 @@ impl Actor {
     #[instrument(skip(wasm))]
     pub fn new(rt: &Runtime, wasm: impl AsRef<[u8]>) -> Result<Self> {
         let wasm = wasm.as_ref();
-        // TODO: Optimize parsing, add functionality to `wascap` to parse from a custom section
-        // directly
-        match wasmparser::Parser::new(0).parse_all(wasm).next() {
-            Some(Ok(wasmparser::Payload::Version {
-                encoding: wasmparser::Encoding::Component,
-                ..
-            })) => Component::new(rt, wasm).map(Self::Component),
+        match Kind::detect(wasm) {
+            Ok(Kind::Component) => Component::new(rt, wasm).map(Self::Component),
             // fallback to module type
             _ => Module::new(rt, wasm).map(Self::Module),
         }
@@ -119,6 +145,19 @@ impl Actor {
         }
     }
 
+    /// Set [`Config`] used for all instances of this [Actor], overriding the one configured on the [Runtime].
+    pub fn set_config(&mut self, config: Config) -> &mut Self {
+        match self {
+            Self::Module(module) => {
+                module.set_config(config);
+            }
+            Self::Component(component) => {
+                component.set_config(config);
+            }
+        }
+        self
+    }
+
     /// Like [Self::instantiate], but moves the [Actor].
     #[instrument]
     pub async fn into_instance(self) -> anyhow::Result<Instance> {
@@ -215,6 +254,32 @@ impl Actor {
             .into_logging()
             .await
     }
+
+    /// Instantiates and returns a [`MessagingHandlerInstance`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if either instantiation fails or no messaging handler bindings are exported by the [`Instance`]
+    pub async fn as_messaging_handler(&self) -> anyhow::Result<MessagingHandlerInstance> {
+        self.instantiate()
+            .await
+            .context("failed to instantiate actor")?
+            .into_messaging_handler()
+            .await
+    }
+
+    /// Instantiates and returns a [`BlobstoreHandlerInstance`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if either instantiation fails or no blobstore handler bindings are exported by the [`Instance`]
+    pub async fn as_blobstore_handler(&self) -> anyhow::Result<BlobstoreHandlerInstance> {
+        self.instantiate()
+            .await
+            .context("failed to instantiate actor")?
+            .into_blobstore_handler()
+            .await
+    }
 }
 
 /// A pre-loaded, configured wasmCloud actor instance, which is either a module or a component
@@ -243,6 +308,22 @@ pub enum LoggingInstance {
     Component(ComponentInterfaceInstance<component::logging_bindings::Logging>),
 }
 
+/// A pre-loaded, configured [`MessagingHandler`] instance, which is either a module or a component
+pub enum MessagingHandlerInstance {
+    /// WebAssembly module containing an actor
+    Module(ModuleGuestInstance),
+    /// WebAssembly component containing an actor
+    Component(ComponentInterfaceInstance<component::messaging_handler_bindings::MessagingHandler>),
+}
+
+/// A pre-loaded, configured [`BlobstoreHandler`] instance, which is either a module or a component
+pub enum BlobstoreHandlerInstance {
+    /// WebAssembly module containing an actor
+    Module(ModuleGuestInstance),
+    /// WebAssembly component containing an actor
+    Component(ComponentInterfaceInstance<component::blobstore_handler_bindings::BlobstoreHandler>),
+}
+
 /// A pre-loaded, configured [`IncomingHttp`] instance, which is either a module or a component
 pub enum IncomingHttpInstance {
     /// WebAssembly module containing an actor
@@ -294,6 +375,43 @@ impl Logging for LoggingInstance {
     }
 }
 
+#[async_trait]
+impl MessagingHandler for MessagingHandlerInstance {
+    async fn handle_message(&self, msg: types::BrokerMessage) -> anyhow::Result<()> {
+        match self {
+            Self::Module(module) => module.handle_message(msg),
+            Self::Component(component) => component.handle_message(msg),
+        }
+        .await
+    }
+}
+
+#[async_trait]
+impl BlobstoreHandler for BlobstoreHandlerInstance {
+    async fn receive_chunk(&self, chunk: blobstore::types::Chunk) -> anyhow::Result<bool> {
+        match self {
+            Self::Module(module) => module.receive_chunk(chunk),
+            Self::Component(component) => component.receive_chunk(chunk),
+        }
+        .await
+    }
+}
+
+impl IncomingHttpInstance {
+    /// Handle a msgpack-encoded `wasmcloud:httpserver` request, returning the msgpack-encoded
+    /// response. Actors not exporting `wasi:http/incoming-handler` receive the request as-is.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the request cannot be decoded, the actor call fails or the actor returns an error
+    pub async fn handle_compat(&self, request: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Module(module) => module.handle_http_compat(request).await,
+            Self::Component(component) => component.handle_compat(request).await,
+        }
+    }
+}
+
 #[async_trait]
 impl IncomingHttp for IncomingHttpInstance {
     async fn handle(
@@ -317,6 +435,19 @@ impl Instance {
         }
     }
 
+    /// Set [`Blobstore`] handler for this [Instance].
+    pub fn blobstore(&mut self, blobstore: Arc<dyn Blobstore + Send + Sync>) -> &mut Self {
+        match self {
+            Self::Module(module) => {
+                module.blobstore(blobstore);
+            }
+            Self::Component(component) => {
+                component.blobstore(blobstore);
+            }
+        }
+        self
+    }
+
     /// Set [`Bus`] handler for this [Instance].
     pub fn bus(&mut self, bus: Arc<dyn Bus + Send + Sync>) -> &mut Self {
         match self {
@@ -346,6 +477,38 @@ impl Instance {
         self
     }
 
+    /// Set [`KeyValueAtomic`] handler for this [Instance].
+    pub fn keyvalue_atomic(
+        &mut self,
+        keyvalue_atomic: Arc<dyn KeyValueAtomic + Send + Sync>,
+    ) -> &mut Self {
+        match self {
+            Self::Module(module) => {
+                module.keyvalue_atomic(keyvalue_atomic);
+            }
+            Self::Component(component) => {
+                component.keyvalue_atomic(keyvalue_atomic);
+            }
+        }
+        self
+    }
+
+    /// Set [`KeyValueBatch`] handler for this [Instance].
+    pub fn keyvalue_batch(
+        &mut self,
+        keyvalue_batch: Arc<dyn KeyValueBatch + Send + Sync>,
+    ) -> &mut Self {
+        match self {
+            Self::Module(module) => {
+                module.keyvalue_batch(keyvalue_batch);
+            }
+            Self::Component(component) => {
+                component.keyvalue_batch(keyvalue_batch);
+            }
+        }
+        self
+    }
+
     /// Set [`KeyValueReadWrite`] handler for this [Instance].
     pub fn keyvalue_readwrite(
         &mut self,
@@ -388,6 +551,22 @@ impl Instance {
         self
     }
 
+    /// Set [`OutgoingHttp`] handler for this [Instance].
+    pub fn outgoing_http(
+        &mut self,
+        outgoing_http: Arc<dyn OutgoingHttp + Send + Sync>,
+    ) -> &mut Self {
+        match self {
+            Self::Module(module) => {
+                module.outgoing_http(outgoing_http);
+            }
+            Self::Component(component) => {
+                component.outgoing_http(outgoing_http);
+            }
+        }
+        self
+    }
+
     /// Set actor stderr stream. If another stderr was set, it is replaced and the old one is flushed and shut down if supported by underlying actor implementation.
     ///
     /// # Errors
@@ -408,7 +587,24 @@ impl Instance {
         Ok(self)
     }
 
-    /// Invoke an operation on an [Instance] producing a response
+    pub(crate) fn handler_mut(&mut self) -> &mut builtin::Handler {
+        match self {
+            Self::Module(module) => module.handler_mut(),
+            Self::Component(component) => component.handler_mut(),
+        }
+    }
+
+    /// Resets all random number generators of the [Instance] to the state of new generators seeded
+    /// with `seed`
+    pub(crate) fn reseed(&mut self, seed: u64) {
+        match self {
+            Self::Module(module) => module.reseed(seed),
+            Self::Component(component) => component.reseed(seed),
+        }
+    }
+
+    /// Invoke an operation on an [Instance] producing a response.
+    /// The invocation is recorded or replayed if configured on the [Runtime].
     ///
     /// # Errors
     ///
@@ -420,6 +616,19 @@ impl Instance {
         operation: impl AsRef<str>,
         request: impl AsyncRead + Send + Sync + Unpin + 'static,
         response: impl AsyncWrite + Send + Sync + Unpin + 'static,
+    ) -> anyhow::Result<Result<(), String>> {
+        if let Some(mode) = self.handler_mut().record_mode().cloned() {
+            return record::call(self, &mode, operation.as_ref(), request, response).await;
+        }
+        self.call_direct(operation, request, response).await
+    }
+
+    /// Like [Self::call], but never records or replays the invocation
+    pub(crate) async fn call_direct(
+        &mut self,
+        operation: impl AsRef<str>,
+        request: impl AsyncRead + Send + Sync + Unpin + 'static,
+        response: impl AsyncWrite + Send + Sync + Unpin + 'static,
     ) -> anyhow::Result<Result<(), String>> {
         match self {
             Self::Module(module) => module
@@ -478,6 +687,40 @@ impl Instance {
                 .map(LoggingInstance::Component),
         }
     }
+
+    /// Instantiates and returns a [`MessagingHandlerInstance`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if no messaging handler bindings are exported by the [`Instance`]
+    pub async fn into_messaging_handler(self) -> anyhow::Result<MessagingHandlerInstance> {
+        match self {
+            Self::Module(module) => Ok(MessagingHandlerInstance::Module(
+                ModuleGuestInstance::from(module),
+            )),
+            Self::Component(component) => component
+                .into_messaging_handler()
+                .await
+                .map(MessagingHandlerInstance::Component),
+        }
+    }
+
+    /// Instantiates and returns a [`BlobstoreHandlerInstance`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if no blobstore handler bindings are exported by the [`Instance`]
+    pub async fn into_blobstore_handler(self) -> anyhow::Result<BlobstoreHandlerInstance> {
+        match self {
+            Self::Module(module) => Ok(BlobstoreHandlerInstance::Module(
+                ModuleGuestInstance::from(module),
+            )),
+            Self::Component(component) => component
+                .into_blobstore_handler()
+                .await
+                .map(BlobstoreHandlerInstance::Component),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -612,6 +855,11 @@ impl InstancePool {
         self.instances.read().await.limit
     }
 
+    /// Return the number of idle actor instances currently held by the pool.
+    pub async fn pooled(&self) -> usize {
+        self.instances.read().await.instances.len()
+    }
+
     /// Resize the pool to hold at most `limit` actor instances returning the old value.
     pub async fn set_limit(&self, limit: Option<NonZeroUsize>) -> Option<NonZeroUsize> {
         self.instances.write().await.set_limit(limit)
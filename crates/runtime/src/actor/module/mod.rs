@@ -1,31 +1,49 @@
 mod wasmbus;
 
-use wasmbus::guest_call;
-
-use crate::actor::claims;
+use wasmbus::{guest_call, guest_call_v2, rpc_version};
+
+use crate::actor::{claims, Preopen};
+use crate::capability::blobstore;
+use crate::capability::compat::{
+    Chunk, ChunkResponse, HttpRequest, HttpResponse, SubMessage, BLOBSTORE_RECEIVE_CHUNK,
+    HTTP_SERVER_HANDLE_REQUEST, MESSAGING_HANDLE_MESSAGE,
+};
 use crate::capability::logging::logging;
-use crate::capability::{builtin, Bus, IncomingHttp, KeyValueReadWrite, Logging, Messaging};
-use crate::Runtime;
+use crate::capability::messaging::types;
+use crate::capability::{
+    builtin, Blobstore, BlobstoreHandler, Bus, IncomingHttp, KeyValueAtomic, KeyValueBatch,
+    KeyValueReadWrite, Logging, Messaging, MessagingHandler, OutgoingHttp,
+};
+use crate::deterministic::SharedRng;
+use crate::profiling::{InstanceProfiler, Profiler};
+use crate::{ActorConfig, Deterministic, Runtime};
 
 use core::any::Any;
 use core::fmt::{self, Debug};
 
 use std::io::Cursor;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail, ensure, Context};
 use async_trait::async_trait;
 use futures::lock::Mutex;
+use futures::try_join;
 use serde_json::json;
 use tokio::io::{sink, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::runtime::Handle;
 use tokio::task;
 use tracing::{instrument, trace};
 use wascap::jwt;
-use wasi_common::file::{FdFlags, FileType};
+use wasi_common::dir::{OpenResult, ReaddirCursor, ReaddirEntity, WasiDir};
+use wasi_common::file::{FdFlags, FileType, Filestat, OFlags};
 use wasi_common::pipe::WritePipe;
+use wasi_common::ErrorExt;
 use wasmtime::TypedFunc;
-use wasmtime_wasi::{WasiCtxBuilder, WasiFile};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiFile};
+
+/// Capacity of the in-memory pipe used to read responses produced by the guest
+const RESPONSE_PIPE_CAPACITY: usize = 64 * 1024;
 
 /// Actor module instance configuration
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -115,8 +133,66 @@ impl<T: AsyncWrite + Sync + Send + Unpin + 'static> WasiFile for AsyncWritePipe<
     }
 }
 
+/// [`WasiDir`] wrapper, which only allows read access to the wrapped directory
+struct ReadOnlyDir(Box<dyn WasiDir>);
+
+#[async_trait]
+impl WasiDir for ReadOnlyDir {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    async fn open_file(
+        &self,
+        symlink_follow: bool,
+        path: &str,
+        oflags: OFlags,
+        read: bool,
+        write: bool,
+        fdflags: FdFlags,
+    ) -> Result<OpenResult, wasi_common::Error> {
+        if write
+            || oflags.intersects(OFlags::CREATE | OFlags::EXCLUSIVE | OFlags::TRUNCATE)
+            || fdflags.contains(FdFlags::APPEND)
+        {
+            return Err(wasi_common::Error::perm());
+        }
+        match self
+            .0
+            .open_file(symlink_follow, path, oflags, read, write, fdflags)
+            .await?
+        {
+            OpenResult::Dir(dir) => Ok(OpenResult::Dir(Box::new(Self(dir)))),
+            file @ OpenResult::File(..) => Ok(file),
+        }
+    }
+    async fn readdir(
+        &self,
+        cursor: ReaddirCursor,
+    ) -> Result<
+        Box<dyn Iterator<Item = Result<ReaddirEntity, wasi_common::Error>> + Send>,
+        wasi_common::Error,
+    > {
+        self.0.readdir(cursor).await
+    }
+    async fn read_link(&self, path: &str) -> Result<PathBuf, wasi_common::Error> {
+        self.0.read_link(path).await
+    }
+    async fn get_filestat(&self) -> Result<Filestat, wasi_common::Error> {
+        self.0.get_filestat().await
+    }
+    async fn get_path_filestat(
+        &self,
+        path: &str,
+        follow_symlinks: bool,
+    ) -> Result<Filestat, wasi_common::Error> {
+        self.0.get_path_filestat(path, follow_symlinks).await
+    }
+}
+
 struct Ctx {
     wasi: wasmtime_wasi::WasiCtx,
+    /// Random number generator used by `wasi`, if deterministic execution is enabled
+    wasi_rng: SharedRng,
     wasmbus: wasmbus::Ctx,
 }
 
@@ -140,8 +216,11 @@ impl Ctx {
 pub struct Module {
     claims: Option<jwt::Claims<jwt::Actor>>,
     config: Config,
+    actor_config: ActorConfig,
     handler: builtin::HandlerBuilder,
     module: wasmtime::Module,
+    deterministic: Option<Deterministic>,
+    profiler: Option<Profiler>,
 }
 
 impl Debug for Module {
@@ -149,7 +228,10 @@ impl Debug for Module {
         f.debug_struct("Module")
             .field("claims", &self.claims)
             .field("config", &self.config)
+            .field("actor_config", &self.actor_config)
             .field("handler", &self.handler)
+            .field("deterministic", &self.deterministic)
+            .field("profiler", &self.profiler)
             .field("runtime", &"wasmtime")
             .finish()
     }
@@ -161,26 +243,79 @@ impl Module {
     pub fn claims(&self) -> Option<&jwt::Claims<jwt::Actor>> {
         self.claims.as_ref()
     }
+
+    /// Set [`ActorConfig`] used for all instances of this [Module].
+    pub fn set_config(&mut self, config: ActorConfig) -> &mut Self {
+        self.actor_config = config;
+        self
+    }
+}
+
+/// Configures argv, environment and preopened directories of [`WasiCtx`] according to [`ActorConfig`]
+fn configure_wasi(wasi: &mut WasiCtx, config: &ActorConfig) -> anyhow::Result<()> {
+    wasi.push_arg(config.argv0.as_deref().unwrap_or("main.wasm"))
+        .context("failed to set argv[0]")?;
+    for (k, v) in &config.env {
+        wasi.push_env(k, v)
+            .with_context(|| format!("failed to set environment variable `{k}`"))?;
+    }
+    for Preopen {
+        host_path,
+        guest_path,
+        read_only,
+    } in &config.preopens
+    {
+        let dir =
+            wasmtime_wasi::Dir::open_ambient_dir(host_path, wasmtime_wasi::ambient_authority())
+                .with_context(|| format!("failed to open `{}`", host_path.display()))?;
+        let dir: Box<dyn WasiDir> = Box::new(wasmtime_wasi::dir::Dir::from_cap_std(dir));
+        // NOTE: WASI preview1 has no way to restrict access rights of a preopened directory,
+        // so read-only access is enforced by the host instead
+        let dir = if *read_only {
+            Box::new(ReadOnlyDir(dir))
+        } else {
+            dir
+        };
+        wasi.push_preopened_dir(dir, guest_path)
+            .with_context(|| format!("failed to preopen `{guest_path}`"))?;
+    }
+    Ok(())
 }
 
 async fn instantiate(
     module: &wasmtime::Module,
     config: &Config,
+    actor_config: &ActorConfig,
     handler: impl Into<builtin::Handler>,
+    deterministic: Option<&Deterministic>,
+    profiler: Option<&Profiler>,
+    claims: Option<&jwt::Claims<jwt::Actor>>,
 ) -> anyhow::Result<Instance> {
-    let wasi = WasiCtxBuilder::new();
-    let wasi = wasi
-        .arg("main.wasm")
-        .context("failed to set argv[0]")?
-        .build();
+    let (mut wasi, wasi_rng, rng) = if let Some(deterministic) = deterministic {
+        let wasi_rng = deterministic.rng();
+        (
+            deterministic.module_wasi(wasi_rng.clone()),
+            wasi_rng,
+            deterministic.rng(),
+        )
+    } else {
+        (
+            WasiCtxBuilder::new().build(),
+            SharedRng::from_entropy(),
+            SharedRng::from_entropy(),
+        )
+    };
+    configure_wasi(&mut wasi, actor_config)?;
     let ctx = Ctx {
         wasi,
-        wasmbus: wasmbus::Ctx::new(handler),
+        wasi_rng,
+        wasmbus: wasmbus::Ctx::new(handler, rng),
     };
 
     let engine = module.engine();
 
     let mut store = wasmtime::Store::new(engine, ctx);
+    let profiler = profiler.map(|profiler| InstanceProfiler::new(profiler, claims, &mut store));
     let mut linker = wasmtime::Linker::<Ctx>::new(engine);
 
     wasmtime_wasi::add_to_linker(&mut linker, |ctx| &mut ctx.wasi)
@@ -201,8 +336,28 @@ async fn instantiate(
         .await
         .context("failed to instantiate module")?;
     let start = instance.get_typed_func(&mut store, "_start");
-    let guest_call = instance
-        .get_typed_func::<guest_call::Params, guest_call::Result>(&mut store, "__guest_call");
+    let version = match instance.get_typed_func::<rpc_version::Params, rpc_version::Result>(
+        &mut store,
+        "__wasmbus_rpc_version",
+    ) {
+        Ok(version) => version
+            .call_async(&mut store, ())
+            .await
+            .context("failed to call `__wasmbus_rpc_version`")?,
+        Err(_) => 1,
+    };
+    let guest_call = if version >= 2 {
+        instance
+            .get_typed_func::<guest_call_v2::Params, guest_call_v2::Result>(
+                &mut store,
+                "__guest_call_v2",
+            )
+            .map(GuestCall::V2)
+    } else {
+        instance
+            .get_typed_func::<guest_call::Params, guest_call::Result>(&mut store, "__guest_call")
+            .map(GuestCall::V1)
+    };
     let (start, guest_call) = match (start, guest_call) {
         (Ok(start), Ok(guest_call)) => (Some(start), Some(guest_call)),
         (Ok(start), Err(_)) => (Some(start), None),
@@ -215,6 +370,7 @@ async fn instantiate(
         store,
         guest_call,
         start,
+        profiler,
     })
 }
 
@@ -230,13 +386,25 @@ impl Module {
             claims,
             handler: rt.handler.clone(),
             config: rt.module_config,
+            actor_config: rt.actor_config.clone(),
+            deterministic: rt.deterministic.clone(),
+            profiler: rt.profiler.clone(),
         })
     }
 
     /// Like [Self::instantiate], but moves the [Module].
     #[instrument]
     pub async fn into_instance(self) -> anyhow::Result<Instance> {
-        instantiate(&self.module, &self.config, self.handler).await
+        instantiate(
+            &self.module,
+            &self.config,
+            &self.actor_config,
+            self.handler,
+            self.deterministic.as_ref(),
+            self.profiler.as_ref(),
+            self.claims.as_ref(),
+        )
+        .await
     }
 
     /// Like [Self::instantiate], but moves the [Module] and returns the associated [jwt::Claims].
@@ -244,14 +412,32 @@ impl Module {
     pub async fn into_instance_claims(
         self,
     ) -> anyhow::Result<(Instance, Option<jwt::Claims<jwt::Actor>>)> {
-        let instance = instantiate(&self.module, &self.config, self.handler).await?;
+        let instance = instantiate(
+            &self.module,
+            &self.config,
+            &self.actor_config,
+            self.handler,
+            self.deterministic.as_ref(),
+            self.profiler.as_ref(),
+            self.claims.as_ref(),
+        )
+        .await?;
         Ok((instance, self.claims))
     }
 
     /// Instantiates a [Module] and returns the resulting [Instance].
     #[instrument]
     pub async fn instantiate(&self) -> anyhow::Result<Instance> {
-        instantiate(&self.module, &self.config, self.handler.clone()).await
+        instantiate(
+            &self.module,
+            &self.config,
+            &self.actor_config,
+            self.handler.clone(),
+            self.deterministic.as_ref(),
+            self.profiler.as_ref(),
+            self.claims.as_ref(),
+        )
+        .await
     }
 
     /// Instantiate a [Module] producing an [Instance] and invoke an operation on it using [Instance::call]
@@ -270,11 +456,21 @@ impl Module {
     }
 }
 
+/// wasmbus guest call export, version of which is negotiated via `__wasmbus_rpc_version`
+#[derive(Clone, Copy)]
+enum GuestCall {
+    /// `__guest_call` of wasmbus v1, which buffers both the request and the response
+    V1(TypedFunc<guest_call::Params, guest_call::Result>),
+    /// `__guest_call_v2` of wasmbus v2, which streams both the request and the response
+    V2(TypedFunc<guest_call_v2::Params, guest_call_v2::Result>),
+}
+
 /// An instance of a [Module]
 pub struct Instance {
     store: wasmtime::Store<Ctx>,
-    guest_call: Option<TypedFunc<guest_call::Params, guest_call::Result>>,
+    guest_call: Option<GuestCall>,
     start: Option<TypedFunc<(), ()>>,
+    profiler: Option<InstanceProfiler>,
 }
 
 impl Debug for Instance {
@@ -289,17 +485,35 @@ impl Debug for Instance {
 
 impl Instance {
     /// Returns a mutable reference to embedded [`builtin::Handler`]
-    fn handler_mut(&mut self) -> &mut builtin::Handler {
+    pub(crate) fn handler_mut(&mut self) -> &mut builtin::Handler {
         &mut self.store.data_mut().wasmbus.handler
     }
 
     /// Reset [`Instance`] state to defaults
     pub fn reset(&mut self, rt: &Runtime) {
         *self.handler_mut() = rt.handler.clone().into();
-        self.store
-            .data_mut()
-            .wasi
+        let ctx = self.store.data_mut();
+        ctx.wasi
             .set_stderr(Box::new(WritePipe::new(std::io::sink())));
+        if let Some(Deterministic { seed, .. }) = rt.deterministic {
+            self.reseed(seed);
+        } else {
+            ctx.wasmbus.rng = SharedRng::from_entropy();
+        }
+    }
+
+    /// Resets all random number generators of this [Instance] to the state of new generators seeded
+    /// with `seed`
+    pub(crate) fn reseed(&mut self, seed: u64) {
+        let ctx = self.store.data_mut();
+        ctx.wasi_rng.reseed(seed);
+        ctx.wasmbus.rng.reseed(seed);
+    }
+
+    /// Set [`Blobstore`] handler for this [Instance].
+    pub fn blobstore(&mut self, blobstore: Arc<dyn Blobstore + Send + Sync>) -> &mut Self {
+        self.handler_mut().replace_blobstore(blobstore);
+        self
     }
 
     /// Set [`Bus`] handler for this [Instance].
@@ -317,6 +531,24 @@ impl Instance {
         self
     }
 
+    /// Set [`KeyValueAtomic`] handler for this [Instance].
+    pub fn keyvalue_atomic(
+        &mut self,
+        keyvalue_atomic: Arc<dyn KeyValueAtomic + Send + Sync>,
+    ) -> &mut Self {
+        self.handler_mut().replace_keyvalue_atomic(keyvalue_atomic);
+        self
+    }
+
+    /// Set [`KeyValueBatch`] handler for this [Instance].
+    pub fn keyvalue_batch(
+        &mut self,
+        keyvalue_batch: Arc<dyn KeyValueBatch + Send + Sync>,
+    ) -> &mut Self {
+        self.handler_mut().replace_keyvalue_batch(keyvalue_batch);
+        self
+    }
+
     /// Set [`KeyValueReadWrite`] handler for this [Instance].
     pub fn keyvalue_readwrite(
         &mut self,
@@ -339,6 +571,15 @@ impl Instance {
         self
     }
 
+    /// Set [`OutgoingHttp`] handler for this [Instance].
+    pub fn outgoing_http(
+        &mut self,
+        outgoing_http: Arc<dyn OutgoingHttp + Send + Sync>,
+    ) -> &mut Self {
+        self.handler_mut().replace_outgoing_http(outgoing_http);
+        self
+    }
+
     /// Set actor stderr stream. If another stderr was set, it is replaced.
     pub fn stderr(&mut self, stderr: impl AsyncWrite + Send + Sync + Unpin + 'static) -> &mut Self {
         let stderr = AsyncWritePipe(Arc::new(Mutex::new(stderr)));
@@ -351,13 +592,29 @@ impl Instance {
     pub async fn call(
         &mut self,
         operation: impl AsRef<str>,
+        request: impl AsyncRead + Send + Sync + Unpin + 'static,
+        response: impl AsyncWrite + Send + Sync + Unpin + 'static,
+    ) -> anyhow::Result<Result<(), String>> {
+        let operation = operation.as_ref();
+        let sampling = self
+            .profiler
+            .as_ref()
+            .and_then(|profiler| profiler.start(operation));
+        let res = self.call_guest(operation, request, response).await;
+        if let Some(sampling) = sampling {
+            sampling.finish().await;
+        }
+        res
+    }
+
+    async fn call_guest(
+        &mut self,
+        operation: &str,
         mut request: impl AsyncRead + Send + Sync + Unpin + 'static,
         mut response: impl AsyncWrite + Send + Sync + Unpin + 'static,
     ) -> anyhow::Result<Result<(), String>> {
         self.store.data_mut().reset();
 
-        // TODO: Introduce wasmbus v2 with two-way streaming
-
         let guest_call = match (self.start, self.guest_call) {
             (Some(start), Some(guest_call)) => {
                 trace!("call `_start`");
@@ -373,7 +630,7 @@ impl Instance {
                 self.store
                     .data_mut()
                     .wasi
-                    .push_arg(operation.as_ref())
+                    .push_arg(operation)
                     .context("failed to push arg")?;
                 let stdin = AsyncReadPipe(Arc::new(Mutex::new(request)));
                 let stdout = AsyncWritePipe(Arc::new(Mutex::new(response)));
@@ -392,30 +649,54 @@ impl Instance {
             }
         };
 
-        let operation = operation.as_ref();
         let operation_len = operation
             .len()
             .try_into()
             .context("operation string length does not fit in u32")?;
 
-        let mut payload = vec![];
-        let payload_len = request
-            .read_to_end(&mut payload)
-            .await
-            .context("failed to read payload")?
-            .try_into()
-            .context("payload length does not fit in u32")?;
+        let (code, mut response) = match guest_call {
+            GuestCall::V1(guest_call) => {
+                let mut payload = vec![];
+                let payload_len = request
+                    .read_to_end(&mut payload)
+                    .await
+                    .context("failed to read payload")?
+                    .try_into()
+                    .context("payload length does not fit in u32")?;
 
-        self.store
-            .data_mut()
-            .wasmbus
-            .set_guest_call(operation.to_string(), payload);
+                self.store
+                    .data_mut()
+                    .wasmbus
+                    .set_guest_call(operation.to_string(), payload);
 
-        trace!("call `_guest_call`");
-        let code = guest_call
-            .call_async(&mut self.store, (operation_len, payload_len))
-            .await
-            .context("failed to call `__guest_call`")?;
+                trace!("call `__guest_call`");
+                let code = guest_call
+                    .call_async(&mut self.store, (operation_len, payload_len))
+                    .await
+                    .context("failed to call `__guest_call`")?;
+                (code, Some(response))
+            }
+            GuestCall::V2(guest_call) => {
+                self.store.data_mut().wasmbus.set_guest_stream(
+                    operation.to_string(),
+                    Box::new(request),
+                    Box::new(response),
+                );
+
+                trace!("call `__guest_call_v2`");
+                let code = guest_call
+                    .call_async(&mut self.store, (operation_len,))
+                    .await;
+                // NOTE: The response stream must be closed even if the call fails, since the caller may be
+                // waiting for it to finish
+                if let Some(mut response) =
+                    self.store.data_mut().wasmbus.take_guest_response_stream()
+                {
+                    response.flush().await.context("failed to flush response")?;
+                }
+                (code.context("failed to call `__guest_call_v2`")?, None)
+            }
+        };
         if let Some(err) = self.store.data_mut().wasmbus.take_guest_error() {
             return Ok(Err(err));
         } else if let Some(err) = self.store.data_mut().wasmbus.take_host_error() {
@@ -427,7 +708,7 @@ impl Instance {
         if !console_log.is_empty() {
             trace!(?console_log);
         }
-        if let Some(res) = res {
+        if let (Some(res), Some(response)) = (res, response.as_mut()) {
             response
                 .write_all(&res)
                 .await
@@ -490,27 +771,92 @@ impl Logging for GuestInstance {
     }
 }
 
+#[async_trait]
+impl MessagingHandler for GuestInstance {
+    #[instrument(skip(self))]
+    async fn handle_message(&self, msg: types::BrokerMessage) -> anyhow::Result<()> {
+        let request =
+            rmp_serde::to_vec_named(&SubMessage::from(msg)).context("failed to encode request")?;
+        self.call(MESSAGING_HANDLE_MESSAGE, Cursor::new(request), sink())
+            .await
+            .context("failed to call actor")?
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+#[async_trait]
+impl BlobstoreHandler for GuestInstance {
+    #[instrument(skip(self, chunk))]
+    async fn receive_chunk(&self, chunk: blobstore::types::Chunk) -> anyhow::Result<bool> {
+        let request =
+            rmp_serde::to_vec_named(&Chunk::from(chunk)).context("failed to encode request")?;
+        let (response_tx, mut response_rx) = tokio::io::duplex(RESPONSE_PIPE_CAPACITY);
+        let mut response = vec![];
+        let (res, _) = try_join!(
+            async {
+                self.call(BLOBSTORE_RECEIVE_CHUNK, Cursor::new(request), response_tx)
+                    .await
+                    .context("failed to call actor")
+            },
+            async {
+                response_rx
+                    .read_to_end(&mut response)
+                    .await
+                    .context("failed to read response")
+            },
+        )?;
+        res.map_err(|e| anyhow!(e))?;
+        let ChunkResponse { cancel_download } =
+            rmp_serde::from_slice(&response).context("failed to decode response")?;
+        Ok(cancel_download)
+    }
+}
+
+impl GuestInstance {
+    /// Handle a msgpack-encoded `wasmcloud:httpserver` request, returning the msgpack-encoded
+    /// response produced by the actor. The request is passed to the actor as-is.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the actor call fails or the actor returns an error
+    #[instrument(skip_all)]
+    pub async fn handle_http_compat(&self, request: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        let (response_tx, mut response_rx) = tokio::io::duplex(RESPONSE_PIPE_CAPACITY);
+        let mut response = vec![];
+        let (res, _) = try_join!(
+            async {
+                self.call(
+                    HTTP_SERVER_HANDLE_REQUEST,
+                    Cursor::new(request),
+                    response_tx,
+                )
+                .await
+                .context("failed to call actor")
+            },
+            async {
+                response_rx
+                    .read_to_end(&mut response)
+                    .await
+                    .context("failed to read response")
+            },
+        )?;
+        res.map_err(|e| anyhow!(e))?;
+        Ok(response)
+    }
+}
+
 #[async_trait]
 impl IncomingHttp for GuestInstance {
-    #[allow(unused)] // TODO: Remove
     #[instrument(skip_all)]
     async fn handle(
         &self,
         request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
     ) -> anyhow::Result<http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>> {
-        let (
-            http::request::Parts {
-                method,
-                uri,
-                headers,
-                ..
-            },
-            body,
-        ) = request.into_parts();
-        let path_with_query = uri.path_and_query().map(http::uri::PathAndQuery::as_str);
-        let scheme = uri.scheme_str();
-        let authority = uri.authority().map(http::uri::Authority::as_str);
-        self.0.lock().await;
-        bail!("unsupported"); // TODO: Support
+        let request = HttpRequest::from_http(request).await?;
+        let request = rmp_serde::to_vec_named(&request).context("failed to encode request")?;
+        let response = self.handle_http_compat(request).await?;
+        let response: HttpResponse =
+            rmp_serde::from_slice(&response).context("failed to decode response")?;
+        response.try_into()
     }
 }
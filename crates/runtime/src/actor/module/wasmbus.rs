@@ -1,13 +1,19 @@
 use crate::capability::builtin::{self, Bus, Logging};
 use crate::capability::logging::logging;
+use crate::deterministic::SharedRng;
 
 use core::fmt::{self, Debug};
+use core::mem;
+
+use std::collections::HashMap;
+use std::io::{self, Cursor};
 
 use anyhow::{anyhow, bail, Context, Result};
-use futures::try_join;
-use rand::{thread_rng, Rng, RngCore};
+use futures::{try_join, FutureExt};
+use rand::{Rng, RngCore};
 use serde::Deserialize;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::task::JoinHandle;
 use tracing::{instrument, trace, trace_span, warn};
 
 pub mod guest_call {
@@ -17,6 +23,24 @@ pub mod guest_call {
     pub type Result = wasm::usize;
 }
 
+/// `__guest_call_v2` export of wasmbus v2, which only receives the operation length.
+/// Request and response are streamed using `__guest_request_read` and `__guest_response_write`.
+pub mod guest_call_v2 {
+    use super::wasm;
+
+    pub type Params = (wasm::usize,);
+    pub type Result = wasm::usize;
+}
+
+/// Returns the wasmbus ABI version exported by the guest in `__wasmbus_rpc_version`, defaulting to 1
+pub mod rpc_version {
+    pub type Params = ();
+    pub type Result = u32;
+}
+
+/// Maximum amount of bytes read by the host from a stream at once on behalf of the guest
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
 mod wasm {
     #[allow(non_camel_case_types)]
     pub type ptr = u32;
@@ -27,14 +51,38 @@ mod wasm {
     pub const SUCCESS: usize = 1;
 }
 
+/// Streaming host call started by the guest using `__host_call_start`
+enum HostCall {
+    /// Call handled by the host itself, which is buffered and processed once the request is closed
+    Builtin {
+        binding: String,
+        namespace: String,
+        operation: String,
+        request: Vec<u8>,
+        response: Option<Cursor<Vec<u8>>>,
+    },
+    /// Call handled by [`Bus`], which is streamed
+    Bus {
+        request: Option<Box<dyn AsyncWrite + Sync + Send + Unpin>>,
+        response: Box<dyn AsyncRead + Sync + Send + Unpin>,
+        result: JoinHandle<Result<(), String>>,
+    },
+}
+
 pub struct Ctx {
     console_log: Vec<String>,
     guest_call: Option<(String, Vec<u8>)>,
     guest_error: Option<String>,
     guest_response: Option<Vec<u8>>,
+    guest_operation: Option<String>,
+    guest_request_stream: Option<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+    guest_response_stream: Option<Box<dyn AsyncWrite + Sync + Send + Unpin>>,
+    host_calls: HashMap<wasm::usize, HostCall>,
+    next_host_call: wasm::usize,
     host_error: Option<String>,
     host_response: Option<Vec<u8>>,
     pub(crate) handler: builtin::Handler,
+    pub(crate) rng: SharedRng,
 }
 
 impl Debug for Ctx {
@@ -44,6 +92,8 @@ impl Debug for Ctx {
             .field("guest_call", &self.guest_call)
             .field("guest_error", &self.guest_error)
             .field("guest_response", &self.guest_response)
+            .field("guest_operation", &self.guest_operation)
+            .field("host_calls", &self.host_calls.len())
             .field("host_error", &self.host_error)
             .field("host_response", &self.host_response)
             .finish()
@@ -51,15 +101,21 @@ impl Debug for Ctx {
 }
 
 impl Ctx {
-    pub(super) fn new(handler: impl Into<builtin::Handler>) -> Self {
+    pub(super) fn new(handler: impl Into<builtin::Handler>, rng: SharedRng) -> Self {
         Self {
             console_log: Vec::default(),
             guest_call: None,
             guest_error: None,
             guest_response: None,
+            guest_operation: None,
+            guest_request_stream: None,
+            guest_response_stream: None,
+            host_calls: HashMap::default(),
+            next_host_call: 0,
             host_error: None,
             host_response: None,
             handler: handler.into(),
+            rng,
         }
     }
 
@@ -68,6 +124,10 @@ impl Ctx {
         self.guest_call = None;
         self.guest_error = None;
         self.guest_response = None;
+        self.guest_operation = None;
+        self.guest_request_stream = None;
+        self.guest_response_stream = None;
+        self.host_calls.clear();
         self.host_error = None;
         self.host_response = None;
     }
@@ -76,6 +136,27 @@ impl Ctx {
         self.guest_call = Some((operation, payload));
     }
 
+    /// Sets a wasmbus v2 guest call, which streams the request from `request` and the response to `response`
+    pub fn set_guest_stream(
+        &mut self,
+        operation: String,
+        request: Box<dyn AsyncRead + Sync + Send + Unpin>,
+        response: Box<dyn AsyncWrite + Sync + Send + Unpin>,
+    ) {
+        self.guest_operation = Some(operation);
+        self.guest_request_stream = Some(request);
+        self.guest_response_stream = Some(response);
+    }
+
+    /// Takes the wasmbus v2 guest response stream, dropping the request stream and all pending host calls
+    pub fn take_guest_response_stream(
+        &mut self,
+    ) -> Option<Box<dyn AsyncWrite + Sync + Send + Unpin>> {
+        self.guest_request_stream = None;
+        self.host_calls.clear();
+        self.guest_response_stream.take()
+    }
+
     pub fn take_console_log<T: FromIterator<String>>(&mut self) -> T {
         self.console_log.drain(..).collect()
     }
@@ -228,6 +309,7 @@ fn guest_response(
 #[instrument]
 async fn handle(
     handler: &mut builtin::Handler,
+    rng: &mut SharedRng,
     binding: String,
     namespace: String,
     operation: String,
@@ -256,14 +338,13 @@ async fn handle(
         }
         ("wasmcloud:builtin:numbergen", "NumberGen.GenerateGuid") => {
             let mut buf = uuid::Bytes::default();
-            thread_rng()
-                .try_fill_bytes(&mut buf)
+            rng.try_fill_bytes(&mut buf)
                 .context("failed to fill buffer")?;
             let guid = uuid::Builder::from_random_bytes(buf).into_uuid();
             rmp_serde::to_vec(&guid.to_string()).context("failed to serialize GUID string")
         }
         ("wasmcloud:builtin:numbergen", "NumberGen.Random32") => {
-            let v = thread_rng().next_u32();
+            let v = rng.next_u32();
             rmp_serde::to_vec(&v).context("failed to serialize u32")
         }
         ("wasmcloud:builtin:numbergen", "NumberGen.RandomInRange") => {
@@ -274,7 +355,7 @@ async fn handle(
             }
             let Request { min, max } =
                 rmp_serde::from_slice(&payload).context("failed to deserialize range limit")?;
-            let v = thread_rng().gen_range(min..=max);
+            let v = rng.gen_range(min..=max);
             rmp_serde::to_vec(&v).context("failed to serialize u32")
         }
         _ => {
@@ -334,7 +415,11 @@ async fn host_call(
 
     let pld = read_bytes(&mut store, &memory, payload_ptr, payload_len)
         .context("failed to read `__host_call` payload")?;
-    match handle(&mut store.data_mut().wasmbus.handler, bd, ns, op, pld).await {
+    let super::Ctx {
+        wasmbus: Ctx { handler, rng, .. },
+        ..
+    } = store.data_mut();
+    match handle(handler, rng, bd, ns, op, pld).await {
         Ok(res) => {
             set_host_response(&mut store, res);
             Ok(wasm::SUCCESS)
@@ -417,6 +502,343 @@ fn host_response_len(store: wasmtime::Caller<'_, super::Ctx>) -> wasm::usize {
         })
 }
 
+/// Converts a byte count into a wasm [`wasm::usize`], which is guaranteed to fit, since it is bounded
+/// by a guest buffer length
+fn count(n: usize) -> wasm::usize {
+    n.try_into().unwrap_or(wasm::ERROR - 1)
+}
+
+/// Reads up to `buf_len` bytes from `stream` into guest memory at `buf_ptr` in chunks of at most
+/// [`READ_CHUNK_SIZE`] bytes, so that the size of the host buffer is not controlled by the guest.
+///
+/// Only the first chunk is awaited, subsequent chunks are only read while they are immediately
+/// available. Reading stops on EOF or once `buf_len` bytes are read. The I/O error is only
+/// returned if no bytes were read, otherwise it is left to be encountered by the next read.
+async fn read_into_guest(
+    store: &mut wasmtime::Caller<'_, super::Ctx>,
+    stream: &mut (impl AsyncRead + Unpin + ?Sized),
+    buf_ptr: wasm::ptr,
+    buf_len: wasm::usize,
+) -> Result<io::Result<wasm::usize>> {
+    let len: usize = buf_len.try_into().context("size does not fit in usize")?;
+    let mut buf = vec![0; len.min(READ_CHUNK_SIZE)];
+    let memory = caller_memory(store);
+    let mut total = 0;
+    while total < len {
+        let chunk = &mut buf[..(len - total).min(READ_CHUNK_SIZE)];
+        let res = if total == 0 {
+            stream.read(chunk).await
+        } else if let Some(res) = stream.read(chunk).now_or_never() {
+            res
+        } else {
+            break;
+        };
+        let n = match res {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if total == 0 => return Ok(Err(err)),
+            Err(_) => break,
+        };
+        let ptr = buf_ptr
+            .checked_add(count(total))
+            .context("pointer overflows")?;
+        write_bytes(store, &memory, ptr, &buf[..n])?;
+        total += n;
+    }
+    Ok(Ok(count(total)))
+}
+
+#[instrument(skip(store))]
+fn guest_operation(mut store: wasmtime::Caller<'_, super::Ctx>, op_ptr: wasm::ptr) -> Result<()> {
+    let op = store
+        .data_mut()
+        .wasmbus
+        .guest_operation
+        .take()
+        .context("unexpected `__guest_operation`")?;
+
+    let memory = caller_memory(&mut store);
+    write_bytes(&mut store, &memory, op_ptr, op)
+        .context("failed to write `__guest_operation` operation into guest memory")
+}
+
+#[instrument(skip(store))]
+async fn guest_request_read(
+    mut store: wasmtime::Caller<'_, super::Ctx>,
+    buf_ptr: wasm::ptr,
+    buf_len: wasm::usize,
+) -> Result<wasm::usize> {
+    let Some(mut request) = store.data_mut().wasmbus.guest_request_stream.take() else {
+        set_host_error(&mut store, "no guest request stream available");
+        return Ok(wasm::ERROR);
+    };
+    let res = read_into_guest(&mut store, &mut request, buf_ptr, buf_len)
+        .await
+        .context("failed to write `__guest_request_read` request into guest memory")?;
+    store.data_mut().wasmbus.guest_request_stream = Some(request);
+    match res {
+        Ok(n) => Ok(n),
+        Err(err) => {
+            set_host_error(&mut store, format!("failed to read request: {err}"));
+            Ok(wasm::ERROR)
+        }
+    }
+}
+
+#[instrument(skip(store))]
+async fn guest_response_write(
+    mut store: wasmtime::Caller<'_, super::Ctx>,
+    buf_ptr: wasm::ptr,
+    buf_len: wasm::usize,
+) -> Result<wasm::usize> {
+    let memory = caller_memory(&mut store);
+    let buf = read_bytes(&mut store, &memory, buf_ptr, buf_len)
+        .context("failed to read `__guest_response_write` response")?;
+    let Some(response) = store.data_mut().wasmbus.guest_response_stream.as_mut() else {
+        set_host_error(&mut store, "no guest response stream available");
+        return Ok(wasm::ERROR);
+    };
+    let res = response.write_all(&buf).await;
+    if let Err(err) = res {
+        set_host_error(&mut store, format!("failed to write response: {err}"));
+        return Ok(wasm::ERROR);
+    }
+    Ok(wasm::SUCCESS)
+}
+
+#[instrument(skip(store))]
+#[allow(clippy::too_many_arguments)]
+async fn host_call_start(
+    mut store: wasmtime::Caller<'_, super::Ctx>,
+    binding_ptr: wasm::ptr,
+    binding_len: wasm::usize,
+    namespace_ptr: wasm::ptr,
+    namespace_len: wasm::usize,
+    operation_ptr: wasm::ptr,
+    operation_len: wasm::usize,
+) -> Result<wasm::usize> {
+    let memory = caller_memory(&mut store);
+    let binding = read_string(&mut store, &memory, binding_ptr, binding_len)
+        .context("failed to read `__host_call_start` binding")?;
+    let namespace = read_string(&mut store, &memory, namespace_ptr, namespace_len)
+        .context("failed to read `__host_call_start` namespace")?;
+    let operation = read_string(&mut store, &memory, operation_ptr, operation_len)
+        .context("failed to read `__host_call_start` operation")?;
+
+    let call = if matches!(
+        namespace.as_str(),
+        "wasmcloud:builtin:logging" | "wasmcloud:builtin:numbergen"
+    ) {
+        HostCall::Builtin {
+            binding,
+            namespace,
+            operation,
+            request: vec![],
+            response: None,
+        }
+    } else {
+        let res = store
+            .data()
+            .wasmbus
+            .handler
+            .call(format!("{binding}:{namespace}/{operation}"))
+            .await;
+        match res {
+            Ok((result, request, response)) => HostCall::Bus {
+                request: Some(request),
+                response,
+                result: tokio::spawn(result),
+            },
+            Err(err) => {
+                set_host_error(&mut store, format!("{err:#}"));
+                return Ok(wasm::ERROR);
+            }
+        }
+    };
+    let ctx = &mut store.data_mut().wasmbus;
+    let id = ctx.next_host_call;
+    ctx.next_host_call = id.wrapping_add(1) % wasm::ERROR;
+    ctx.host_calls.insert(id, call);
+    Ok(id)
+}
+
+/// Closes the request stream of a [`HostCall`], which processes the request if the call is a builtin
+async fn close_host_call_request(
+    handler: &mut builtin::Handler,
+    rng: &mut SharedRng,
+    call: &mut HostCall,
+) -> anyhow::Result<()> {
+    match call {
+        HostCall::Builtin {
+            response: Some(..), ..
+        } => Ok(()),
+        HostCall::Builtin {
+            binding,
+            namespace,
+            operation,
+            request,
+            response,
+        } => {
+            let res = handle(
+                handler,
+                rng,
+                mem::take(binding),
+                mem::take(namespace),
+                mem::take(operation),
+                mem::take(request),
+            )
+            .await?;
+            *response = Some(Cursor::new(res));
+            Ok(())
+        }
+        HostCall::Bus { request, .. } => {
+            if let Some(mut request) = request.take() {
+                request
+                    .shutdown()
+                    .await
+                    .context("failed to close request stream")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[instrument(skip(store))]
+async fn host_call_write(
+    mut store: wasmtime::Caller<'_, super::Ctx>,
+    call: wasm::usize,
+    buf_ptr: wasm::ptr,
+    buf_len: wasm::usize,
+) -> Result<wasm::usize> {
+    let memory = caller_memory(&mut store);
+    let buf = read_bytes(&mut store, &memory, buf_ptr, buf_len)
+        .context("failed to read `__host_call_write` request")?;
+    let res = match store.data_mut().wasmbus.host_calls.get_mut(&call) {
+        None => Err(anyhow!("unknown host call `{call}`")),
+        Some(HostCall::Builtin {
+            request,
+            response: None,
+            ..
+        }) => {
+            request.extend(buf);
+            Ok(())
+        }
+        Some(HostCall::Bus {
+            request: Some(request),
+            ..
+        }) => request
+            .write_all(&buf)
+            .await
+            .context("failed to write request"),
+        Some(_) => Err(anyhow!("request stream of host call `{call}` is closed")),
+    };
+    if let Err(err) = res {
+        set_host_error(&mut store, format!("{err:#}"));
+        return Ok(wasm::ERROR);
+    }
+    Ok(wasm::SUCCESS)
+}
+
+#[instrument(skip(store))]
+async fn host_call_close(
+    mut store: wasmtime::Caller<'_, super::Ctx>,
+    call: wasm::usize,
+) -> Result<wasm::usize> {
+    let Ctx {
+        handler,
+        rng,
+        host_calls,
+        ..
+    } = &mut store.data_mut().wasmbus;
+    let res = if let Some(call) = host_calls.get_mut(&call) {
+        close_host_call_request(handler, rng, call).await
+    } else {
+        Err(anyhow!("unknown host call `{call}`"))
+    };
+    if let Err(err) = res {
+        set_host_error(&mut store, format!("{err:#}"));
+        return Ok(wasm::ERROR);
+    }
+    Ok(wasm::SUCCESS)
+}
+
+#[instrument(skip(store))]
+async fn host_call_read(
+    mut store: wasmtime::Caller<'_, super::Ctx>,
+    call: wasm::usize,
+    buf_ptr: wasm::ptr,
+    buf_len: wasm::usize,
+) -> Result<wasm::usize> {
+    let Ctx {
+        handler,
+        rng,
+        host_calls,
+        ..
+    } = &mut store.data_mut().wasmbus;
+    let Some(mut host_call) = host_calls.remove(&call) else {
+        set_host_error(&mut store, format!("unknown host call `{call}`"));
+        return Ok(wasm::ERROR);
+    };
+    // NOTE: Reading the response implicitly closes the request stream
+    let closed = close_host_call_request(handler, rng, &mut host_call).await;
+    let res = match closed {
+        Ok(()) => match &mut host_call {
+            HostCall::Builtin {
+                response: Some(response),
+                ..
+            } => read_into_guest(&mut store, response, buf_ptr, buf_len).await,
+            HostCall::Builtin { response: None, .. } => Ok(Ok(0)),
+            HostCall::Bus { response, .. } => {
+                read_into_guest(&mut store, response, buf_ptr, buf_len).await
+            }
+        }
+        .context("failed to write `__host_call_read` response into guest memory")?
+        .context("failed to read response"),
+        Err(err) => Err(err),
+    };
+    store.data_mut().wasmbus.host_calls.insert(call, host_call);
+    match res {
+        Ok(n) => Ok(n),
+        Err(err) => {
+            set_host_error(&mut store, format!("{err:#}"));
+            Ok(wasm::ERROR)
+        }
+    }
+}
+
+#[instrument(skip(store))]
+async fn host_call_finish(
+    mut store: wasmtime::Caller<'_, super::Ctx>,
+    call: wasm::usize,
+) -> Result<wasm::usize> {
+    let Ctx {
+        handler,
+        rng,
+        host_calls,
+        ..
+    } = &mut store.data_mut().wasmbus;
+    let res = if let Some(mut call) = host_calls.remove(&call) {
+        match close_host_call_request(handler, rng, &mut call).await {
+            Ok(()) => match call {
+                HostCall::Builtin { .. } => Ok(()),
+                HostCall::Bus { result, .. } => match result.await {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(err)) => Err(anyhow!(err)),
+                    Err(err) => Err(anyhow!(err).context("host call task failed")),
+                },
+            },
+            Err(err) => Err(err),
+        }
+    } else {
+        Err(anyhow!("unknown host call `{call}`"))
+    };
+    if let Err(err) = res {
+        set_host_error(&mut store, format!("{err:#}"));
+        return Ok(wasm::ERROR);
+    }
+    Ok(wasm::SUCCESS)
+}
+
 pub(super) fn add_to_linker(linker: &mut wasmtime::Linker<super::Ctx>) -> Result<()> {
     linker.func_wrap("wasmbus", "__console_log", console_log)?;
     linker.func_wrap("wasmbus", "__guest_error", guest_error)?;
@@ -451,5 +873,55 @@ pub(super) fn add_to_linker(linker: &mut wasmtime::Linker<super::Ctx>) -> Result
     linker.func_wrap("wasmbus", "__host_error_len", host_error_len)?;
     linker.func_wrap("wasmbus", "__host_response", host_response)?;
     linker.func_wrap("wasmbus", "__host_response_len", host_response_len)?;
+
+    // wasmbus v2
+    linker.func_wrap("wasmbus", "__guest_operation", guest_operation)?;
+    linker.func_wrap2_async(
+        "wasmbus",
+        "__guest_request_read",
+        |store, buf_ptr, buf_len| Box::new(guest_request_read(store, buf_ptr, buf_len)),
+    )?;
+    linker.func_wrap2_async(
+        "wasmbus",
+        "__guest_response_write",
+        |store, buf_ptr, buf_len| Box::new(guest_response_write(store, buf_ptr, buf_len)),
+    )?;
+    linker.func_wrap6_async(
+        "wasmbus",
+        "__host_call_start",
+        |store,
+         binding_ptr,
+         binding_len,
+         namespace_ptr,
+         namespace_len,
+         operation_ptr,
+         operation_len| {
+            Box::new(host_call_start(
+                store,
+                binding_ptr,
+                binding_len,
+                namespace_ptr,
+                namespace_len,
+                operation_ptr,
+                operation_len,
+            ))
+        },
+    )?;
+    linker.func_wrap3_async(
+        "wasmbus",
+        "__host_call_write",
+        |store, call, buf_ptr, buf_len| Box::new(host_call_write(store, call, buf_ptr, buf_len)),
+    )?;
+    linker.func_wrap1_async("wasmbus", "__host_call_close", |store, call| {
+        Box::new(host_call_close(store, call))
+    })?;
+    linker.func_wrap3_async(
+        "wasmbus",
+        "__host_call_read",
+        |store, call, buf_ptr, buf_len| Box::new(host_call_read(store, call, buf_ptr, buf_len)),
+    )?;
+    linker.func_wrap1_async("wasmbus", "__host_call_finish", |store, call| {
+        Box::new(host_call_finish(store, call))
+    })?;
     Ok(())
 }
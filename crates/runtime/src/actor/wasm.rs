@@ -0,0 +1,196 @@
+use anyhow::{bail, ensure, Context, Result};
+use sha2::{Digest, Sha256};
+use wascap::jwt;
+
+/// Name of the custom section claims are embedded in
+const CLAIMS_SECTION: &str = "jwt";
+
+/// Custom section ID
+const CUSTOM_SECTION: u8 = 0;
+/// Component core module section ID
+const COMPONENT_CORE_MODULE_SECTION: u8 = 1;
+/// Component component section ID
+const COMPONENT_COMPONENT_SECTION: u8 = 4;
+
+/// Kind of a WebAssembly binary
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// WebAssembly module
+    Module,
+    /// WebAssembly component
+    Component,
+}
+
+impl Kind {
+    /// Detects the [Kind] of a WebAssembly binary from its header, without parsing the rest of it
+    ///
+    /// # Errors
+    ///
+    /// Fails if `wasm` does not start with a valid WebAssembly header
+    pub fn detect(wasm: impl AsRef<[u8]>) -> Result<Self> {
+        match wasm.as_ref() {
+            [0x00, 0x61, 0x73, 0x6d, _, _, 0x00, 0x00, ..] => Ok(Self::Module),
+            [0x00, 0x61, 0x73, 0x6d, _, _, 0x01, 0x00, ..] => Ok(Self::Component),
+            [0x00, 0x61, 0x73, 0x6d, ..] => bail!("unsupported WebAssembly binary layer"),
+            _ => bail!("not a WebAssembly binary"),
+        }
+    }
+}
+
+/// Reads an unsigned LEB128-encoded `u32` from `buf`, returning it along with the number of bytes read
+fn read_u32(buf: &[u8]) -> Result<(u32, usize)> {
+    let mut v = 0u32;
+    for (i, b) in buf.iter().take(5).enumerate() {
+        v |= u32::from(b & 0x7f) << (i * 7);
+        if b & 0x80 == 0 {
+            return Ok((v, i + 1));
+        }
+    }
+    bail!("invalid LEB128-encoded integer")
+}
+
+/// Appends minimal unsigned LEB128 encoding of `v` to `buf`
+fn write_u32(buf: &mut Vec<u8>, mut v: u32) {
+    loop {
+        #[allow(clippy::cast_possible_truncation)]
+        let b = (v & 0x7f) as u8;
+        v >>= 7;
+        if v == 0 {
+            buf.push(b);
+            return;
+        }
+        buf.push(b | 0x80);
+    }
+}
+
+/// A top-level section of a WebAssembly binary
+struct Section<'a> {
+    id: u8,
+    data: &'a [u8],
+}
+
+impl Section<'_> {
+    /// Returns name and contents of the section, if it is a custom section
+    fn custom(&self) -> Result<Option<(&str, &[u8])>> {
+        if self.id != CUSTOM_SECTION {
+            return Ok(None);
+        }
+        let (n, off) = read_u32(self.data).context("failed to read custom section name length")?;
+        let (name, end) = usize::try_from(n)
+            .ok()
+            .and_then(|n| off.checked_add(n))
+            .and_then(|end| self.data.get(off..end).map(|name| (name, end)))
+            .context("custom section name out of bounds")?;
+        let name = std::str::from_utf8(name).context("custom section name is not valid UTF-8")?;
+        Ok(Some((name, &self.data[end..])))
+    }
+
+    fn is_claims(&self) -> bool {
+        matches!(self.custom(), Ok(Some((CLAIMS_SECTION, _))))
+    }
+}
+
+/// Splits `wasm` into its header and an iterator over top-level sections. Section contents are not parsed.
+fn sections(wasm: &[u8]) -> Result<(Kind, &[u8], impl Iterator<Item = Result<Section<'_>>>)> {
+    let kind = Kind::detect(wasm)?;
+    let (header, mut rest) = wasm.split_at(8);
+    Ok((
+        kind,
+        header,
+        std::iter::from_fn(move || {
+            let (&id, tail) = rest.split_first()?;
+            let section = read_u32(tail)
+                .context("failed to read section size")
+                .and_then(|(n, off)| {
+                    usize::try_from(n)
+                        .ok()
+                        .and_then(|n| off.checked_add(n))
+                        .and_then(|end| tail.get(off..end).map(|data| (data, end)))
+                        .context("section out of bounds")
+                });
+            match section {
+                Ok((data, end)) => {
+                    rest = &tail[end..];
+                    Some(Ok(Section { id, data }))
+                }
+                Err(e) => {
+                    rest = &[];
+                    Some(Err(e))
+                }
+            }
+        }),
+    ))
+}
+
+/// Appends `wasm` with all claims sections removed to `buf`, re-encoding section sizes.
+/// This matches the representation used by `wascap` to compute the module hash.
+fn strip_claims(wasm: &[u8], buf: &mut Vec<u8>) -> Result<()> {
+    let (kind, header, sections) = sections(wasm)?;
+    buf.extend_from_slice(header);
+    for section in sections {
+        let section = section?;
+        if section.is_claims() {
+            continue;
+        }
+        buf.push(section.id);
+        match (kind, section.id) {
+            (Kind::Component, COMPONENT_CORE_MODULE_SECTION | COMPONENT_COMPONENT_SECTION) => {
+                let mut nested = Vec::with_capacity(section.data.len());
+                strip_claims(section.data, &mut nested)?;
+                write_u32(
+                    buf,
+                    nested.len().try_into().context("section size overflow")?,
+                );
+                buf.extend(nested);
+            }
+            _ => {
+                write_u32(
+                    buf,
+                    section
+                        .data
+                        .len()
+                        .try_into()
+                        .context("section size overflow")?,
+                );
+                buf.extend_from_slice(section.data);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Extracts claims from the top-level claims section of `wasm`, if one is present, and verifies that
+/// the module hash contained within matches `wasm`. Unlike [`wascap::wasm::extract_claims`], only
+/// section headers are read to locate the claims, so unsigned binaries are not parsed at all.
+pub(crate) fn extract_claims(wasm: &[u8]) -> Result<Option<jwt::Token<jwt::Actor>>> {
+    let (_, _, mut sections) = sections(wasm)?;
+    let token = sections.find_map(|section| match section {
+        Ok(section) => match section.custom() {
+            Ok(Some((CLAIMS_SECTION, token))) => Some(Ok(token)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        },
+        Err(e) => Some(Err(e)),
+    });
+    let Some(token) = token.transpose()? else {
+        return Ok(None);
+    };
+    let jwt = std::str::from_utf8(token)
+        .context("claims are not valid UTF-8")?
+        .to_string();
+    let claims = jwt::Claims::<jwt::Actor>::decode(&jwt).context("failed to decode claims")?;
+    let module_hash = claims
+        .metadata
+        .as_ref()
+        .map(|actor| actor.module_hash.as_str())
+        .context("actor metadata missing from claims")?;
+
+    let mut stripped = Vec::with_capacity(wasm.len());
+    strip_claims(wasm, &mut stripped)?;
+    let hash = hex::encode_upper(Sha256::digest(&stripped));
+    ensure!(
+        module_hash == hash,
+        "module hash `{module_hash}` does not match computed hash `{hash}`"
+    );
+    Ok(Some(jwt::Token { jwt, claims }))
+}
@@ -1,5 +1,8 @@
+use super::http::types::RequestOptions;
 use super::logging::logging;
-use super::{format_opt, messaging};
+use super::{blobstore, format_opt, messaging};
+
+use crate::record;
 
 use core::fmt::Debug;
 use core::future::Future;
@@ -11,16 +14,21 @@ use std::time::Duration;
 use anyhow::{bail, ensure, Context, Result};
 use async_trait::async_trait;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::oneshot;
 use tracing::{instrument, trace};
 
 #[derive(Clone, Default)]
 pub struct Handler {
     blobstore: Option<Arc<dyn Blobstore + Sync + Send>>,
     bus: Option<Arc<dyn Bus + Sync + Send>>,
+    keyvalue_atomic: Option<Arc<dyn KeyValueAtomic + Sync + Send>>,
+    keyvalue_batch: Option<Arc<dyn KeyValueBatch + Sync + Send>>,
     keyvalue_readwrite: Option<Arc<dyn KeyValueReadWrite + Sync + Send>>,
     logging: Option<Arc<dyn Logging + Sync + Send>>,
     incoming_http: Option<Arc<dyn IncomingHttp + Sync + Send>>,
     messaging: Option<Arc<dyn Messaging + Sync + Send>>,
+    outgoing_http: Option<Arc<dyn OutgoingHttp + Sync + Send>>,
+    record: Option<record::Mode>,
 }
 
 impl Debug for Handler {
@@ -29,14 +37,23 @@ impl Debug for Handler {
             .field("blobstore", &format_opt(&self.blobstore))
             .field("bus", &format_opt(&self.bus))
             .field("incoming_http", &format_opt(&self.incoming_http))
+            .field("keyvalue_atomic", &format_opt(&self.keyvalue_atomic))
+            .field("keyvalue_batch", &format_opt(&self.keyvalue_batch))
             .field("keyvalue_readwrite", &format_opt(&self.keyvalue_readwrite))
             .field("logging", &format_opt(&self.logging))
             .field("messaging", &format_opt(&self.messaging))
+            .field("outgoing_http", &format_opt(&self.outgoing_http))
+            .field("record", &self.record)
             .finish()
     }
 }
 
 impl Handler {
+    /// Returns the record or replay mode, if any
+    pub(crate) fn record_mode(&self) -> Option<&record::Mode> {
+        self.record.as_ref()
+    }
+
     /// Replace [`Blobstore`] handler returning the old one, if such was set
     pub fn replace_blobstore(
         &mut self,
@@ -61,6 +78,22 @@ impl Handler {
         self.incoming_http.replace(incoming_http)
     }
 
+    /// Replace [`KeyValueAtomic`] handler returning the old one, if such was set
+    pub fn replace_keyvalue_atomic(
+        &mut self,
+        keyvalue_atomic: Arc<dyn KeyValueAtomic + Send + Sync>,
+    ) -> Option<Arc<dyn KeyValueAtomic + Send + Sync>> {
+        self.keyvalue_atomic.replace(keyvalue_atomic)
+    }
+
+    /// Replace [`KeyValueBatch`] handler returning the old one, if such was set
+    pub fn replace_keyvalue_batch(
+        &mut self,
+        keyvalue_batch: Arc<dyn KeyValueBatch + Send + Sync>,
+    ) -> Option<Arc<dyn KeyValueBatch + Send + Sync>> {
+        self.keyvalue_batch.replace(keyvalue_batch)
+    }
+
     /// Replace [`KeyValueReadWrite`] handler returning the old one, if such was set
     pub fn replace_keyvalue_readwrite(
         &mut self,
@@ -84,6 +117,14 @@ impl Handler {
     ) -> Option<Arc<dyn Messaging + Send + Sync>> {
         self.messaging.replace(messaging)
     }
+
+    /// Replace [`OutgoingHttp`] handler returning the old one, if such was set
+    pub fn replace_outgoing_http(
+        &mut self,
+        outgoing_http: Arc<dyn OutgoingHttp + Send + Sync>,
+    ) -> Option<Arc<dyn OutgoingHttp + Send + Sync>> {
+        self.outgoing_http.replace(outgoing_http)
+    }
 }
 
 #[async_trait]
@@ -144,6 +185,10 @@ pub trait Bus {
     }
 }
 
+/// HTTP trailers, which may be inserted into [`http::Request`] and [`http::Response`] extensions.
+/// Trailers are sent by the producer of the body once it has been fully written.
+pub type HttpTrailers = oneshot::Receiver<http::HeaderMap>;
+
 #[async_trait]
 /// `wasi:http/incoming-handler` implementation
 pub trait IncomingHttp {
@@ -155,9 +200,66 @@ pub trait IncomingHttp {
 }
 
 #[async_trait]
-/// `wasi:blobstore/consumer` implementation
+/// `wasi:http/outgoing-handler` implementation
+pub trait OutgoingHttp {
+    /// Handle `wasi:http/outgoing-handler`
+    async fn handle(
+        &self,
+        request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+        options: Option<RequestOptions>,
+    ) -> anyhow::Result<http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>>;
+}
+
+#[async_trait]
+/// `wasmcloud:blobstore/consumer` implementation
 pub trait Blobstore {
-    // TODO: Implement
+    /// Handle `wasmcloud:blobstore/consumer.container-exists`
+    async fn container_exists(&self, container: &str) -> anyhow::Result<bool>;
+
+    /// Handle `wasmcloud:blobstore/consumer.create-container`
+    async fn create_container(&self, container: &str) -> anyhow::Result<()>;
+
+    /// Handle `wasmcloud:blobstore/consumer.remove-container`
+    async fn remove_container(&self, container: &str) -> anyhow::Result<()>;
+
+    /// Handle `wasmcloud:blobstore/consumer.get-container-info`
+    async fn get_container_info(
+        &self,
+        container: &str,
+    ) -> anyhow::Result<Option<blobstore::types::ContainerInfo>>;
+
+    /// Handle `wasmcloud:blobstore/consumer.get-object-info`
+    async fn get_object_info(
+        &self,
+        container: &str,
+        object: String,
+    ) -> anyhow::Result<Option<blobstore::types::ObjectInfo>>;
+
+    /// Handle `wasmcloud:blobstore/consumer.remove-object`
+    async fn remove_object(&self, container: &str, object: String) -> anyhow::Result<bool>;
+
+    /// Handle `wasmcloud:blobstore/consumer.put-object` and `wasmcloud:blobstore/consumer.put-chunk`.
+    /// The object is written from `value` until EOF is reached.
+    async fn put_object(
+        &self,
+        container: &str,
+        object: String,
+        value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+        content_type: Option<String>,
+        content_encoding: Option<String>,
+    ) -> anyhow::Result<()>;
+
+    /// Handle `wasmcloud:blobstore/consumer.stream-object`. The object contents are expected to be
+    /// delivered to the actor in chunks asynchronously.
+    async fn stream_object(&self, container: &str, object: String) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+/// `wasmcloud:blobstore/handler` implementation
+pub trait BlobstoreHandler {
+    /// Handle `wasmcloud:blobstore/handler.receive-chunk`, returning `true` if the download
+    /// should be cancelled
+    async fn receive_chunk(&self, chunk: blobstore::types::Chunk) -> anyhow::Result<bool>;
 }
 
 #[async_trait]
@@ -172,6 +274,46 @@ pub trait Logging {
     ) -> anyhow::Result<()>;
 }
 
+#[async_trait]
+/// `wasi:keyvalue/atomic` implementation
+pub trait KeyValueAtomic {
+    /// Handle `wasi:keyvalue/atomic.increment`
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64>;
+
+    /// Handle `wasi:keyvalue/atomic.compare-and-swap`
+    async fn compare_and_swap(
+        &self,
+        bucket: &str,
+        key: String,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<bool>;
+}
+
+#[async_trait]
+/// `wasi:keyvalue/batch` implementation
+pub trait KeyValueBatch {
+    /// Handle `wasi:keyvalue/batch.get-many`
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>>;
+
+    /// Handle `wasi:keyvalue/batch.get-keys`
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>>;
+
+    /// Handle `wasi:keyvalue/batch.set-many`
+    async fn set_many(
+        &self,
+        bucket: &str,
+        entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()>;
+
+    /// Handle `wasi:keyvalue/batch.delete-many`
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()>;
+}
+
 #[async_trait]
 /// `wasi:keyvalue/readwrite` implementation
 pub trait KeyValueReadWrite {
@@ -197,6 +339,13 @@ pub trait KeyValueReadWrite {
     async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool>;
 }
 
+#[async_trait]
+/// `wasmcloud:messaging/handler` implementation
+pub trait MessagingHandler {
+    /// Handle `wasmcloud:messaging/handler.handle-message`
+    async fn handle_message(&self, msg: messaging::types::BrokerMessage) -> anyhow::Result<()>;
+}
+
 #[async_trait]
 /// `wasmcloud:messaging/consumer` implementation
 pub trait Messaging {
@@ -241,6 +390,103 @@ impl Bus for Handler {
     }
 }
 
+#[async_trait]
+impl Blobstore for Handler {
+    #[instrument]
+    async fn container_exists(&self, container: &str) -> anyhow::Result<bool> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.container-exists`")?
+            .container_exists(container)
+            .await
+    }
+
+    #[instrument]
+    async fn create_container(&self, container: &str) -> anyhow::Result<()> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.create-container`")?
+            .create_container(container)
+            .await
+    }
+
+    #[instrument]
+    async fn remove_container(&self, container: &str) -> anyhow::Result<()> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.remove-container`")?
+            .remove_container(container)
+            .await
+    }
+
+    #[instrument]
+    async fn get_container_info(
+        &self,
+        container: &str,
+    ) -> anyhow::Result<Option<blobstore::types::ContainerInfo>> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.get-container-info`")?
+            .get_container_info(container)
+            .await
+    }
+
+    #[instrument]
+    async fn get_object_info(
+        &self,
+        container: &str,
+        object: String,
+    ) -> anyhow::Result<Option<blobstore::types::ObjectInfo>> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.get-object-info`")?
+            .get_object_info(container, object)
+            .await
+    }
+
+    #[instrument]
+    async fn remove_object(&self, container: &str, object: String) -> anyhow::Result<bool> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.remove-object`")?
+            .remove_object(container, object)
+            .await
+    }
+
+    #[instrument(skip(value))]
+    async fn put_object(
+        &self,
+        container: &str,
+        object: String,
+        value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+        content_type: Option<String>,
+        content_encoding: Option<String>,
+    ) -> anyhow::Result<()> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.put-object`")?
+            .put_object(container, object, value, content_type, content_encoding)
+            .await
+    }
+
+    #[instrument]
+    async fn stream_object(&self, container: &str, object: String) -> anyhow::Result<()> {
+        trace!("call `Blobstore` handler");
+        self.blobstore
+            .as_ref()
+            .context("cannot handle `wasmcloud:blobstore/consumer.stream-object`")?
+            .stream_object(container, object)
+            .await
+    }
+}
+
 #[async_trait]
 impl Logging for Handler {
     #[instrument]
@@ -260,6 +506,86 @@ impl Logging for Handler {
     }
 }
 
+#[async_trait]
+impl KeyValueAtomic for Handler {
+    #[instrument]
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
+        trace!("call `KeyValueAtomic` handler");
+        self.keyvalue_atomic
+            .as_ref()
+            .context("cannot handle `wasi:keyvalue/atomic.increment`")?
+            .increment(bucket, key, delta)
+            .await
+    }
+
+    #[instrument]
+    async fn compare_and_swap(
+        &self,
+        bucket: &str,
+        key: String,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<bool> {
+        trace!("call `KeyValueAtomic` handler");
+        self.keyvalue_atomic
+            .as_ref()
+            .context("cannot handle `wasi:keyvalue/atomic.compare-and-swap`")?
+            .compare_and_swap(bucket, key, old, new)
+            .await
+    }
+}
+
+#[async_trait]
+impl KeyValueBatch for Handler {
+    #[instrument]
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        trace!("call `KeyValueBatch` handler");
+        self.keyvalue_batch
+            .as_ref()
+            .context("cannot handle `wasi:keyvalue/batch.get-many`")?
+            .get_many(bucket, keys)
+            .await
+    }
+
+    #[instrument]
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        trace!("call `KeyValueBatch` handler");
+        self.keyvalue_batch
+            .as_ref()
+            .context("cannot handle `wasi:keyvalue/batch.get-keys`")?
+            .get_keys(bucket)
+            .await
+    }
+
+    #[instrument(skip(entries))]
+    async fn set_many(
+        &self,
+        bucket: &str,
+        entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        trace!("call `KeyValueBatch` handler");
+        self.keyvalue_batch
+            .as_ref()
+            .context("cannot handle `wasi:keyvalue/batch.set-many`")?
+            .set_many(bucket, entries)
+            .await
+    }
+
+    #[instrument]
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        trace!("call `KeyValueBatch` handler");
+        self.keyvalue_batch
+            .as_ref()
+            .context("cannot handle `wasi:keyvalue/batch.delete-many`")?
+            .delete_many(bucket, keys)
+            .await
+    }
+}
+
 #[async_trait]
 impl KeyValueReadWrite for Handler {
     #[instrument]
@@ -328,6 +654,23 @@ impl IncomingHttp for Handler {
     }
 }
 
+#[async_trait]
+impl OutgoingHttp for Handler {
+    #[instrument(skip(request))]
+    async fn handle(
+        &self,
+        request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+        options: Option<RequestOptions>,
+    ) -> anyhow::Result<http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>> {
+        trace!("call `OutgoingHttp` handler");
+        self.outgoing_http
+            .as_ref()
+            .context("cannot handle `wasi:http/outgoing-handler.handle`")?
+            .handle(request, options)
+            .await
+    }
+}
+
 #[async_trait]
 impl Messaging for Handler {
     #[instrument(skip(body))]
@@ -381,12 +724,20 @@ pub(crate) struct HandlerBuilder {
     pub bus: Option<Arc<dyn Bus + Sync + Send>>,
     /// [`IncomingHttp`] handler
     pub incoming_http: Option<Arc<dyn IncomingHttp + Sync + Send>>,
+    /// [`KeyValueAtomic`] handler
+    pub keyvalue_atomic: Option<Arc<dyn KeyValueAtomic + Sync + Send>>,
+    /// [`KeyValueBatch`] handler
+    pub keyvalue_batch: Option<Arc<dyn KeyValueBatch + Sync + Send>>,
     /// [`KeyValueReadWrite`] handler
     pub keyvalue_readwrite: Option<Arc<dyn KeyValueReadWrite + Sync + Send>>,
     /// [`Logging`] handler
     pub logging: Option<Arc<dyn Logging + Sync + Send>>,
     /// [`Messaging`] handler
     pub messaging: Option<Arc<dyn Messaging + Sync + Send>>,
+    /// [`OutgoingHttp`] handler
+    pub outgoing_http: Option<Arc<dyn OutgoingHttp + Sync + Send>>,
+    /// Record or replay mode
+    pub record: Option<record::Mode>,
 }
 
 impl HandlerBuilder {
@@ -417,6 +768,28 @@ impl HandlerBuilder {
         }
     }
 
+    /// Set [`KeyValueAtomic`] handler
+    pub fn keyvalue_atomic(
+        self,
+        keyvalue_atomic: Arc<impl KeyValueAtomic + Sync + Send + 'static>,
+    ) -> Self {
+        Self {
+            keyvalue_atomic: Some(keyvalue_atomic),
+            ..self
+        }
+    }
+
+    /// Set [`KeyValueBatch`] handler
+    pub fn keyvalue_batch(
+        self,
+        keyvalue_batch: Arc<impl KeyValueBatch + Sync + Send + 'static>,
+    ) -> Self {
+        Self {
+            keyvalue_batch: Some(keyvalue_batch),
+            ..self
+        }
+    }
+
     /// Set [`KeyValueReadWrite`] handler
     pub fn keyvalue_readwrite(
         self,
@@ -443,6 +816,17 @@ impl HandlerBuilder {
             ..self
         }
     }
+
+    /// Set [`OutgoingHttp`] handler
+    pub fn outgoing_http(
+        self,
+        outgoing_http: Arc<impl OutgoingHttp + Sync + Send + 'static>,
+    ) -> Self {
+        Self {
+            outgoing_http: Some(outgoing_http),
+            ..self
+        }
+    }
 }
 
 impl Debug for HandlerBuilder {
@@ -451,9 +835,13 @@ impl Debug for HandlerBuilder {
             .field("blobstore", &format_opt(&self.blobstore))
             .field("bus", &format_opt(&self.bus))
             .field("incoming_http", &format_opt(&self.incoming_http))
+            .field("keyvalue_atomic", &format_opt(&self.keyvalue_atomic))
+            .field("keyvalue_batch", &format_opt(&self.keyvalue_batch))
             .field("keyvalue_readwrite", &format_opt(&self.keyvalue_readwrite))
             .field("logging", &format_opt(&self.logging))
             .field("messaging", &format_opt(&self.messaging))
+            .field("outgoing_http", &format_opt(&self.outgoing_http))
+            .field("record", &self.record)
             .finish()
     }
 }
@@ -464,18 +852,26 @@ impl From<Handler> for HandlerBuilder {
             blobstore,
             bus,
             incoming_http,
+            keyvalue_atomic,
+            keyvalue_batch,
             keyvalue_readwrite,
             logging,
             messaging,
+            outgoing_http,
+            record,
         }: Handler,
     ) -> Self {
         Self {
             blobstore,
             bus,
             incoming_http,
+            keyvalue_atomic,
+            keyvalue_batch,
             keyvalue_readwrite,
             logging,
             messaging,
+            outgoing_http,
+            record,
         }
     }
 }
@@ -486,18 +882,26 @@ impl From<HandlerBuilder> for Handler {
             blobstore,
             bus,
             incoming_http,
+            keyvalue_atomic,
+            keyvalue_batch,
             keyvalue_readwrite,
             logging,
             messaging,
+            outgoing_http,
+            record,
         }: HandlerBuilder,
     ) -> Self {
         Self {
             blobstore,
             bus,
+            keyvalue_atomic,
+            keyvalue_batch,
             keyvalue_readwrite,
             logging,
             incoming_http,
             messaging,
+            outgoing_http,
+            record,
         }
     }
 }
@@ -0,0 +1,360 @@
+use crate::capability::{blobstore, messaging};
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// `wasmcloud:httpserver` operation used to deliver HTTP requests to actors
+pub const HTTP_SERVER_HANDLE_REQUEST: &str = "HttpServer.HandleRequest";
+
+/// `wasmcloud:httpclient` operation used to send HTTP requests on behalf of actors
+pub const HTTP_CLIENT_REQUEST: &str = "wasmcloud:httpclient/HttpClient.Request";
+
+/// `wasmcloud:messaging` operation used to deliver subscribed messages to actors
+pub const MESSAGING_HANDLE_MESSAGE: &str = "MessageSubscriber.HandleMessage";
+
+/// `wasmcloud:blobstore` operation used to deliver object chunks to actors
+pub const BLOBSTORE_RECEIVE_CHUNK: &str = "ChunkReceiver.ReceiveChunk";
+
+/// HTTP request as defined by the `wasmcloud:httpserver` contract
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HttpRequest {
+    /// HTTP method. One of: GET,POST,PUT,DELETE,HEAD,OPTIONS,CONNECT,PATCH,TRACE
+    #[serde(default)]
+    pub method: String,
+    /// Full request path
+    #[serde(default)]
+    pub path: String,
+    /// Query string
+    #[serde(rename = "queryString")]
+    #[serde(default)]
+    pub query_string: String,
+    /// Map of request headers (string key, string value)
+    pub header: HashMap<String, Vec<String>>,
+    /// Request body as a byte array. May be empty.
+    #[serde(with = "serde_bytes")]
+    #[serde(default)]
+    pub body: Vec<u8>,
+}
+
+/// HTTP request as defined by the `wasmcloud:httpclient` contract
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HttpClientRequest {
+    /// HTTP method
+    pub method: String,
+    /// Full URL
+    pub url: String,
+    /// Map of request headers
+    pub headers: HashMap<String, Vec<String>>,
+    /// Request body as a byte array. May be empty.
+    #[serde(with = "serde_bytes")]
+    #[serde(default)]
+    pub body: Vec<u8>,
+}
+
+/// HTTP response as defined by the `wasmcloud:httpserver` and `wasmcloud:httpclient` contracts
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HttpResponse {
+    /// Three-digit number, usually in the range 100-599,
+    /// A value of 200 indicates success.
+    #[serde(rename = "statusCode")]
+    #[serde(default)]
+    pub status_code: u16,
+    /// Map of headers (string keys, list of values)
+    pub header: HashMap<String, Vec<String>>,
+    /// Body of response as a byte array. May be an empty array.
+    #[serde(with = "serde_bytes")]
+    #[serde(default)]
+    pub body: Vec<u8>,
+}
+
+impl Default for HttpResponse {
+    fn default() -> Self {
+        Self {
+            status_code: 200,
+            header: HashMap::default(),
+            body: Vec::default(),
+        }
+    }
+}
+
+fn from_header_map(headers: &http::HeaderMap) -> HashMap<String, Vec<String>> {
+    headers.keys().fold(HashMap::default(), |mut header, name| {
+        let values = headers
+            .get_all(name)
+            .iter()
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            .collect();
+        header.insert(name.to_string(), values);
+        header
+    })
+}
+
+fn into_header_map(header: HashMap<String, Vec<String>>) -> anyhow::Result<http::HeaderMap> {
+    let mut headers = http::HeaderMap::new();
+    for (name, values) in header {
+        let name = http::HeaderName::try_from(name).context("invalid header name")?;
+        for value in values {
+            let value = http::HeaderValue::try_from(value).context("invalid header value")?;
+            headers.append(&name, value);
+        }
+    }
+    Ok(headers)
+}
+
+impl HttpRequest {
+    /// Reads the body of an [`http::Request`] to end and converts the request into a [`HttpRequest`].
+    /// Trailers, if any, are discarded.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading the body fails
+    pub async fn from_http(
+        request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+    ) -> anyhow::Result<Self> {
+        let (
+            http::request::Parts {
+                method,
+                uri,
+                headers,
+                ..
+            },
+            mut body,
+        ) = request.into_parts();
+        let mut buf = vec![];
+        body.read_to_end(&mut buf)
+            .await
+            .context("failed to read request body")?;
+        Ok(Self {
+            method: method.to_string(),
+            path: uri.path().into(),
+            query_string: uri.query().map(Into::into).unwrap_or_default(),
+            header: from_header_map(&headers),
+            body: buf,
+        })
+    }
+}
+
+impl TryFrom<HttpRequest> for http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>> {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        HttpRequest {
+            method,
+            path,
+            query_string,
+            header,
+            body,
+        }: HttpRequest,
+    ) -> Result<Self, Self::Error> {
+        let uri = if query_string.is_empty() {
+            path
+        } else {
+            format!("{path}?{query_string}")
+        };
+        let mut request = http::Request::builder()
+            .method(method.as_str())
+            .uri(uri)
+            .body(Box::new(Cursor::new(body)) as Box<dyn AsyncRead + Sync + Send + Unpin>)
+            .context("failed to build request")?;
+        *request.headers_mut() = into_header_map(header)?;
+        Ok(request)
+    }
+}
+
+impl HttpClientRequest {
+    /// Reads the body of an [`http::Request`] to end and converts the request into a [`HttpClientRequest`].
+    /// Trailers, if any, are discarded.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading the body fails
+    pub async fn from_http(
+        request: http::Request<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+    ) -> anyhow::Result<Self> {
+        let (
+            http::request::Parts {
+                method,
+                uri,
+                headers,
+                ..
+            },
+            mut body,
+        ) = request.into_parts();
+        let mut buf = vec![];
+        body.read_to_end(&mut buf)
+            .await
+            .context("failed to read request body")?;
+        Ok(Self {
+            method: method.to_string(),
+            url: uri.to_string(),
+            headers: from_header_map(&headers),
+            body: buf,
+        })
+    }
+}
+
+impl HttpResponse {
+    /// Reads the body of an [`http::Response`] to end and converts the response into a [`HttpResponse`].
+    /// Trailers, if any, are discarded.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading the body fails
+    pub async fn from_http(
+        response: http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>,
+    ) -> anyhow::Result<Self> {
+        let (
+            http::response::Parts {
+                status, headers, ..
+            },
+            mut body,
+        ) = response.into_parts();
+        let mut buf = vec![];
+        body.read_to_end(&mut buf)
+            .await
+            .context("failed to read response body")?;
+        Ok(Self {
+            status_code: status.as_u16(),
+            header: from_header_map(&headers),
+            body: buf,
+        })
+    }
+}
+
+impl TryFrom<HttpResponse> for http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>> {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        HttpResponse {
+            status_code,
+            header,
+            body,
+        }: HttpResponse,
+    ) -> Result<Self, Self::Error> {
+        let mut response = http::Response::builder()
+            .status(status_code)
+            .body(Box::new(Cursor::new(body)) as Box<dyn AsyncRead + Sync + Send + Unpin>)
+            .context("failed to build response")?;
+        *response.headers_mut() = into_header_map(header)?;
+        Ok(response)
+    }
+}
+
+/// Message received as part of a subscription as defined by the `wasmcloud:messaging` contract
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SubMessage {
+    /// The subject, or topic, of the message
+    #[serde(default)]
+    pub subject: String,
+    /// An optional topic on which the reply should be sent
+    #[serde(rename = "replyTo")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    /// The message payload
+    #[serde(with = "serde_bytes")]
+    #[serde(default)]
+    pub body: Vec<u8>,
+}
+
+impl From<SubMessage> for messaging::types::BrokerMessage {
+    fn from(
+        SubMessage {
+            subject,
+            reply_to,
+            body,
+        }: SubMessage,
+    ) -> Self {
+        Self {
+            subject,
+            reply_to,
+            body: Some(body),
+        }
+    }
+}
+
+impl From<messaging::types::BrokerMessage> for SubMessage {
+    fn from(
+        messaging::types::BrokerMessage {
+            subject,
+            reply_to,
+            body,
+        }: messaging::types::BrokerMessage,
+    ) -> Self {
+        Self {
+            subject,
+            reply_to,
+            body: body.unwrap_or_default(),
+        }
+    }
+}
+
+/// Object chunk as defined by the `wasmcloud:blobstore` contract
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chunk {
+    /// Object ID
+    pub object_id: String,
+    /// Container ID
+    pub container_id: String,
+    /// Chunk contents
+    #[serde(with = "serde_bytes")]
+    #[serde(default)]
+    pub bytes: Vec<u8>,
+    /// Offset of the chunk within the object
+    pub offset: u64,
+    /// Whether this is the last chunk of the object
+    pub is_last: bool,
+}
+
+impl From<Chunk> for blobstore::types::Chunk {
+    fn from(
+        Chunk {
+            object_id,
+            container_id,
+            bytes,
+            offset,
+            is_last,
+        }: Chunk,
+    ) -> Self {
+        Self {
+            object_id,
+            container_id,
+            bytes,
+            offset,
+            is_last,
+        }
+    }
+}
+
+impl From<blobstore::types::Chunk> for Chunk {
+    fn from(
+        blobstore::types::Chunk {
+            object_id,
+            container_id,
+            bytes,
+            offset,
+            is_last,
+        }: blobstore::types::Chunk,
+    ) -> Self {
+        Self {
+            object_id,
+            container_id,
+            bytes,
+            offset,
+            is_last,
+        }
+    }
+}
+
+/// Response to a delivered object chunk as defined by the `wasmcloud:blobstore` contract
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkResponse {
+    /// Whether the download should be cancelled
+    #[serde(default)]
+    pub cancel_download: bool,
+}
@@ -1,9 +1,15 @@
 pub(crate) mod builtin;
 
+/// Compatibility types for wasmbus contracts
+pub mod compat;
+
 /// Provider implementations
 pub mod provider;
 
-pub use builtin::{Blobstore, Bus, IncomingHttp, KeyValueReadWrite, Logging, Messaging};
+pub use builtin::{
+    Blobstore, BlobstoreHandler, Bus, HttpTrailers, IncomingHttp, KeyValueAtomic, KeyValueBatch,
+    KeyValueReadWrite, Logging, Messaging, MessagingHandler, OutgoingHttp,
+};
 
 #[allow(clippy::doc_markdown)]
 #[allow(missing_docs)]
@@ -31,6 +37,7 @@ mod bindgen {
     });
 }
 
+pub use bindgen::wasi::http;
 pub use bindgen::wasi::keyvalue;
 pub use bindgen::wasi::logging;
 pub use bindgen::wasmcloud::blobstore;
@@ -0,0 +1,467 @@
+use crate::capability::{KeyValueAtomic, KeyValueBatch, KeyValueReadWrite};
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context};
+use async_trait::async_trait;
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tracing::{instrument, trace};
+
+/// Name of the directory under the root, which values are written to before being moved into place
+const TMP_DIR: &str = ".tmp";
+
+/// Maximum length of bucket names and keys in bytes. Names are hex-encoded to produce file names,
+/// so this ensures file names do not exceed the common limit of 255 bytes.
+pub const MAX_NAME_LEN: usize = 127;
+
+/// Returns the file name used for bucket name or key `name`
+fn encode_name(name: &str) -> anyhow::Result<String> {
+    ensure!(!name.is_empty(), "name cannot be empty");
+    ensure!(
+        name.len() <= MAX_NAME_LEN,
+        "name length of {} bytes exceeds limit of {MAX_NAME_LEN} bytes",
+        name.len()
+    );
+    Ok(hex::encode(name))
+}
+
+/// Returns the bucket name or key encoded as file name `name`, if it is valid
+fn decode_name(name: &OsStr) -> Option<String> {
+    let name = hex::decode(name.to_str()?).ok()?;
+    String::from_utf8(name).ok()
+}
+
+/// Reads the value stored at `path`, returning [None] if it does not exist
+async fn read_value(path: &Path) -> anyhow::Result<Option<Vec<u8>>> {
+    match fs::read(path).await {
+        Ok(buf) => Ok(Some(buf)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("failed to read `{}`", path.display())),
+    }
+}
+
+/// Returns the size of the value stored at `path` or 0 if it does not exist
+async fn value_size(path: &Path) -> anyhow::Result<u64> {
+    match fs::metadata(path).await {
+        Ok(md) => Ok(md.len()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e).with_context(|| format!("failed to stat `{}`", path.display())),
+    }
+}
+
+/// Flushes directory entries of `dir` to disk, so that renames and removals within it survive a crash
+async fn sync_dir(dir: &Path) -> anyhow::Result<()> {
+    // NOTE: Directories cannot be opened as files on all platforms, notably Windows
+    if cfg!(unix) {
+        fs::File::open(dir)
+            .await
+            .with_context(|| format!("failed to open `{}`", dir.display()))?
+            .sync_all()
+            .await
+            .with_context(|| format!("failed to sync `{}`", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Reads `value` into memory, reading at most one byte more than `max`, if set, to detect values
+/// exceeding it
+async fn read_to_end(value: impl AsyncRead + Unpin, max: Option<u64>) -> anyhow::Result<Vec<u8>> {
+    let mut buf = vec![];
+    value
+        .take(max.map_or(u64::MAX, |max| max.saturating_add(1)))
+        .read_to_end(&mut buf)
+        .await
+        .context("failed to read value")?;
+    if let Some(max) = max {
+        let len: u64 = buf.len().try_into().context("size does not fit in `u64`")?;
+        ensure!(len <= max, "value size exceeds limit of {max} bytes");
+    }
+    Ok(buf)
+}
+
+/// Parses a numeric value stored as a decimal string, as done by `wasmcloud:keyvalue` providers
+fn parse_u64(value: &[u8]) -> anyhow::Result<u64> {
+    std::str::from_utf8(value)
+        .context("value is not valid UTF-8")?
+        .parse()
+        .context("value is not a valid `u64`")
+}
+
+/// File-backed [`KeyValueReadWrite`] implementation, which stores each bucket in a directory under
+/// the root and each value in a separate file within it.
+///
+/// Values are written to a temporary file, synced to disk and atomically renamed into place, so a
+/// crash never leaves a partially-written value behind. Batch operations are not atomic as a whole.
+/// The store must not be used by multiple processes concurrently.
+#[derive(Debug)]
+pub struct KeyValue {
+    root: PathBuf,
+    max_value_size: Option<u64>,
+    max_bucket_size: Option<u64>,
+    /// Sizes of buckets in bytes, which are computed on first write if [`Self::max_bucket_size`] is set.
+    /// The lock is held for the duration of every mutation.
+    sizes: Mutex<HashMap<String, u64>>,
+    next_tmp: AtomicU64,
+}
+
+impl KeyValue {
+    /// Opens a store rooted at `root`, creating the directory if it does not exist.
+    /// Temporary files left behind by a previous crash are removed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the root directory cannot be created or cleaned up
+    #[instrument(skip(root), fields(root = %root.as_ref().display()))]
+    pub async fn open(root: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let tmp = root.join(TMP_DIR);
+        match fs::remove_dir_all(&tmp).await {
+            Ok(()) => trace!("removed temporary files"),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to remove `{}`", tmp.display()))
+            }
+        }
+        fs::create_dir_all(&tmp)
+            .await
+            .with_context(|| format!("failed to create `{}`", tmp.display()))?;
+        Ok(Self {
+            root,
+            max_value_size: None,
+            max_bucket_size: None,
+            sizes: Mutex::default(),
+            next_tmp: AtomicU64::default(),
+        })
+    }
+
+    /// Set maximum size of a single value in bytes
+    #[must_use]
+    pub fn max_value_size(self, max_value_size: Option<u64>) -> Self {
+        Self {
+            max_value_size,
+            ..self
+        }
+    }
+
+    /// Set maximum total size of all values in a bucket in bytes
+    #[must_use]
+    pub fn max_bucket_size(self, max_bucket_size: Option<u64>) -> Self {
+        Self {
+            max_bucket_size,
+            ..self
+        }
+    }
+
+    /// Root directory of the store
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn bucket_path(&self, bucket: &str) -> anyhow::Result<PathBuf> {
+        encode_name(bucket)
+            .map(|name| self.root.join(name))
+            .context("invalid bucket name")
+    }
+
+    fn value_path(&self, bucket: &str, key: &str) -> anyhow::Result<PathBuf> {
+        let dir = self.bucket_path(bucket)?;
+        encode_name(key)
+            .map(|name| dir.join(name))
+            .context("invalid key")
+    }
+
+    /// Returns the current size of `bucket`, computing it if it is not known yet
+    async fn bucket_size(
+        &self,
+        sizes: &mut HashMap<String, u64>,
+        bucket: &str,
+    ) -> anyhow::Result<u64> {
+        if let Some(size) = sizes.get(bucket) {
+            return Ok(*size);
+        }
+        let mut size = 0;
+        for key in self.keys(bucket).await? {
+            size += value_size(&self.value_path(bucket, &key)?).await?;
+        }
+        sizes.insert(bucket.into(), size);
+        Ok(size)
+    }
+
+    /// Returns all keys in `bucket`, which is empty if the bucket does not exist
+    async fn keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        let dir = self.bucket_path(bucket)?;
+        let mut entries = match fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e).with_context(|| format!("failed to read `{}`", dir.display())),
+        };
+        let mut keys = vec![];
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .with_context(|| format!("failed to read `{}` entry", dir.display()))?
+        {
+            if let Some(key) = decode_name(&entry.file_name()) {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Returns `true` if `bucket` exists
+    async fn bucket_exists(&self, bucket: &str) -> anyhow::Result<bool> {
+        let dir = self.bucket_path(bucket)?;
+        fs::try_exists(&dir)
+            .await
+            .with_context(|| format!("failed to stat `{}`", dir.display()))
+    }
+
+    /// Reads `key` in `bucket`, failing if either does not exist
+    async fn read(&self, bucket: &str, key: &str) -> anyhow::Result<Vec<u8>> {
+        ensure!(self.bucket_exists(bucket).await?, "bucket not found");
+        read_value(&self.value_path(bucket, key)?)
+            .await?
+            .context("key not found")
+    }
+
+    /// Atomically writes `value` to `key` in `bucket`, enforcing configured size limits.
+    /// Must be called with [`Self::sizes`] locked.
+    async fn write(
+        &self,
+        sizes: &mut HashMap<String, u64>,
+        bucket: &str,
+        key: &str,
+        value: &[u8],
+    ) -> anyhow::Result<()> {
+        let path = self.value_path(bucket, key)?;
+        let len: u64 = value
+            .len()
+            .try_into()
+            .context("size does not fit in `u64`")?;
+        if let Some(max) = self.max_value_size {
+            ensure!(
+                len <= max,
+                "value size of {len} bytes exceeds limit of {max} bytes"
+            );
+        }
+        let mut new_bucket_size = None;
+        if let Some(max) = self.max_bucket_size {
+            let size = self.bucket_size(sizes, bucket).await?;
+            let size = size.saturating_sub(value_size(&path).await?) + len;
+            ensure!(
+                size <= max,
+                "bucket size of {size} bytes would exceed limit of {max} bytes"
+            );
+            new_bucket_size = Some(size);
+        }
+
+        let dir = self.bucket_path(bucket)?;
+        fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("failed to create `{}`", dir.display()))?;
+        let tmp = self
+            .root
+            .join(TMP_DIR)
+            .join(self.next_tmp.fetch_add(1, Ordering::Relaxed).to_string());
+        let mut file = fs::File::create(&tmp)
+            .await
+            .with_context(|| format!("failed to create `{}`", tmp.display()))?;
+        file.write_all(value)
+            .await
+            .with_context(|| format!("failed to write `{}`", tmp.display()))?;
+        file.sync_all()
+            .await
+            .with_context(|| format!("failed to sync `{}`", tmp.display()))?;
+        fs::rename(&tmp, &path)
+            .await
+            .with_context(|| format!("failed to rename `{}`", tmp.display()))?;
+        sync_dir(&dir).await?;
+        if let Some(size) = new_bucket_size {
+            sizes.insert(bucket.into(), size);
+        }
+        Ok(())
+    }
+
+    /// Removes `key` from `bucket`, returning `false` if it does not exist.
+    /// Must be called with [`Self::sizes`] locked.
+    async fn remove(
+        &self,
+        sizes: &mut HashMap<String, u64>,
+        bucket: &str,
+        key: &str,
+    ) -> anyhow::Result<bool> {
+        let path = self.value_path(bucket, key)?;
+        let len: u64 = value_size(&path).await?;
+        match fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to remove `{}`", path.display()))
+            }
+        }
+        sync_dir(&self.bucket_path(bucket)?).await?;
+        if let Some(size) = sizes.get_mut(bucket) {
+            *size = size.saturating_sub(len);
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl KeyValueReadWrite for KeyValue {
+    #[instrument]
+    async fn get(
+        &self,
+        bucket: &str,
+        key: String,
+    ) -> anyhow::Result<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)> {
+        let value = self.read(bucket, &key).await?;
+        let size = value
+            .len()
+            .try_into()
+            .context("size does not fit in `u64`")?;
+        Ok((Box::new(Cursor::new(value)), size))
+    }
+
+    #[instrument(skip(value))]
+    async fn set(
+        &self,
+        bucket: &str,
+        key: String,
+        value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+    ) -> anyhow::Result<()> {
+        let value = read_to_end(value, self.max_value_size).await?;
+        let mut sizes = self.sizes.lock().await;
+        self.write(&mut sizes, bucket, &key, &value).await
+    }
+
+    #[instrument]
+    async fn delete(&self, bucket: &str, key: String) -> anyhow::Result<()> {
+        let mut sizes = self.sizes.lock().await;
+        ensure!(self.bucket_exists(bucket).await?, "bucket not found");
+        ensure!(
+            self.remove(&mut sizes, bucket, &key).await?,
+            "key not found"
+        );
+        Ok(())
+    }
+
+    #[instrument]
+    async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool> {
+        ensure!(self.bucket_exists(bucket).await?, "bucket not found");
+        let path = self.value_path(bucket, &key)?;
+        fs::try_exists(&path)
+            .await
+            .with_context(|| format!("failed to stat `{}`", path.display()))
+    }
+}
+
+#[async_trait]
+impl KeyValueAtomic for KeyValue {
+    #[instrument]
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
+        let mut sizes = self.sizes.lock().await;
+        let value = match read_value(&self.value_path(bucket, &key)?).await? {
+            Some(value) => parse_u64(&value)?,
+            None => 0,
+        };
+        let Some(value) = value.checked_add(delta) else {
+            bail!("value overflow")
+        };
+        self.write(&mut sizes, bucket, &key, value.to_string().as_bytes())
+            .await?;
+        Ok(value)
+    }
+
+    #[instrument]
+    async fn compare_and_swap(
+        &self,
+        bucket: &str,
+        key: String,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<bool> {
+        let mut sizes = self.sizes.lock().await;
+        let value = self.read(bucket, &key).await?;
+        if parse_u64(&value)? != old {
+            return Ok(false);
+        }
+        self.write(&mut sizes, bucket, &key, new.to_string().as_bytes())
+            .await?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl KeyValueBatch for KeyValue {
+    #[instrument]
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        ensure!(self.bucket_exists(bucket).await?, "bucket not found");
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            let value = read_value(&self.value_path(bucket, &key)?)
+                .await?
+                .with_context(|| format!("key `{key}` not found"))?;
+            let size = value
+                .len()
+                .try_into()
+                .context("size does not fit in `u64`")?;
+            values.push((
+                Box::new(Cursor::new(value)) as Box<dyn AsyncRead + Sync + Send + Unpin>,
+                size,
+            ));
+        }
+        Ok(values)
+    }
+
+    #[instrument]
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        self.keys(bucket).await
+    }
+
+    #[instrument(skip(entries))]
+    async fn set_many(
+        &self,
+        bucket: &str,
+        entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        let mut values = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            let value = read_to_end(value, self.max_value_size)
+                .await
+                .with_context(|| format!("failed to read value of `{key}`"))?;
+            values.push((key, value));
+        }
+        let mut sizes = self.sizes.lock().await;
+        for (key, value) in values {
+            self.write(&mut sizes, bucket, &key, &value)
+                .await
+                .with_context(|| format!("failed to write `{key}`"))?;
+        }
+        Ok(())
+    }
+
+    #[instrument]
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        let mut sizes = self.sizes.lock().await;
+        ensure!(self.bucket_exists(bucket).await?, "bucket not found");
+        for key in keys {
+            self.remove(&mut sizes, bucket, &key)
+                .await
+                .with_context(|| format!("failed to delete `{key}`"))?;
+        }
+        Ok(())
+    }
+}
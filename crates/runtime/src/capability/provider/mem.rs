@@ -1,11 +1,11 @@
-use crate::capability::KeyValueReadWrite;
+use crate::capability::{KeyValueAtomic, KeyValueBatch, KeyValueReadWrite};
 
 use std::collections::{hash_map, BTreeMap, HashMap};
 use std::io::Cursor;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::sync::RwLock;
 use tracing::instrument;
 
@@ -15,6 +15,18 @@ type Bucket = HashMap<String, Vec<u8>>;
 #[derive(Debug)]
 pub struct KeyValue(RwLock<HashMap<String, RwLock<Bucket>>>);
 
+impl KeyValue {
+    /// Returns a copy of all buckets and their contents
+    pub async fn snapshot(&self) -> HashMap<String, Bucket> {
+        let kv = self.0.read().await;
+        let mut snapshot = HashMap::with_capacity(kv.len());
+        for (name, bucket) in kv.iter() {
+            snapshot.insert(name.clone(), bucket.read().await.clone());
+        }
+        snapshot
+    }
+}
+
 impl FromIterator<(String, RwLock<Bucket>)> for KeyValue {
     fn from_iter<T: IntoIterator<Item = (String, RwLock<Bucket>)>>(iter: T) -> Self {
         Self(RwLock::new(iter.into_iter().collect()))
@@ -120,3 +132,117 @@ impl KeyValueReadWrite for KeyValue {
         Ok(bucket.contains_key(&key))
     }
 }
+
+/// Parses a numeric value stored as a decimal string, as done by `wasmcloud:keyvalue` providers
+fn parse_u64(value: &[u8]) -> anyhow::Result<u64> {
+    std::str::from_utf8(value)
+        .context("value is not valid UTF-8")?
+        .parse()
+        .context("value is not a valid `u64`")
+}
+
+#[async_trait]
+impl KeyValueAtomic for KeyValue {
+    #[instrument]
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
+        let mut kv = self.0.write().await;
+        let mut bucket = kv.entry(bucket.into()).or_default().write().await;
+        let value = match bucket.get(&key) {
+            Some(value) => parse_u64(value)?,
+            None => 0,
+        };
+        let Some(value) = value.checked_add(delta) else {
+            bail!("value overflow")
+        };
+        bucket.insert(key, value.to_string().into_bytes());
+        Ok(value)
+    }
+
+    #[instrument]
+    async fn compare_and_swap(
+        &self,
+        bucket: &str,
+        key: String,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<bool> {
+        let kv = self.0.read().await;
+        let mut bucket = kv.get(bucket).context("bucket not found")?.write().await;
+        let value = bucket.get_mut(&key).context("key not found")?;
+        if parse_u64(value)? != old {
+            return Ok(false);
+        }
+        *value = new.to_string().into_bytes();
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl KeyValueBatch for KeyValue {
+    #[instrument]
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        let kv = self.0.read().await;
+        let bucket = kv.get(bucket).context("bucket not found")?.read().await;
+        keys.into_iter()
+            .map(|key| {
+                let value = bucket
+                    .get(&key)
+                    .with_context(|| format!("key `{key}` not found"))?;
+                let size = value
+                    .len()
+                    .try_into()
+                    .context("size does not fit in `u64`")?;
+                Ok((
+                    Box::new(Cursor::new(value.clone()))
+                        as Box<dyn AsyncRead + Sync + Send + Unpin>,
+                    size,
+                ))
+            })
+            .collect()
+    }
+
+    #[instrument]
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        let kv = self.0.read().await;
+        let Some(bucket) = kv.get(bucket) else {
+            return Ok(vec![])
+        };
+        let keys = bucket.read().await.keys().cloned().collect();
+        Ok(keys)
+    }
+
+    #[instrument(skip(entries))]
+    async fn set_many(
+        &self,
+        bucket: &str,
+        entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        let mut values = Vec::with_capacity(entries.len());
+        for (key, mut value) in entries {
+            let mut buf = vec![];
+            value
+                .read_to_end(&mut buf)
+                .await
+                .with_context(|| format!("failed to read value of `{key}`"))?;
+            values.push((key, buf));
+        }
+        let mut kv = self.0.write().await;
+        let mut bucket = kv.entry(bucket.into()).or_default().write().await;
+        bucket.extend(values);
+        Ok(())
+    }
+
+    #[instrument]
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        let kv = self.0.read().await;
+        let mut bucket = kv.get(bucket).context("bucket not found")?.write().await;
+        for key in keys {
+            bucket.remove(&key);
+        }
+        Ok(())
+    }
+}
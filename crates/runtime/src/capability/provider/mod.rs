@@ -1,4 +1,7 @@
+/// File-backed provider implementations
+pub mod file;
 /// In-memory provider implementations
 pub mod mem;
 
+pub use file::KeyValue as FileKeyValue;
 pub use mem::KeyValue as MemoryKeyValue;
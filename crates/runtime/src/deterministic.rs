@@ -0,0 +1,208 @@
+use core::time::Duration;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use wasmtime_wasi::preview2::{self, HostMonotonicClock, HostWallClock};
+
+/// Virtual clock, which only advances when explicitly requested via [`VirtualClock::advance`].
+/// Clones of a [`VirtualClock`] share the same state.
+#[derive(Clone, Debug)]
+pub struct VirtualClock {
+    /// Wall clock time as nanoseconds since UNIX epoch
+    wall: Arc<AtomicU64>,
+    /// Monotonic clock time as nanoseconds since clock creation
+    monotonic: Arc<AtomicU64>,
+}
+
+impl VirtualClock {
+    /// Returns a new [`VirtualClock`] with wall clock set to `now`
+    #[must_use]
+    pub fn new(now: SystemTime) -> Self {
+        let wall = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            .try_into()
+            .unwrap_or(u64::MAX);
+        Self {
+            wall: Arc::new(AtomicU64::new(wall)),
+            monotonic: Arc::default(),
+        }
+    }
+
+    /// Advances both wall and monotonic clocks by `duration`
+    pub fn advance(&self, duration: Duration) {
+        let nanos = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        self.wall.fetch_add(nanos, Ordering::Relaxed);
+        self.monotonic.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    /// Returns current wall clock time as [`Duration`] since UNIX epoch
+    #[must_use]
+    pub fn wall(&self) -> Duration {
+        Duration::from_nanos(self.wall.load(Ordering::Relaxed))
+    }
+
+    /// Returns current monotonic clock time as nanoseconds since clock creation
+    #[must_use]
+    pub fn monotonic(&self) -> u64 {
+        self.monotonic.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new(UNIX_EPOCH)
+    }
+}
+
+impl HostWallClock for VirtualClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self) -> Duration {
+        self.wall()
+    }
+}
+
+impl HostMonotonicClock for VirtualClock {
+    fn resolution(&self) -> u64 {
+        1
+    }
+
+    fn now(&self) -> u64 {
+        self.monotonic()
+    }
+}
+
+/// Random number generator, which can be reseeded while in use by an actor instance.
+/// Clones of a [`SharedRng`] share the same state.
+#[derive(Clone, Debug)]
+pub(crate) struct SharedRng(Arc<Mutex<StdRng>>);
+
+impl SharedRng {
+    /// Returns a new [`SharedRng`] seeded with `seed`
+    pub(crate) fn seed_from_u64(seed: u64) -> Self {
+        Self(Arc::new(Mutex::new(StdRng::seed_from_u64(seed))))
+    }
+
+    /// Returns a new [`SharedRng`] seeded from operating system entropy
+    pub(crate) fn from_entropy() -> Self {
+        Self(Arc::new(Mutex::new(StdRng::from_entropy())))
+    }
+
+    /// Resets the state to that of a new [`SharedRng`] seeded with `seed`
+    pub(crate) fn reseed(&self, seed: u64) {
+        *self.lock() = StdRng::seed_from_u64(seed);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StdRng> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl RngCore for SharedRng {
+    fn next_u32(&mut self) -> u32 {
+        self.lock().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.lock().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.lock().fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.lock().try_fill_bytes(dest)
+    }
+}
+
+/// `wasi:clocks/wall-clock` implementation for actor modules
+struct ModuleWallClock(VirtualClock);
+
+impl wasi_common::WasiSystemClock for ModuleWallClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self, _precision: Duration) -> cap_std::time::SystemTime {
+        cap_std::time::SystemTime::from_std(UNIX_EPOCH + self.0.wall())
+    }
+}
+
+/// `wasi:clocks/monotonic-clock` implementation for actor modules
+struct ModuleMonotonicClock {
+    clock: VirtualClock,
+    start: Instant,
+}
+
+impl wasi_common::WasiMonotonicClock for ModuleMonotonicClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self, _precision: Duration) -> cap_std::time::Instant {
+        let elapsed = Duration::from_nanos(self.clock.monotonic());
+        cap_std::time::Instant::from_std(self.start + elapsed)
+    }
+}
+
+/// Deterministic actor execution configuration. All randomness available to actors is derived from `seed`
+/// and all clocks available to actors are driven by `clock`.
+#[derive(Clone, Debug, Default)]
+pub struct Deterministic {
+    /// Seed used to initialize random number generators of each actor instance
+    pub seed: u64,
+    /// Virtual clock shared by all actor instances
+    pub clock: VirtualClock,
+}
+
+impl Deterministic {
+    /// Returns a new random number generator seeded with [`Self::seed`]
+    pub(crate) fn rng(&self) -> SharedRng {
+        SharedRng::seed_from_u64(self.seed)
+    }
+
+    /// Configures randomness and clocks of a component [`preview2::WasiCtxBuilder`] using
+    /// `secure` and `insecure` random number generators
+    pub(crate) fn configure_wasi(
+        &self,
+        wasi: preview2::WasiCtxBuilder,
+        secure: SharedRng,
+        insecure: SharedRng,
+    ) -> preview2::WasiCtxBuilder {
+        wasi.set_secure_random_to_custom_generator(secure)
+            .set_insecure_random(insecure)
+            .set_insecure_random_seed(self.seed.into())
+            .set_wall_clock(self.clock.clone())
+            .set_monotonic_clock(self.clock.clone())
+    }
+
+    /// Returns a new module [`wasi_common::WasiCtx`] with deterministic clocks and randomness
+    /// produced by `rng`
+    ///
+    /// NOTE: Unlike [`preview2::WasiCtxBuilder`], [`wasmtime_wasi::WasiCtxBuilder`] does not allow
+    /// replacing the random number generator or clocks, so the context is constructed from the same
+    /// parts the builder uses, except for randomness and clocks.
+    pub(crate) fn module_wasi(&self, rng: SharedRng) -> wasi_common::WasiCtx {
+        let clocks = wasi_common::WasiClocks::new()
+            .with_system(ModuleWallClock(self.clock.clone()))
+            .with_monotonic(ModuleMonotonicClock {
+                clock: self.clock.clone(),
+                start: Instant::now(),
+            });
+        wasi_common::WasiCtx::new(
+            Box::new(rng),
+            clocks,
+            wasmtime_wasi::sched_ctx(),
+            wasi_common::table::Table::new(),
+        )
+    }
+}
@@ -11,6 +11,15 @@ pub mod actor;
 /// Capability provider implementations and adaptors
 pub mod capability;
 
+/// Deterministic actor execution support
+pub mod deterministic;
+
+/// Guest profiling of actor invocations
+pub mod profiling;
+
+/// Actor invocation record and replay support
+pub mod record;
+
 /// Shared wasmCloud runtime engine
 pub mod runtime;
 
@@ -18,6 +27,9 @@ pub use actor::{
     Actor, Config as ActorConfig, Instance as ActorInstance, InstancePool as ActorInstancePool,
     PooledInstance as PooledActorInstance,
 };
+pub use deterministic::{Deterministic, VirtualClock};
+pub use profiling::{NativeProfiler, Profiling};
+pub use record::{Recording, Replay};
 pub use runtime::*;
 
 pub use async_trait::async_trait;
@@ -0,0 +1,320 @@
+use core::fmt::{self, Debug};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::time::Duration;
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use fxprof_processed_profile::{
+    CategoryHandle, CpuDelta, Frame, FrameFlags, FrameInfo, Profile, ReferenceTimestamp,
+    SamplingInterval, ThreadHandle, Timestamp,
+};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::{debug, warn};
+use wascap::jwt;
+use wasmtime::{UpdateDeadline, WasmBacktrace};
+
+/// Native profiler integration of the engine, which allows external tools to attribute samples to actor code
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NativeProfiler {
+    /// Write a perf map to `/tmp/perf-{pid}.map` for use with `perf` on Linux
+    PerfMap,
+    /// Write jitdump files for use with `perf` on Linux
+    JitDump,
+    /// Report compiled code to Intel `VTune`
+    VTune,
+}
+
+impl From<NativeProfiler> for wasmtime::ProfilingStrategy {
+    fn from(profiler: NativeProfiler) -> Self {
+        match profiler {
+            NativeProfiler::PerfMap => Self::PerfMap,
+            NativeProfiler::JitDump => Self::JitDump,
+            NativeProfiler::VTune => Self::VTune,
+        }
+    }
+}
+
+/// Guest profiling configuration
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Profiling {
+    /// Directory, to which profiles of sampled invocations are written in Firefox profiler format
+    pub dir: PathBuf,
+    /// Interval at which invocations are sampled
+    pub interval: Duration,
+    /// Whether invocations of all actors are sampled. If `false`, only invocations of actors enabled via
+    /// [`Runtime::set_actor_profiling`](crate::Runtime::set_actor_profiling) are sampled.
+    pub all: bool,
+}
+
+impl Profiling {
+    /// Returns a new [`Profiling`] configuration writing profiles to `dir`, sampling every millisecond
+    /// and with no actors sampled by default
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            interval: Duration::from_millis(1),
+            all: false,
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Thread incrementing the engine epoch, which drives sampling. The thread is stopped on drop.
+struct Ticker(Arc<AtomicBool>);
+
+impl Ticker {
+    fn spawn(engine: wasmtime::Engine, interval: Duration) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        thread::spawn({
+            let stop = Arc::clone(&stop);
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    thread::sleep(interval);
+                    engine.increment_epoch();
+                }
+            }
+        });
+        Self(stop)
+    }
+}
+
+impl Drop for Ticker {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Guest profiling state shared by a [`Runtime`](crate::Runtime) and all of its actors
+#[derive(Clone)]
+pub(crate) struct Profiler {
+    config: Arc<Profiling>,
+    actors: Arc<RwLock<HashSet<String>>>,
+    _ticker: Arc<Ticker>,
+}
+
+impl Debug for Profiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Profiler")
+            .field("config", &self.config)
+            .field("actors", &self.actors)
+            .finish()
+    }
+}
+
+impl Profiler {
+    /// Returns a new [`Profiler`] and starts ticking the `engine` epoch every [`Profiling::interval`].
+    /// The `engine` must have epoch interruption enabled.
+    pub(crate) fn new(engine: &wasmtime::Engine, config: Profiling) -> Self {
+        let ticker = Ticker::spawn(engine.clone(), config.interval);
+        Self {
+            config: Arc::new(config),
+            actors: Arc::default(),
+            _ticker: Arc::new(ticker),
+        }
+    }
+
+    /// Enables or disables sampling of invocations of actor identified by `actor_id`
+    pub(crate) fn set_actor(&self, actor_id: String, enable: bool) {
+        let mut actors = self.actors.write().unwrap_or_else(PoisonError::into_inner);
+        if enable {
+            actors.insert(actor_id);
+        } else {
+            actors.remove(&actor_id);
+        }
+    }
+
+    fn is_sampled(&self, actor_id: &str) -> bool {
+        self.config.all
+            || self
+                .actors
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .contains(actor_id)
+    }
+}
+
+/// Guest profiler of a single actor instance
+#[derive(Clone)]
+pub(crate) struct InstanceProfiler {
+    profiler: Profiler,
+    actor_id: String,
+    actor_name: Option<String>,
+    sampler: Arc<Mutex<Option<Sampler>>>,
+}
+
+impl Debug for InstanceProfiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstanceProfiler")
+            .field("actor_id", &self.actor_id)
+            .field("actor_name", &self.actor_name)
+            .finish()
+    }
+}
+
+impl InstanceProfiler {
+    /// Returns a new [`InstanceProfiler`] for actor identified by `claims` and configures `store` to sample
+    /// the actor on each epoch tick while an invocation is being profiled
+    pub(crate) fn new<T>(
+        profiler: &Profiler,
+        claims: Option<&jwt::Claims<jwt::Actor>>,
+        store: &mut wasmtime::Store<T>,
+    ) -> Self {
+        let sampler: Arc<Mutex<Option<Sampler>>> = Arc::default();
+        store.epoch_deadline_callback({
+            let sampler = Arc::clone(&sampler);
+            move |ctx| {
+                if let Some(sampler) = lock(&sampler).as_mut() {
+                    sampler.sample(&WasmBacktrace::capture(&ctx));
+                }
+                Ok(UpdateDeadline::Continue(1))
+            }
+        });
+        store.set_epoch_deadline(1);
+        let (actor_id, actor_name) = claims.map_or_else(
+            || ("unknown".into(), None),
+            |claims| {
+                (
+                    claims.subject.clone(),
+                    claims
+                        .metadata
+                        .as_ref()
+                        .and_then(|actor| actor.name.clone()),
+                )
+            },
+        );
+        Self {
+            profiler: profiler.clone(),
+            actor_id,
+            actor_name,
+            sampler,
+        }
+    }
+
+    /// Starts sampling an invocation of `operation`, if sampling is enabled for the actor
+    pub(crate) fn start(&self, operation: &str) -> Option<Sampling> {
+        if !self.profiler.is_sampled(&self.actor_id) {
+            return None;
+        }
+        let name = self.actor_name.as_deref().unwrap_or(&self.actor_id);
+        *lock(&self.sampler) = Some(Sampler::new(
+            &format!("{name} ({})", self.actor_id),
+            operation,
+            self.profiler.config.interval,
+        ));
+        Some(Sampling {
+            dir: self.profiler.config.dir.clone(),
+            actor_id: self.actor_id.clone(),
+            sampler: Arc::clone(&self.sampler),
+        })
+    }
+}
+
+/// Sampled invocation in progress
+pub(crate) struct Sampling {
+    dir: PathBuf,
+    actor_id: String,
+    sampler: Arc<Mutex<Option<Sampler>>>,
+}
+
+impl Sampling {
+    /// Stops sampling and writes the profile to disk
+    pub(crate) async fn finish(self) {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let Some(sampler) = lock(&self.sampler).take() else {
+            return;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = self.dir.join(format!("{}-{now}-{n}.json", self.actor_id));
+        // NOTE: Failure to write a profile must not fail the invocation
+        let res = async {
+            let buf = serde_json::to_vec(&sampler.profile).context("failed to encode profile")?;
+            fs::create_dir_all(&self.dir)
+                .await
+                .context("failed to create profile directory")?;
+            fs::write(&path, buf)
+                .await
+                .with_context(|| format!("failed to write `{}`", path.display()))
+        }
+        .await;
+        match res {
+            Ok(()) => debug!(path = %path.display(), "wrote actor profile"),
+            Err(e) => warn!("failed to write actor profile: {e:#}"),
+        }
+    }
+}
+
+/// Firefox profiler-compatible profile of a single invocation
+struct Sampler {
+    profile: Profile,
+    thread: ThreadHandle,
+    start: Instant,
+}
+
+impl Sampler {
+    fn new(actor: &str, operation: &str, interval: Duration) -> Self {
+        let mut profile = Profile::new(
+            "wasmCloud",
+            ReferenceTimestamp::from_system_time(SystemTime::now()),
+            SamplingInterval::from_nanos(interval.as_nanos().try_into().unwrap_or(u64::MAX)),
+        );
+        let start = Timestamp::from_millis_since_reference(0.0);
+        let process = profile.add_process(actor, std::process::id(), start);
+        let thread = profile.add_thread(process, std::process::id(), start, true);
+        profile.set_thread_name(thread, operation);
+        Self {
+            profile,
+            thread,
+            start: Instant::now(),
+        }
+    }
+
+    fn sample(&mut self, backtrace: &WasmBacktrace) {
+        let frames: Vec<_> = backtrace
+            .frames()
+            .iter()
+            .rev()
+            .map(|frame| {
+                let module = frame.module_name().unwrap_or("<unknown>");
+                let name = if let Some(func) = frame.func_name() {
+                    format!("{module}!{func}")
+                } else {
+                    format!("{module}!<wasm function {}>", frame.func_index())
+                };
+                FrameInfo {
+                    frame: Frame::Label(self.profile.intern_string(&name)),
+                    category_pair: CategoryHandle::OTHER.into(),
+                    flags: FrameFlags::empty(),
+                }
+            })
+            .collect();
+        let elapsed = self
+            .start
+            .elapsed()
+            .as_nanos()
+            .try_into()
+            .unwrap_or(u64::MAX);
+        self.profile.add_sample(
+            self.thread,
+            Timestamp::from_nanos_since_reference(elapsed),
+            frames.into_iter(),
+            CpuDelta::ZERO,
+            1,
+        );
+    }
+}
@@ -0,0 +1,908 @@
+use crate::capability::builtin::Handler;
+use crate::capability::compat::SubMessage;
+use crate::capability::logging::logging;
+use crate::capability::{
+    messaging, Bus, KeyValueAtomic, KeyValueBatch, KeyValueReadWrite, Logging, Messaging,
+};
+use crate::deterministic::SharedRng;
+use crate::{ActorInstance, Deterministic, VirtualClock};
+
+use core::fmt::{self, Debug};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use core::task::{ready, Context as TaskContext, Poll};
+use core::time::Duration;
+
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, ensure, Context};
+use async_trait::async_trait;
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::fs;
+use tokio::io::{sink, AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
+use tracing::{instrument, warn};
+
+const BUS_CALL: &str = "wasmcloud:bus/host.call";
+
+/// Host call performed by an actor during a recorded invocation
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HostCall {
+    /// Called function, for example `wasi:keyvalue/readwrite.get`
+    pub function: String,
+    /// Call parameters
+    pub params: serde_json::Value,
+    /// Call result, errors are recorded as strings
+    pub result: Result<serde_json::Value, String>,
+}
+
+/// Recorded actor invocation
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Recording {
+    /// Invoked operation
+    pub operation: String,
+    /// Request payload
+    pub request: Vec<u8>,
+    /// Response payload produced by the actor
+    pub response: Vec<u8>,
+    /// Invocation result, outer error represents a failure to call the actor, inner - the
+    /// application-layer error originating from within the actor itself
+    pub result: Result<Result<(), String>, String>,
+    /// Host calls performed by the actor in order they were made
+    pub calls: Vec<HostCall>,
+    /// Seed of the deterministic runtime, if deterministic execution was enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Seed the actor random number generators were reseeded with at the start of the invocation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation_seed: Option<u64>,
+    /// Wall clock time at the start of invocation, if deterministic execution was enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<Duration>,
+}
+
+impl Recording {
+    /// Reads a [`Recording`] from a JSON file at `path`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read or does not contain a valid recording
+    pub async fn read(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let buf = fs::read(path)
+            .await
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        serde_json::from_slice(&buf).context("failed to decode recording")
+    }
+
+    /// Writes the [`Recording`] as JSON to a file at `path`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be written
+    pub async fn write(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let buf = serde_json::to_vec(self).context("failed to encode recording")?;
+        fs::write(path, buf)
+            .await
+            .with_context(|| format!("failed to write `{}`", path.display()))
+    }
+}
+
+/// Divergence of a replayed invocation from the [`Recording`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Divergence {
+    /// Invoked operation or request payload differs from the recorded one
+    Invocation {
+        /// Invoked operation
+        operation: String,
+        /// Request payload
+        request: Vec<u8>,
+    },
+    /// Host call at `index` differs from the recorded one, subsequent host calls are not compared
+    HostCall {
+        /// Position of the host call within the invocation
+        index: usize,
+        /// Recorded host call, [`None`] if no more host calls were recorded
+        expected: Option<HostCall>,
+        /// Host call performed on replay
+        actual: HostCall,
+    },
+    /// Recorded host calls starting at `index` were not performed on replay
+    MissingHostCalls {
+        /// Position of the first missing host call within the invocation
+        index: usize,
+    },
+    /// Response payload differs from the recorded one
+    Response {
+        /// Response payload produced on replay
+        actual: Vec<u8>,
+    },
+    /// Invocation result differs from the recorded one
+    Result {
+        /// Invocation result produced on replay
+        actual: Result<Result<(), String>, String>,
+    },
+}
+
+/// Replay of a [`Recording`], which can be shared with a [`Runtime`](crate::Runtime) via
+/// [`RuntimeBuilder::replay`](crate::RuntimeBuilder::replay). Clones of a [`Replay`] share the same state.
+#[derive(Clone)]
+pub struct Replay {
+    recording: Arc<Recording>,
+    divergences: Arc<Mutex<Vec<Divergence>>>,
+}
+
+impl Debug for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replay")
+            .field("operation", &self.recording.operation)
+            .field("calls", &self.recording.calls.len())
+            .field("divergences", &self.divergences().len())
+            .finish()
+    }
+}
+
+impl From<Recording> for Replay {
+    fn from(recording: Recording) -> Self {
+        Self::new(recording)
+    }
+}
+
+impl Replay {
+    /// Returns a new [`Replay`] of `recording`
+    #[must_use]
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording: Arc::new(recording),
+            divergences: Arc::default(),
+        }
+    }
+
+    /// Returns the replayed [`Recording`]
+    #[must_use]
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Returns all divergences from the [`Recording`] observed so far
+    #[must_use]
+    pub fn divergences(&self) -> Vec<Divergence> {
+        lock(&self.divergences).clone()
+    }
+
+    /// Returns [`Deterministic`] configuration matching the one used for recording, if any
+    #[must_use]
+    pub fn deterministic(&self) -> Option<Deterministic> {
+        let Recording { seed, clock, .. } = self.recording.as_ref();
+        seed.map(|seed| Deterministic {
+            seed,
+            clock: VirtualClock::new(UNIX_EPOCH + clock.unwrap_or_default()),
+        })
+    }
+
+    fn compare(&self, actual: Recording) {
+        let Recording {
+            operation,
+            request,
+            response,
+            result,
+            calls,
+            ..
+        } = actual;
+        let expected = self.recording.as_ref();
+        let mut divergences = vec![];
+        if operation != expected.operation || request != expected.request {
+            divergences.push(Divergence::Invocation { operation, request });
+        }
+        let n = calls.len();
+        if let Some((index, actual)) = calls.into_iter().enumerate().find(|(index, actual)| {
+            expected.calls.get(*index).map_or(true, |expected| {
+                expected.function != actual.function || expected.params != actual.params
+            })
+        }) {
+            divergences.push(Divergence::HostCall {
+                index,
+                expected: expected.calls.get(index).cloned(),
+                actual,
+            });
+        } else if n < expected.calls.len() {
+            divergences.push(Divergence::MissingHostCalls { index: n });
+        }
+        if response != expected.response {
+            divergences.push(Divergence::Response { actual: response });
+        }
+        if result != expected.result {
+            divergences.push(Divergence::Result { actual: result });
+        }
+        for divergence in &divergences {
+            warn!(?divergence, "replay diverged from recording");
+        }
+        lock(&self.divergences).extend(divergences);
+    }
+}
+
+/// Record or replay mode of a [`Runtime`](crate::Runtime)
+#[derive(Clone, Debug)]
+pub(crate) enum Mode {
+    /// Record each invocation to a new file within `dir`, drawing invocation seeds from `seeds`
+    Record {
+        dir: PathBuf,
+        deterministic: Deterministic,
+        seeds: SharedRng,
+    },
+    /// Replay each invocation against a [`Replay`]
+    Replay(Replay),
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Replaces all host call handlers supporting record and replay in `handler` by `with`
+fn intercept<T>(mut handler: Handler, with: &Arc<T>) -> Handler
+where
+    T: Bus + KeyValueAtomic + KeyValueBatch + KeyValueReadWrite + Logging + Messaging + Send + Sync,
+    T: 'static,
+{
+    handler.replace_bus(with.clone());
+    handler.replace_keyvalue_atomic(with.clone());
+    handler.replace_keyvalue_batch(with.clone());
+    handler.replace_keyvalue_readwrite(with.clone());
+    handler.replace_logging(with.clone());
+    handler.replace_messaging(with.clone());
+    handler
+}
+
+/// Invoke an operation on an [`ActorInstance`] recording or replaying it according to `mode`.
+///
+/// Request and response payloads are buffered in memory and `wasmcloud:bus/host.call` streams are
+/// captured in full. Blobstore and HTTP host calls are currently neither recorded nor replayed.
+#[instrument(skip(instance, request, response))]
+pub(crate) async fn call(
+    instance: &mut ActorInstance,
+    mode: &Mode,
+    operation: &str,
+    mut request: impl AsyncRead + Send + Sync + Unpin + 'static,
+    response: impl AsyncWrite + Send + Sync + Unpin + 'static,
+) -> anyhow::Result<Result<(), String>> {
+    let mut req = vec![];
+    request
+        .read_to_end(&mut req)
+        .await
+        .context("failed to read request")?;
+
+    let handler = instance.handler_mut().clone();
+    let recorder = Arc::new(Recorder {
+        handler: match mode {
+            Mode::Record { .. } => handler.clone(),
+            Mode::Replay(replay) => intercept(handler.clone(), &Arc::new(Player::new(replay))),
+        },
+        calls: Mutex::default(),
+    });
+    // NOTE: Instances may be reused across invocations, so random number generators are reseeded to
+    // make randomness of each invocation reproducible from the recording alone
+    let (seed, invocation_seed, clock) = match mode {
+        Mode::Record {
+            deterministic: Deterministic { seed, clock },
+            seeds,
+            ..
+        } => (
+            Some(*seed),
+            Some(seeds.clone().next_u64()),
+            Some(clock.wall()),
+        ),
+        Mode::Replay(replay) => (None, replay.recording.invocation_seed, None),
+    };
+    if let Some(invocation_seed) = invocation_seed {
+        instance.reseed(invocation_seed);
+    }
+    *instance.handler_mut() = intercept(handler.clone(), &recorder);
+    let res_buf = Arc::default();
+    let res = instance
+        .call_direct(
+            operation,
+            Cursor::new(req.clone()),
+            Tee::new(response, Arc::clone(&res_buf)),
+        )
+        .await;
+    *instance.handler_mut() = handler;
+
+    let recording = Recording {
+        operation: operation.into(),
+        request: req,
+        response: lock(&res_buf).clone(),
+        result: res.as_ref().map_err(|e| format!("{e:#}")).cloned(),
+        calls: recorder.calls(),
+        seed,
+        invocation_seed,
+        clock,
+    };
+    match mode {
+        Mode::Record { dir, .. } => {
+            static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = dir.join(format!("{now}-{n}.json"));
+            // NOTE: Failure to record an invocation must not fail the invocation itself
+            if let Err(e) = fs::create_dir_all(dir)
+                .await
+                .context("failed to create recording directory")
+            {
+                warn!("failed to record invocation: {e:#}");
+            } else if let Err(e) = recording.write(&path).await {
+                warn!("failed to record invocation: {e:#}");
+            }
+        }
+        Mode::Replay(replay) => replay.compare(recording),
+    }
+    res
+}
+
+/// [`AsyncRead`] or [`AsyncWrite`] wrapper, which copies all data read or written into a shared buffer
+struct Tee<T> {
+    inner: T,
+    buf: Arc<Mutex<Vec<u8>>>,
+}
+
+impl<T> Tee<T> {
+    fn new(inner: T, buf: Arc<Mutex<Vec<u8>>>) -> Self {
+        Self { inner, buf }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Tee<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let n = buf.filled().len();
+        ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        lock(&self.buf).extend_from_slice(&buf.filled()[n..]);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Tee<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = ready!(Pin::new(&mut self.inner).poll_write(cx, buf))?;
+        lock(&self.buf).extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// `wasmcloud:bus/host.call` result as recorded in [`HostCall::result`]
+#[derive(Deserialize, Serialize)]
+struct BusResult {
+    response: Vec<u8>,
+    result: Option<Result<(), String>>,
+}
+
+/// Host call recorded by [`Recorder`]
+enum Entry {
+    Call(HostCall),
+    /// `wasmcloud:bus/host.call`, which is only complete once the streams are consumed
+    Bus {
+        params: serde_json::Value,
+        request: Arc<Mutex<Vec<u8>>>,
+        response: Arc<Mutex<Vec<u8>>>,
+        result: Arc<Mutex<Option<Result<(), String>>>>,
+    },
+}
+
+/// Host call handler, which records all calls made to the wrapped [`Handler`]
+struct Recorder {
+    handler: Handler,
+    calls: Mutex<Vec<Entry>>,
+}
+
+impl Recorder {
+    fn push<T: Serialize>(
+        &self,
+        function: &str,
+        params: serde_json::Value,
+        result: &anyhow::Result<T>,
+    ) {
+        let result = match result {
+            Ok(v) => serde_json::to_value(v).map_err(|e| format!("failed to encode result: {e}")),
+            Err(e) => Err(format!("{e:#}")),
+        };
+        lock(&self.calls).push(Entry::Call(HostCall {
+            function: function.into(),
+            params,
+            result,
+        }));
+    }
+
+    fn calls(&self) -> Vec<HostCall> {
+        lock(&self.calls)
+            .iter()
+            .map(|entry| match entry {
+                Entry::Call(call) => call.clone(),
+                Entry::Bus {
+                    params,
+                    request,
+                    response,
+                    result,
+                } => {
+                    let mut params = params.clone();
+                    params["request"] = json!(*lock(request));
+                    HostCall {
+                        function: BUS_CALL.into(),
+                        params,
+                        result: serde_json::to_value(BusResult {
+                            response: lock(response).clone(),
+                            result: lock(result).clone(),
+                        })
+                        .map_err(|e| format!("failed to encode result: {e}")),
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reads `value` to end
+async fn read_value(mut value: impl AsyncRead + Unpin) -> anyhow::Result<Vec<u8>> {
+    let mut buf = vec![];
+    value
+        .read_to_end(&mut buf)
+        .await
+        .context("failed to read value")?;
+    Ok(buf)
+}
+
+#[async_trait]
+impl Bus for Recorder {
+    async fn call(
+        &self,
+        operation: String,
+    ) -> anyhow::Result<(
+        Pin<Box<dyn Future<Output = Result<(), String>> + Send>>,
+        Box<dyn AsyncWrite + Sync + Send + Unpin>,
+        Box<dyn AsyncRead + Sync + Send + Unpin>,
+    )> {
+        let params = json!({ "operation": operation });
+        let (res, input, output) = match self.handler.call(operation).await {
+            Ok(call) => call,
+            Err(e) => {
+                lock(&self.calls).push(Entry::Call(HostCall {
+                    function: BUS_CALL.into(),
+                    params,
+                    result: Err(format!("{e:#}")),
+                }));
+                return Err(e);
+            }
+        };
+        let request = Arc::default();
+        let response = Arc::default();
+        let result = Arc::new(Mutex::default());
+        lock(&self.calls).push(Entry::Bus {
+            params,
+            request: Arc::clone(&request),
+            response: Arc::clone(&response),
+            result: Arc::clone(&result),
+        });
+        Ok((
+            Box::pin(async move {
+                let res = res.await;
+                *lock(&result) = Some(res.clone());
+                res
+            }),
+            Box::new(Tee::new(input, request)),
+            Box::new(Tee::new(output, response)),
+        ))
+    }
+}
+
+#[async_trait]
+impl KeyValueAtomic for Recorder {
+    async fn increment(&self, bucket: &str, key: String, delta: u64) -> anyhow::Result<u64> {
+        let params = json!({ "bucket": bucket, "key": key, "delta": delta });
+        let res = self.handler.increment(bucket, key, delta).await;
+        self.push("wasi:keyvalue/atomic.increment", params, &res);
+        res
+    }
+
+    async fn compare_and_swap(
+        &self,
+        bucket: &str,
+        key: String,
+        old: u64,
+        new: u64,
+    ) -> anyhow::Result<bool> {
+        let params = json!({ "bucket": bucket, "key": key, "old": old, "new": new });
+        let res = self.handler.compare_and_swap(bucket, key, old, new).await;
+        self.push("wasi:keyvalue/atomic.compare-and-swap", params, &res);
+        res
+    }
+}
+
+#[async_trait]
+impl KeyValueBatch for Recorder {
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        let params = json!({ "bucket": bucket, "keys": keys });
+        let res = async {
+            let mut values = vec![];
+            for (value, size) in self.handler.get_many(bucket, keys).await? {
+                values.push((read_value(value).await?, size));
+            }
+            anyhow::Ok(values)
+        }
+        .await;
+        self.push("wasi:keyvalue/batch.get-many", params, &res);
+        Ok(res?
+            .into_iter()
+            .map(|(value, size)| {
+                (
+                    Box::new(Cursor::new(value)) as Box<dyn AsyncRead + Sync + Send + Unpin>,
+                    size,
+                )
+            })
+            .collect())
+    }
+
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        let params = json!({ "bucket": bucket });
+        let res = self.handler.get_keys(bucket).await;
+        self.push("wasi:keyvalue/batch.get-keys", params, &res);
+        res
+    }
+
+    async fn set_many(
+        &self,
+        bucket: &str,
+        entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        let mut values = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            values.push((key, read_value(value).await?));
+        }
+        let params = json!({ "bucket": bucket, "entries": values });
+        let entries = values
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    key,
+                    Box::new(Cursor::new(value)) as Box<dyn AsyncRead + Sync + Send + Unpin>,
+                )
+            })
+            .collect();
+        let res = self.handler.set_many(bucket, entries).await;
+        self.push("wasi:keyvalue/batch.set-many", params, &res);
+        res
+    }
+
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        let params = json!({ "bucket": bucket, "keys": keys });
+        let res = self.handler.delete_many(bucket, keys).await;
+        self.push("wasi:keyvalue/batch.delete-many", params, &res);
+        res
+    }
+}
+
+#[async_trait]
+impl KeyValueReadWrite for Recorder {
+    async fn get(
+        &self,
+        bucket: &str,
+        key: String,
+    ) -> anyhow::Result<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)> {
+        let params = json!({ "bucket": bucket, "key": key });
+        let res = async {
+            let (value, size) = self.handler.get(bucket, key).await?;
+            let value = read_value(value).await?;
+            anyhow::Ok((value, size))
+        }
+        .await;
+        self.push("wasi:keyvalue/readwrite.get", params, &res);
+        let (value, size) = res?;
+        Ok((Box::new(Cursor::new(value)), size))
+    }
+
+    async fn set(
+        &self,
+        bucket: &str,
+        key: String,
+        value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+    ) -> anyhow::Result<()> {
+        let value = read_value(value).await?;
+        let params = json!({ "bucket": bucket, "key": key, "value": value });
+        let res = self
+            .handler
+            .set(bucket, key, Box::new(Cursor::new(value)))
+            .await;
+        self.push("wasi:keyvalue/readwrite.set", params, &res);
+        res
+    }
+
+    async fn delete(&self, bucket: &str, key: String) -> anyhow::Result<()> {
+        let params = json!({ "bucket": bucket, "key": key });
+        let res = self.handler.delete(bucket, key).await;
+        self.push("wasi:keyvalue/readwrite.delete", params, &res);
+        res
+    }
+
+    async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool> {
+        let params = json!({ "bucket": bucket, "key": key });
+        let res = self.handler.exists(bucket, key).await;
+        self.push("wasi:keyvalue/readwrite.exists", params, &res);
+        res
+    }
+}
+
+#[async_trait]
+impl Logging for Recorder {
+    async fn log(
+        &self,
+        level: logging::Level,
+        context: String,
+        message: String,
+    ) -> anyhow::Result<()> {
+        let params = json!({
+            "level": format!("{level:?}"),
+            "context": context,
+            "message": message,
+        });
+        let res = self.handler.log(level, context, message).await;
+        self.push("wasi:logging/logging.log", params, &res);
+        res
+    }
+}
+
+#[async_trait]
+impl Messaging for Recorder {
+    async fn request(
+        &self,
+        subject: String,
+        body: Option<Vec<u8>>,
+        timeout: Duration,
+    ) -> anyhow::Result<messaging::types::BrokerMessage> {
+        let params = json!({
+            "subject": subject,
+            "body": body,
+            "timeout": timeout,
+        });
+        let res = self
+            .handler
+            .request(subject, body, timeout)
+            .await
+            .map(SubMessage::from);
+        self.push("wasmcloud:messaging/consumer.request", params, &res);
+        res.map(Into::into)
+    }
+
+    async fn request_multi(
+        &self,
+        subject: String,
+        body: Option<Vec<u8>>,
+        timeout: Duration,
+        max_results: u32,
+    ) -> anyhow::Result<Vec<messaging::types::BrokerMessage>> {
+        let params = json!({
+            "subject": subject,
+            "body": body,
+            "timeout": timeout,
+            "max_results": max_results,
+        });
+        let res = self
+            .handler
+            .request_multi(subject, body, timeout, max_results)
+            .await
+            .map(|msgs| msgs.into_iter().map(SubMessage::from).collect::<Vec<_>>());
+        self.push("wasmcloud:messaging/consumer.request-multi", params, &res);
+        res.map(|msgs| msgs.into_iter().map(Into::into).collect())
+    }
+
+    async fn publish(&self, msg: messaging::types::BrokerMessage) -> anyhow::Result<()> {
+        let msg = SubMessage::from(msg);
+        let params = json!(msg);
+        let res = self.handler.publish(msg.into()).await;
+        self.push("wasmcloud:messaging/consumer.publish", params, &res);
+        res
+    }
+}
+
+/// Host call handler, which serves calls from a [`Recording`] in order they were recorded
+struct Player {
+    recording: Arc<Recording>,
+    next: AtomicUsize,
+}
+
+impl Player {
+    fn new(Replay { recording, .. }: &Replay) -> Self {
+        Self {
+            recording: Arc::clone(recording),
+            next: AtomicUsize::default(),
+        }
+    }
+
+    /// Returns the recorded result of the next host call, which must be a call to `function`
+    fn next<T: DeserializeOwned>(&self, function: &str) -> anyhow::Result<T> {
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        let HostCall {
+            function: recorded,
+            result,
+            ..
+        } = self.recording.calls.get(index).with_context(|| {
+            format!("replay diverged: `{function}` called, but no more host calls were recorded")
+        })?;
+        ensure!(
+            recorded == function,
+            "replay diverged: `{function}` called, but `{recorded}` was recorded"
+        );
+        match result {
+            Ok(v) => serde_json::from_value(v.clone()).context("failed to decode recorded result"),
+            Err(e) => Err(anyhow!("{e}")),
+        }
+    }
+}
+
+#[async_trait]
+impl Bus for Player {
+    async fn call(
+        &self,
+        _operation: String,
+    ) -> anyhow::Result<(
+        Pin<Box<dyn Future<Output = Result<(), String>> + Send>>,
+        Box<dyn AsyncWrite + Sync + Send + Unpin>,
+        Box<dyn AsyncRead + Sync + Send + Unpin>,
+    )> {
+        let BusResult { response, result } = self.next(BUS_CALL)?;
+        let result = result.unwrap_or_else(|| Err("no result was recorded".into()));
+        Ok((
+            Box::pin(async { result }),
+            Box::new(sink()),
+            Box::new(Cursor::new(response)),
+        ))
+    }
+}
+
+#[async_trait]
+impl KeyValueAtomic for Player {
+    async fn increment(&self, _bucket: &str, _key: String, _delta: u64) -> anyhow::Result<u64> {
+        self.next("wasi:keyvalue/atomic.increment")
+    }
+
+    async fn compare_and_swap(
+        &self,
+        _bucket: &str,
+        _key: String,
+        _old: u64,
+        _new: u64,
+    ) -> anyhow::Result<bool> {
+        self.next("wasi:keyvalue/atomic.compare-and-swap")
+    }
+}
+
+#[async_trait]
+impl KeyValueBatch for Player {
+    async fn get_many(
+        &self,
+        _bucket: &str,
+        _keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        let values: Vec<(Vec<u8>, u64)> = self.next("wasi:keyvalue/batch.get-many")?;
+        Ok(values
+            .into_iter()
+            .map(|(value, size)| {
+                (
+                    Box::new(Cursor::new(value)) as Box<dyn AsyncRead + Sync + Send + Unpin>,
+                    size,
+                )
+            })
+            .collect())
+    }
+
+    async fn get_keys(&self, _bucket: &str) -> anyhow::Result<Vec<String>> {
+        self.next("wasi:keyvalue/batch.get-keys")
+    }
+
+    async fn set_many(
+        &self,
+        _bucket: &str,
+        _entries: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        self.next("wasi:keyvalue/batch.set-many")
+    }
+
+    async fn delete_many(&self, _bucket: &str, _keys: Vec<String>) -> anyhow::Result<()> {
+        self.next("wasi:keyvalue/batch.delete-many")
+    }
+}
+
+#[async_trait]
+impl KeyValueReadWrite for Player {
+    async fn get(
+        &self,
+        _bucket: &str,
+        _key: String,
+    ) -> anyhow::Result<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)> {
+        let (value, size): (Vec<u8>, u64) = self.next("wasi:keyvalue/readwrite.get")?;
+        Ok((Box::new(Cursor::new(value)), size))
+    }
+
+    async fn set(
+        &self,
+        _bucket: &str,
+        _key: String,
+        _value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+    ) -> anyhow::Result<()> {
+        self.next("wasi:keyvalue/readwrite.set")
+    }
+
+    async fn delete(&self, _bucket: &str, _key: String) -> anyhow::Result<()> {
+        self.next("wasi:keyvalue/readwrite.delete")
+    }
+
+    async fn exists(&self, _bucket: &str, _key: String) -> anyhow::Result<bool> {
+        self.next("wasi:keyvalue/readwrite.exists")
+    }
+}
+
+#[async_trait]
+impl Logging for Player {
+    async fn log(
+        &self,
+        _level: logging::Level,
+        _context: String,
+        _message: String,
+    ) -> anyhow::Result<()> {
+        self.next("wasi:logging/logging.log")
+    }
+}
+
+#[async_trait]
+impl Messaging for Player {
+    async fn request(
+        &self,
+        _subject: String,
+        _body: Option<Vec<u8>>,
+        _timeout: Duration,
+    ) -> anyhow::Result<messaging::types::BrokerMessage> {
+        self.next::<SubMessage>("wasmcloud:messaging/consumer.request")
+            .map(Into::into)
+    }
+
+    async fn request_multi(
+        &self,
+        _subject: String,
+        _body: Option<Vec<u8>>,
+        _timeout: Duration,
+        _max_results: u32,
+    ) -> anyhow::Result<Vec<messaging::types::BrokerMessage>> {
+        let msgs: Vec<SubMessage> = self.next("wasmcloud:messaging/consumer.request-multi")?;
+        Ok(msgs.into_iter().map(Into::into).collect())
+    }
+
+    async fn publish(&self, _msg: messaging::types::BrokerMessage) -> anyhow::Result<()> {
+        self.next("wasmcloud:messaging/consumer.publish")
+    }
+}
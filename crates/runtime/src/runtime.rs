@@ -1,15 +1,19 @@
 use crate::actor::ModuleConfig;
 use crate::capability::{
-    builtin, Blobstore, Bus, IncomingHttp, KeyValueReadWrite, Logging, Messaging,
+    builtin, Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueBatch, KeyValueReadWrite,
+    Logging, Messaging, OutgoingHttp,
 };
-use crate::ActorConfig;
+use crate::profiling::Profiler;
+use crate::record::{self, Replay};
+use crate::{ActorConfig, Deterministic, NativeProfiler, Profiling};
 
 use core::fmt;
 use core::fmt::Debug;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 
 /// [`RuntimeBuilder`] used to configure and build a [Runtime]
 #[derive(Clone, Default)]
@@ -18,6 +22,10 @@ pub struct RuntimeBuilder {
     handler: builtin::HandlerBuilder,
     actor_config: ActorConfig,
     module_config: ModuleConfig,
+    deterministic: Option<Deterministic>,
+    record: Option<PathBuf>,
+    replay: Option<Replay>,
+    profiling: Option<Profiling>,
 }
 
 impl RuntimeBuilder {
@@ -32,6 +40,10 @@ impl RuntimeBuilder {
             handler: builtin::HandlerBuilder::default(),
             actor_config: ActorConfig::default(),
             module_config: ModuleConfig::default(),
+            deterministic: None,
+            record: None,
+            replay: None,
+            profiling: None,
         }
     }
 
@@ -53,6 +65,68 @@ impl RuntimeBuilder {
         }
     }
 
+    /// Enable deterministic execution of all actor instances using specified [`Deterministic`] configuration.
+    /// Random number generators of each actor instance will be seeded with the same seed and all actor clocks
+    /// will be driven by the same virtual clock.
+    #[must_use]
+    pub fn deterministic(self, deterministic: Deterministic) -> Self {
+        Self {
+            deterministic: Some(deterministic),
+            ..self
+        }
+    }
+
+    /// Record every actor invocation along with all host calls made by the actor to a new JSON file within `dir`.
+    /// Recording requires deterministic execution to be enabled, since randomness and clocks are reproduced on
+    /// replay from the recorded seeds and clock. Random number generators of the invoked actor instance are
+    /// reseeded at the start of every recorded invocation. Replaces replay, if configured.
+    #[must_use]
+    pub fn record(self, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            record: Some(dir.into()),
+            replay: None,
+            ..self
+        }
+    }
+
+    /// Replay every actor invocation against a [`Replay`], serving host calls from the recording and
+    /// collecting all divergences from it in the [`Replay`]. Unless deterministic execution is configured
+    /// explicitly, it is enabled using the recorded seed and clock, if present. Replaces recording, if configured.
+    #[must_use]
+    pub fn replay(self, replay: Replay) -> Self {
+        Self {
+            record: None,
+            replay: Some(replay),
+            ..self
+        }
+    }
+
+    /// Enable sampling guest profiler using specified [`Profiling`] configuration. Invocations of sampled actors
+    /// are profiled and written to [`Profiling::dir`] in Firefox profiler format.
+    /// If not enabled, profiling incurs no overhead.
+    #[must_use]
+    pub fn profiling(self, profiling: Profiling) -> Self {
+        let mut engine_config = self.engine_config;
+        engine_config.epoch_interruption(true);
+        Self {
+            engine_config,
+            profiling: Some(profiling),
+            ..self
+        }
+    }
+
+    /// Enable [`NativeProfiler`] integration, which allows native profilers, like `perf`, to attribute
+    /// samples to actor code
+    #[must_use]
+    pub fn native_profiler(self, profiler: NativeProfiler) -> Self {
+        let mut engine_config = self.engine_config;
+        engine_config.profiler(profiler.into());
+        Self {
+            engine_config,
+            ..self
+        }
+    }
+
     /// Set a [`Blobstore`] handler to use for all actor instances unless overriden for the instance
     #[must_use]
     pub fn blobstore(self, blobstore: Arc<impl Blobstore + Sync + Send + 'static>) -> Self {
@@ -83,6 +157,30 @@ impl RuntimeBuilder {
         }
     }
 
+    /// Set a [`KeyValueAtomic`] handler to use for all actor instances unless overriden for the instance
+    #[must_use]
+    pub fn keyvalue_atomic(
+        self,
+        keyvalue_atomic: Arc<impl KeyValueAtomic + Sync + Send + 'static>,
+    ) -> Self {
+        Self {
+            handler: self.handler.keyvalue_atomic(keyvalue_atomic),
+            ..self
+        }
+    }
+
+    /// Set a [`KeyValueBatch`] handler to use for all actor instances unless overriden for the instance
+    #[must_use]
+    pub fn keyvalue_batch(
+        self,
+        keyvalue_batch: Arc<impl KeyValueBatch + Sync + Send + 'static>,
+    ) -> Self {
+        Self {
+            handler: self.handler.keyvalue_batch(keyvalue_batch),
+            ..self
+        }
+    }
+
     /// Set a [`KeyValueReadWrite`] handler to use for all actor instances unless overriden for the instance
     #[must_use]
     pub fn keyvalue_readwrite(
@@ -113,19 +211,58 @@ impl RuntimeBuilder {
         }
     }
 
+    /// Set a [`OutgoingHttp`] handler to use for all actor instances unless overriden for the instance
+    #[must_use]
+    pub fn outgoing_http(
+        self,
+        outgoing_http: Arc<impl OutgoingHttp + Sync + Send + 'static>,
+    ) -> Self {
+        Self {
+            handler: self.handler.outgoing_http(outgoing_http),
+            ..self
+        }
+    }
+
     /// Turns this builder into a [`Runtime`]
     ///
     /// # Errors
     ///
-    /// Fails if the configuration is not valid
+    /// Fails if the configuration is not valid, for example, if recording is enabled without deterministic
+    /// execution
     pub fn build(self) -> anyhow::Result<Runtime> {
         let engine =
             wasmtime::Engine::new(&self.engine_config).context("failed to construct engine")?;
+        let deterministic = match (self.deterministic, &self.replay) {
+            (None, Some(replay)) => replay.deterministic(),
+            (deterministic, _) => deterministic,
+        };
+        let record = match (self.record, self.replay) {
+            (Some(dir), _) => {
+                let Some(deterministic) = deterministic.clone() else {
+                    bail!("recording requires deterministic execution to be enabled")
+                };
+                Some(record::Mode::Record {
+                    dir,
+                    seeds: deterministic.rng(),
+                    deterministic,
+                })
+            }
+            (None, Some(replay)) => Some(record::Mode::Replay(replay)),
+            (None, None) => None,
+        };
+        let profiler = self
+            .profiling
+            .map(|profiling| Profiler::new(&engine, profiling));
         Ok(Runtime {
             engine,
-            handler: self.handler,
+            handler: builtin::HandlerBuilder {
+                record,
+                ..self.handler
+            },
             actor_config: self.actor_config,
             module_config: self.module_config,
+            deterministic,
+            profiler,
         })
     }
 }
@@ -145,6 +282,8 @@ pub struct Runtime {
     pub(crate) handler: builtin::HandlerBuilder,
     pub(crate) actor_config: ActorConfig,
     pub(crate) module_config: ModuleConfig,
+    pub(crate) deterministic: Option<Deterministic>,
+    pub(crate) profiler: Option<Profiler>,
 }
 
 impl Debug for Runtime {
@@ -153,6 +292,8 @@ impl Debug for Runtime {
             .field("handler", &self.handler)
             .field("actor_config", &self.actor_config)
             .field("module_config", &self.module_config)
+            .field("deterministic", &self.deterministic)
+            .field("profiler", &self.profiler)
             .field("runtime", &"wasmtime")
             .finish()
     }
@@ -179,4 +320,27 @@ impl Runtime {
     pub fn version(&self) -> &str {
         env!("CARGO_PKG_VERSION")
     }
+
+    /// [`ActorConfig`] used by default for actors created using this [Runtime]
+    #[must_use]
+    pub fn actor_config(&self) -> &ActorConfig {
+        &self.actor_config
+    }
+
+    /// Enables or disables guest profiling of invocations of actor identified by `actor_id`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if profiling was not enabled using [`RuntimeBuilder::profiling`]
+    pub fn set_actor_profiling(
+        &self,
+        actor_id: impl Into<String>,
+        enable: bool,
+    ) -> anyhow::Result<()> {
+        let Some(profiler) = &self.profiler else {
+            bail!("guest profiling is not enabled");
+        };
+        profiler.set_actor(actor_id.into(), enable);
+        Ok(())
+    }
 }
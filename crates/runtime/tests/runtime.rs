@@ -1,9 +1,13 @@
+use core::future::Future;
+use core::pin::Pin;
+
 use std::collections::HashMap;
+use std::io::Cursor;
 use std::net::{Ipv6Addr, SocketAddr};
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use anyhow::{bail, ensure, Context};
 use async_trait::async_trait;
@@ -12,16 +16,29 @@ use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde_json::json;
 use tokio::fs;
-use tokio::io::{stderr, AsyncReadExt};
+use tokio::io::{
+    duplex, empty, repeat, sink, stderr, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::spawn;
+use tokio::sync::Barrier;
+use tokio::time::timeout;
 use tracing_subscriber::prelude::*;
 use wasmcloud_actor::{HttpRequest, HttpResponse, Uuid};
+use wasmcloud_runtime::actor::{Kind, Preopen};
 use wasmcloud_runtime::capability;
+use wasmcloud_runtime::capability::blobstore;
 use wasmcloud_runtime::capability::logging::logging;
 use wasmcloud_runtime::capability::messaging;
-use wasmcloud_runtime::capability::provider::MemoryKeyValue;
-use wasmcloud_runtime::{Actor, Runtime};
+use wasmcloud_runtime::capability::provider::{FileKeyValue, MemoryKeyValue};
+use wasmcloud_runtime::capability::{
+    compat, BlobstoreHandler, IncomingHttp, KeyValueAtomic, KeyValueBatch, KeyValueReadWrite,
+    MessagingHandler,
+};
+use wasmcloud_runtime::record::Divergence;
+use wasmcloud_runtime::{
+    Actor, ActorConfig, Deterministic, Profiling, Recording, Replay, Runtime, VirtualClock,
+};
 
 static LOGGER: Lazy<()> = Lazy::new(|| {
     tracing_subscriber::registry()
@@ -114,23 +131,182 @@ impl capability::Messaging for Messaging {
     }
 }
 
+/// [`capability::Bus`] echoing requests back, which only completes calls once all of them are in flight
+struct FanOutBus(Arc<Barrier>);
+
+#[async_trait]
+impl capability::Bus for FanOutBus {
+    async fn call(
+        &self,
+        operation: String,
+    ) -> anyhow::Result<(
+        Pin<Box<dyn Future<Output = Result<(), String>> + Send>>,
+        Box<dyn AsyncWrite + Sync + Send + Unpin>,
+        Box<dyn AsyncRead + Sync + Send + Unpin>,
+    )> {
+        let (req_w, mut req_r) = duplex(1024);
+        let (mut res_w, res_r) = duplex(1024);
+        let barrier = Arc::clone(&self.0);
+        Ok((
+            Box::pin(async move {
+                let mut request = String::new();
+                req_r
+                    .read_to_string(&mut request)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                barrier.wait().await;
+                if operation == "test:fail/echo.Echo" {
+                    return Err("failure".into());
+                }
+                res_w
+                    .write_all(format!("{operation}: {request}").as_bytes())
+                    .await
+                    .map_err(|e| e.to_string())
+            }),
+            Box::new(req_w),
+            Box::new(res_r),
+        ))
+    }
+}
+
+/// In-memory [`capability::Blobstore`], which records object removals and stream requests
+#[derive(Default)]
+struct MemoryBlobstore {
+    containers: Mutex<HashMap<String, HashMap<String, blobstore::types::ObjectInfo>>>,
+    removed: Mutex<Vec<String>>,
+    streamed: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait]
+impl capability::Blobstore for MemoryBlobstore {
+    async fn container_exists(&self, container: &str) -> anyhow::Result<bool> {
+        Ok(self.containers.lock().await.contains_key(container))
+    }
+
+    async fn create_container(&self, container: &str) -> anyhow::Result<()> {
+        self.containers
+            .lock()
+            .await
+            .insert(container.into(), HashMap::default());
+        Ok(())
+    }
+
+    async fn remove_container(&self, container: &str) -> anyhow::Result<()> {
+        self.containers.lock().await.remove(container);
+        Ok(())
+    }
+
+    async fn get_container_info(
+        &self,
+        container: &str,
+    ) -> anyhow::Result<Option<blobstore::types::ContainerInfo>> {
+        let exists = self.containers.lock().await.contains_key(container);
+        Ok(exists.then(|| blobstore::types::ContainerInfo {
+            container_id: container.into(),
+            created_at: 0,
+        }))
+    }
+
+    async fn get_object_info(
+        &self,
+        container: &str,
+        object: String,
+    ) -> anyhow::Result<Option<blobstore::types::ObjectInfo>> {
+        let containers = self.containers.lock().await;
+        let objects = containers.get(container).context("container not found")?;
+        Ok(objects.get(&object).cloned())
+    }
+
+    async fn remove_object(&self, container: &str, object: String) -> anyhow::Result<bool> {
+        let mut containers = self.containers.lock().await;
+        let objects = containers
+            .get_mut(container)
+            .context("container not found")?;
+        let removed = objects.remove(&object).is_some();
+        self.removed.lock().await.push(object);
+        Ok(removed)
+    }
+
+    async fn put_object(
+        &self,
+        container: &str,
+        object: String,
+        mut value: Box<dyn AsyncRead + Sync + Send + Unpin>,
+        content_type: Option<String>,
+        content_encoding: Option<String>,
+    ) -> anyhow::Result<()> {
+        let mut buf = vec![];
+        value
+            .read_to_end(&mut buf)
+            .await
+            .context("failed to read value")?;
+        let mut containers = self.containers.lock().await;
+        let objects = containers
+            .get_mut(container)
+            .context("container not found")?;
+        objects.insert(
+            object.clone(),
+            blobstore::types::ObjectInfo {
+                container_id: container.into(),
+                object_id: object,
+                content_length: buf.len().try_into()?,
+                last_modified: 0,
+                content_type,
+                content_encoding,
+            },
+        );
+        Ok(())
+    }
+
+    async fn stream_object(&self, container: &str, object: String) -> anyhow::Result<()> {
+        self.streamed.lock().await.push((container.into(), object));
+        Ok(())
+    }
+}
+
 fn new_runtime(
     logs: Arc<Mutex<Vec<(logging::Level, String, String)>>>,
     published: Arc<Mutex<Vec<messaging::types::BrokerMessage>>>,
     keyvalue_readwrite: Arc<MemoryKeyValue>,
+    deterministic: Option<Deterministic>,
 ) -> Runtime {
-    Runtime::builder()
+    let rt = Runtime::builder()
         .logging(Arc::new(Logging(logs)))
         .messaging(Arc::new(Messaging(published)))
-        .keyvalue_readwrite(Arc::clone(&keyvalue_readwrite))
-        .build()
-        .expect("failed to construct runtime")
+        .keyvalue_atomic(Arc::clone(&keyvalue_readwrite))
+        .keyvalue_batch(Arc::clone(&keyvalue_readwrite))
+        .keyvalue_readwrite(Arc::clone(&keyvalue_readwrite));
+    let rt = if let Some(deterministic) = deterministic {
+        rt.deterministic(deterministic)
+    } else {
+        rt
+    };
+    rt.build().expect("failed to construct runtime")
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Response {
+    get_random_bytes: [u8; 8],
+    get_random_u64: u64,
+    guid: String,
+    random_in_range: u32,
+    random_32: u32,
 }
 
 async fn run(
     wasm: impl AsRef<Path>,
     interfaces: bool,
 ) -> anyhow::Result<Vec<(logging::Level, String, String)>> {
+    let (logs, _) = run_deterministic(wasm, interfaces, None).await?;
+    Ok(logs)
+}
+
+async fn run_deterministic(
+    wasm: impl AsRef<Path>,
+    interfaces: bool,
+    deterministic: Option<Deterministic>,
+) -> anyhow::Result<(Vec<(logging::Level, String, String)>, Response)> {
     let wasm = fs::read(wasm).await.context("failed to read Wasm")?;
 
     let socket = TcpListener::bind(SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)))
@@ -162,6 +338,7 @@ async fn run(
             Arc::clone(&logs),
             Arc::clone(&published),
             Arc::clone(&keyvalue_readwrite),
+            deterministic,
         );
         let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
         actor.claims().expect("claims missing");
@@ -228,32 +405,16 @@ async fn run(
         );
     }
 
-    #[derive(Deserialize)]
-    #[serde(deny_unknown_fields)]
     // NOTE: If values are truly random, we have nothing to assert for some of these fields
-    struct Response {
-        #[allow(dead_code)]
-        get_random_bytes: [u8; 8],
-        #[allow(dead_code)]
-        get_random_u64: u64,
-        guid: String,
-        random_in_range: u32,
-        #[allow(dead_code)]
-        random_32: u32,
-    }
-    let Response {
-        get_random_bytes: _,
-        get_random_u64: _,
-        guid,
-        random_32: _,
-        random_in_range,
-    } = serde_json::from_slice(&body).context("failed to decode body as JSON")?;
-    ensure!(Uuid::from_str(&guid).is_ok());
+    let response: Response =
+        serde_json::from_slice(&body).context("failed to decode body as JSON")?;
+    ensure!(Uuid::from_str(&response.guid).is_ok());
     ensure!(
-        (42..=4242).contains(&random_in_range),
-        "{random_in_range} should have been within range from 42 to 4242 inclusive"
+        (42..=4242).contains(&response.random_in_range),
+        "{} should have been within range from 42 to 4242 inclusive",
+        response.random_in_range
     );
-    Ok(Arc::try_unwrap(logs).unwrap().into_inner())
+    Ok((Arc::try_unwrap(logs).unwrap().into_inner(), response))
 }
 
 #[tokio::test]
@@ -319,11 +480,59 @@ async fn builtins_module() -> anyhow::Result<()> {
             (logging::Level::Info, "".into(), "info".into()),
             (logging::Level::Warn, "".into(), "warn".into()),
             (logging::Level::Error, "".into(), "error".into()),
+            (logging::Level::Info, "".into(), "streamed".into()),
         ]
     );
     Ok(())
 }
 
+#[tokio::test]
+async fn module_streaming() -> anyhow::Result<()> {
+    init();
+
+    const PART: usize = 256 * 1024;
+
+    let rt = Runtime::builder().build()?;
+    let wasm = fs::read(test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+
+    let (mut request_w, request_r) = duplex(64 * 1024);
+    let (response_w, mut response_r) = duplex(PART);
+    let call = spawn(async move { actor.call("Pipe.Stream", request_r, response_w).await });
+
+    // the response to the first part of the request must be received before the request is
+    // finished, which would never happen if either the host or the actor buffered the request
+    let mut response = vec![0; PART];
+    for (i, b) in [0x00, 0x42].into_iter().enumerate() {
+        request_w
+            .write_all(&vec![b; PART])
+            .await
+            .context("failed to write request")?;
+        timeout(
+            Duration::from_secs(10),
+            response_r.read_exact(&mut response),
+        )
+        .await
+        .with_context(|| format!("response to request part {i} was not streamed"))?
+        .context("failed to read response")?;
+        assert!(response.iter().all(|r| *r == b + 1));
+    }
+    drop(request_w);
+    call.await
+        .context("failed to join call task")?
+        .context("failed to call `Pipe.Stream`")?
+        .expect("`Pipe.Stream` must not fail");
+    let mut rest = vec![];
+    response_r
+        .read_to_end(&mut rest)
+        .await
+        .context("failed to read response")?;
+    assert!(rest.is_empty());
+    Ok(())
+}
+
 #[tokio::test]
 async fn builtins_compat() -> anyhow::Result<()> {
     init();
@@ -435,3 +644,883 @@ async fn builtins_component() -> anyhow::Result<()> {
     );
     Ok(())
 }
+
+#[tokio::test]
+async fn builtins_http_compat() -> anyhow::Result<()> {
+    init();
+
+    for path in [
+        test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED,
+        test_actors::RUST_BUILTINS_COMPAT_REACTOR_PREVIEW2_SIGNED,
+    ] {
+        let rt = new_runtime(
+            Arc::new(vec![].into()),
+            Arc::new(vec![].into()),
+            Arc::new(MemoryKeyValue::from(HashMap::default())),
+            None,
+        );
+        let wasm = fs::read(path).await.context("failed to read Wasm")?;
+        let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+
+        // encoded requests are passed to actors not exporting `wasi:http/incoming-handler` as-is
+        let response = actor
+            .as_incoming_http()
+            .await?
+            .handle_compat(REQUEST.clone())
+            .await?;
+        let HttpResponse {
+            status_code, body, ..
+        } = rmp_serde::from_slice(&response).context("failed to decode response")?;
+        assert_eq!(status_code, 200);
+        let Response {
+            random_in_range, ..
+        } = serde_json::from_slice(&body).context("failed to decode body as JSON")?;
+        assert!((42..=4242).contains(&random_in_range));
+
+        let body = serde_json::to_vec(&json!({
+            "min": 1,
+            "max": 1,
+        }))?;
+        let request = http::Request::builder()
+            .method("POST")
+            .uri("/")
+            .body(Box::new(Cursor::new(body)) as Box<dyn AsyncRead + Sync + Send + Unpin>)?;
+        let response = actor.as_incoming_http().await?.handle(request).await?;
+        assert_eq!(response.status(), 200);
+        let mut body = vec![];
+        response.into_body().read_to_end(&mut body).await?;
+        let Response {
+            random_in_range, ..
+        } = serde_json::from_slice(&body).context("failed to decode body as JSON")?;
+        assert_eq!(random_in_range, 1);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn http_component() -> anyhow::Result<()> {
+    init();
+
+    let rt = Runtime::builder().build()?;
+    let wasm = fs::read(test_actors::RUST_HTTP_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+
+    let request = http::Request::builder()
+        .method("POST")
+        .uri("/foo?bar=baz")
+        .header("x-test", "a")
+        .header("x-test", "b")
+        .body(
+            Box::new(Cursor::new(b"hello".to_vec())) as Box<dyn AsyncRead + Sync + Send + Unpin>
+        )?;
+    let response = actor.as_incoming_http().await?.handle(request).await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "text/plain");
+    assert_eq!(
+        response
+            .headers()
+            .get_all("x-test")
+            .iter()
+            .collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).await?;
+    assert_eq!(body, "POST /foo?bar=baz\nhello");
+
+    // encoded requests are translated for actors exporting `wasi:http/incoming-handler`
+    let request = rmp_serde::to_vec(&HttpRequest {
+        method: "PUT".into(),
+        path: "/compat".into(),
+        query_string: "x=1".into(),
+        header: HashMap::from([("x-test".into(), vec!["c".into()])]),
+        body: b"world".to_vec(),
+    })?;
+    let response = actor
+        .as_incoming_http()
+        .await?
+        .handle_compat(request)
+        .await?;
+    let HttpResponse {
+        status_code,
+        header,
+        body,
+    } = rmp_serde::from_slice(&response).context("failed to decode response")?;
+    assert_eq!(status_code, 200);
+    assert_eq!(header.get("x-test"), Some(&vec!["c".to_string()]));
+    assert_eq!(body, b"PUT /compat?x=1\nworld");
+    Ok(())
+}
+
+#[tokio::test]
+async fn builtins_deterministic() -> anyhow::Result<()> {
+    init();
+
+    let deterministic = Deterministic {
+        seed: 42,
+        clock: VirtualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000)),
+    };
+    for (wasm, interfaces) in [
+        (test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED, false),
+        (
+            test_actors::RUST_BUILTINS_COMPAT_REACTOR_PREVIEW2_SIGNED,
+            false,
+        ),
+        (
+            test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED,
+            true,
+        ),
+    ] {
+        let (_, first) = run_deterministic(wasm, interfaces, Some(deterministic.clone())).await?;
+        let (_, second) = run_deterministic(wasm, interfaces, Some(deterministic.clone())).await?;
+        assert_eq!(first, second, "`{wasm}` responses must be reproducible");
+
+        let (_, other) = run_deterministic(
+            wasm,
+            interfaces,
+            Some(Deterministic {
+                seed: 43,
+                ..deterministic.clone()
+            }),
+        )
+        .await?;
+        assert_ne!(first, other, "`{wasm}` responses must depend on the seed");
+    }
+
+    // Module actors get all randomness from the host-side generator, so the values are fixed by the seed
+    let (_, response) = run_deterministic(
+        test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED,
+        false,
+        Some(deterministic),
+    )
+    .await?;
+    assert_eq!(
+        response,
+        Response {
+            get_random_bytes: [162, 36, 39, 34, 99, 119, 204, 134],
+            get_random_u64: 13_729_986_510_259_751_443,
+            guid: "d13451de-7160-4fa2-b230-76fd782de967".into(),
+            random_in_range: 186,
+            random_32: 4_161_904_618,
+        }
+    );
+
+    let clock = VirtualClock::default();
+    assert_eq!(clock.wall(), Duration::ZERO);
+    assert_eq!(clock.monotonic(), 0);
+    clock.clone().advance(Duration::from_millis(1500));
+    assert_eq!(clock.wall(), Duration::from_millis(1500));
+    assert_eq!(clock.monotonic(), 1_500_000_000);
+    Ok(())
+}
+
+#[tokio::test]
+async fn builtins_record_replay() -> anyhow::Result<()> {
+    init();
+
+    let dir = std::env::temp_dir().join(format!("wasmcloud-runtime-record-{}", std::process::id()));
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let keyvalue_readwrite = Arc::new(MemoryKeyValue::from(HashMap::from([(
+        "".into(),
+        HashMap::from([("foo".into(), b"bar".to_vec())]),
+    )])));
+    let rt = Runtime::builder()
+        .logging(Arc::new(Logging(Arc::default())))
+        .messaging(Arc::new(Messaging(Arc::default())))
+        .keyvalue_atomic(Arc::clone(&keyvalue_readwrite))
+        .keyvalue_batch(Arc::clone(&keyvalue_readwrite))
+        .keyvalue_readwrite(Arc::clone(&keyvalue_readwrite))
+        .deterministic(Deterministic {
+            seed: 42,
+            clock: VirtualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000)),
+        })
+        .record(&dir)
+        .build()
+        .context("failed to construct runtime")?;
+    let actor = Actor::new(&rt, &wasm).context("failed to construct actor")?;
+    // NOTE: The instance is reused, so that the second invocation starts with a used random number generator
+    let mut instance = actor.instantiate().await.context("failed to instantiate")?;
+    for _ in 0..2 {
+        // NOTE: The actor deletes `foo`
+        keyvalue_readwrite
+            .set("", "foo".into(), Box::new(Cursor::new(b"bar")))
+            .await?;
+        instance
+            .call("HttpServer.HandleRequest", REQUEST.as_slice(), sink())
+            .await
+            .context("failed to call `HttpServer.HandleRequest`")?
+            .expect("`HttpServer.HandleRequest` must not fail");
+    }
+
+    let mut entries = fs::read_dir(&dir)
+        .await
+        .context("failed to read recording directory")?;
+    let mut paths = vec![];
+    while let Some(entry) = entries.next_entry().await? {
+        paths.push(entry.path());
+    }
+    // NOTE: Recordings are named after the time they were made at
+    paths.sort();
+    let mut recordings = vec![];
+    for path in paths {
+        recordings.push(Recording::read(path).await?);
+    }
+    fs::remove_dir_all(&dir).await?;
+    let (first, recording) = match recordings.as_slice() {
+        [a, b] => (a.clone(), b.clone()),
+        _ => bail!("expected exactly two recordings"),
+    };
+    ensure!(first.invocation_seed.is_some());
+    ensure!(recording.invocation_seed.is_some());
+    ensure!(first.invocation_seed != recording.invocation_seed);
+    assert_eq!(recording.operation, "HttpServer.HandleRequest");
+    assert_eq!(recording.request, REQUEST.as_slice());
+    assert_eq!(recording.result, Ok(Ok(())));
+    assert_eq!(recording.seed, Some(42));
+    assert!(!recording.response.is_empty());
+    let functions: Vec<_> = recording
+        .calls
+        .iter()
+        .map(|call| call.function.as_str())
+        .collect();
+    assert!(functions.contains(&"wasi:logging/logging.log"));
+    assert!(functions.contains(&"wasi:keyvalue/readwrite.get"));
+    assert!(functions.contains(&"wasi:keyvalue/batch.get-keys"));
+    assert!(functions.contains(&"wasi:keyvalue/atomic.compare-and-swap"));
+    assert!(functions.contains(&"wasmcloud:messaging/consumer.publish"));
+
+    // NOTE: No host call handlers are configured, so all host calls must be served from the recording.
+    // The second invocation is replayed on a new instance, so its randomness must be reproduced from the
+    // invocation seed
+    let replay = Replay::new(recording.clone());
+    let rt = Runtime::builder()
+        .replay(replay.clone())
+        .build()
+        .context("failed to construct runtime")?;
+    let actor = Actor::new(&rt, &wasm).context("failed to construct actor")?;
+    actor
+        .call(
+            &recording.operation,
+            Cursor::new(recording.request.clone()),
+            sink(),
+        )
+        .await
+        .context("failed to replay `HttpServer.HandleRequest`")?
+        .expect("`HttpServer.HandleRequest` must not fail on replay");
+    assert_eq!(replay.divergences(), []);
+
+    let request = rmp_serde::to_vec(&HttpRequest {
+        method: "POST".into(),
+        path: "/".into(),
+        body: br#"{"min":1,"max":2}"#.to_vec(),
+        ..Default::default()
+    })
+    .expect("failed to serialize request");
+    _ = actor
+        .call(&recording.operation, Cursor::new(request.clone()), sink())
+        .await;
+    assert!(matches!(
+        replay.divergences().as_slice(),
+        [Divergence::Invocation { request: actual, .. }, ..] if *actual == request
+    ));
+
+    ensure!(
+        Runtime::builder().record(&dir).build().is_err(),
+        "recording without deterministic execution must fail"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn builtins_profiling() -> anyhow::Result<()> {
+    init();
+
+    let dir =
+        std::env::temp_dir().join(format!("wasmcloud-runtime-profile-{}", std::process::id()));
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let keyvalue_readwrite = Arc::new(MemoryKeyValue::from(HashMap::from([(
+        "".into(),
+        HashMap::from([("foo".into(), b"bar".to_vec())]),
+    )])));
+    let rt = Runtime::builder()
+        .logging(Arc::new(Logging(Arc::default())))
+        .messaging(Arc::new(Messaging(Arc::default())))
+        .keyvalue_atomic(Arc::clone(&keyvalue_readwrite))
+        .keyvalue_batch(Arc::clone(&keyvalue_readwrite))
+        .keyvalue_readwrite(keyvalue_readwrite)
+        .profiling(Profiling::new(&dir))
+        .build()
+        .context("failed to construct runtime")?;
+    let actor = Actor::new(&rt, &wasm).context("failed to construct actor")?;
+    let actor_id = actor.claims().context("claims missing")?.subject.clone();
+
+    // NOTE: Profiling is not enabled for the actor, so no profile must be written
+    actor
+        .call("HttpServer.HandleRequest", REQUEST.as_slice(), sink())
+        .await
+        .context("failed to call `HttpServer.HandleRequest`")?
+        .expect("`HttpServer.HandleRequest` must not fail");
+    ensure!(
+        !dir.exists(),
+        "profile written for actor with profiling disabled"
+    );
+
+    rt.set_actor_profiling(&actor_id, true)?;
+    actor
+        .call("HttpServer.HandleRequest", REQUEST.as_slice(), sink())
+        .await
+        .context("failed to call `HttpServer.HandleRequest`")?
+        .expect("`HttpServer.HandleRequest` must not fail");
+    let mut entries = fs::read_dir(&dir)
+        .await
+        .context("failed to read profile directory")?;
+    let entry = entries.next_entry().await?.context("profile missing")?;
+    ensure!(entries.next_entry().await?.is_none(), "too many profiles");
+    let name = entry.file_name();
+    let profile = fs::read(entry.path()).await?;
+    fs::remove_dir_all(&dir).await?;
+    assert!(name.to_string_lossy().starts_with(&actor_id));
+    let profile: serde_json::Value =
+        serde_json::from_slice(&profile).context("failed to decode profile")?;
+    assert_eq!(profile["meta"]["product"], "wasmCloud");
+    assert_eq!(profile["threads"][0]["name"], "HttpServer.HandleRequest");
+
+    assert!(Runtime::new()?.set_actor_profiling(actor_id, true).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn bus_fanout_component() -> anyhow::Result<()> {
+    init();
+
+    let operations = [
+        "test:a/echo.Echo",
+        "test:b/echo.Echo",
+        "test:fail/echo.Echo",
+    ];
+    let barrier = Arc::new(Barrier::new(operations.len()));
+    let rt = Runtime::builder()
+        .bus(Arc::new(FanOutBus(barrier)))
+        .build()?;
+    let wasm = fs::read(test_actors::RUST_BUS_FANOUT_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+    let request = serde_json::to_vec(&json!({
+        "operations": operations,
+        "body": "ping",
+    }))?;
+    let (response_w, mut response_r) = duplex(4096);
+    // NOTE: Calls made one after another would never complete, since the bus only completes them
+    // once all of them are in flight
+    timeout(
+        Duration::from_secs(10),
+        actor.call("FanOut.Call", Cursor::new(request), response_w),
+    )
+    .await
+    .context("bus calls were not made concurrently")?
+    .context("failed to call `FanOut.Call`")?
+    .expect("`FanOut.Call` must not fail");
+
+    let mut response = vec![];
+    response_r
+        .read_to_end(&mut response)
+        .await
+        .context("failed to read response")?;
+    let response: serde_json::Value =
+        serde_json::from_slice(&response).context("failed to decode response as JSON")?;
+    assert_eq!(
+        response,
+        json!([
+            {
+                "operation": "test:a/echo.Echo",
+                "result": { "Ok": "test:a/echo.Echo: ping" },
+            },
+            {
+                "operation": "test:b/echo.Echo",
+                "result": { "Ok": "test:b/echo.Echo: ping" },
+            },
+            {
+                "operation": "test:fail/echo.Echo",
+                "result": { "Err": "failure" },
+            },
+        ])
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn bus_streams_component() -> anyhow::Result<()> {
+    init();
+
+    // NOTE: `call` returns `(future-result, input-stream, output-stream)`, the guest must be able
+    // to write the request to the callee's stdin via the output stream and read the callee's stdout
+    // via the input stream
+    let rt = Runtime::builder()
+        .bus(Arc::new(FanOutBus(Arc::new(Barrier::new(1)))))
+        .build()?;
+    let wasm = fs::read(test_actors::RUST_BUS_FANOUT_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+    let request = serde_json::to_vec(&json!({
+        "operations": ["test:streams/echo.Echo"],
+        "body": "request",
+    }))?;
+    let (response_w, mut response_r) = duplex(4096);
+    actor
+        .call("FanOut.Call", Cursor::new(request), response_w)
+        .await
+        .context("failed to call `FanOut.Call`")?
+        .expect("`FanOut.Call` must not fail");
+    let mut response = vec![];
+    response_r
+        .read_to_end(&mut response)
+        .await
+        .context("failed to read response")?;
+    let response: serde_json::Value =
+        serde_json::from_slice(&response).context("failed to decode response as JSON")?;
+    assert_eq!(
+        response,
+        json!([
+            {
+                "operation": "test:streams/echo.Echo",
+                "result": { "Ok": "test:streams/echo.Echo: request" },
+            },
+        ])
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn blobstore_component() -> anyhow::Result<()> {
+    init();
+
+    let logs = Arc::new(vec![].into());
+    let blobstore = Arc::new(MemoryBlobstore::default());
+    let rt = Runtime::builder()
+        .logging(Arc::new(Logging(Arc::clone(&logs))))
+        .blobstore(Arc::clone(&blobstore))
+        .build()?;
+    let wasm = fs::read(test_actors::RUST_BLOBSTORE_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+    actor
+        .call("Blobstore.Test", b"".as_slice(), sink())
+        .await
+        .context("failed to call `Blobstore.Test`")?
+        .expect("`Blobstore.Test` must not fail");
+
+    let objects = blobstore.containers.lock().await.remove("container");
+    let mut objects = objects.context("container missing")?.into_iter();
+    match (objects.next(), objects.next()) {
+        (Some((object, info)), None) => {
+            assert_eq!(object, "object");
+            assert_eq!(info.content_length, 9);
+            assert_eq!(info.content_type.as_deref(), Some("text/plain"));
+        }
+        _ => bail!("expected exactly one object to be stored"),
+    }
+    assert_eq!(
+        *blobstore.streamed.lock().await,
+        [("container".to_string(), "object".to_string())]
+    );
+    // the unfinished upload is removed asynchronously once the instance is dropped
+    timeout(Duration::from_secs(10), async {
+        while !blobstore
+            .removed
+            .lock()
+            .await
+            .iter()
+            .any(|o| o == "unfinished")
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .context("unfinished upload was not removed")?;
+    let mut removed = blobstore.removed.lock().await.clone();
+    removed.sort();
+    assert_eq!(removed, ["cancelled", "invalid", "unfinished"]);
+
+    // chunks streamed by the blobstore are delivered via `wasmcloud:blobstore/handler`
+    let handler = actor.as_blobstore_handler().await?;
+    let chunk = |bytes: &[u8], offset, is_last| blobstore::types::Chunk {
+        object_id: "object".into(),
+        container_id: "container".into(),
+        bytes: bytes.to_vec(),
+        offset,
+        is_last,
+    };
+    assert!(!handler.receive_chunk(chunk(b"foobar", 0, false)).await?);
+    assert!(handler.receive_chunk(chunk(b"baz", 6, true)).await?);
+    assert!(handler
+        .receive_chunk(chunk(&[0xff], 9, true))
+        .await
+        .is_err());
+    assert_eq!(
+        *logs.lock().await,
+        [
+            (
+                logging::Level::Info,
+                "receive-chunk".into(),
+                "container/object@0: foobar (last: false)".into(),
+            ),
+            (
+                logging::Level::Info,
+                "receive-chunk".into(),
+                "container/object@6: baz (last: true)".into(),
+            ),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn messaging_component() -> anyhow::Result<()> {
+    init();
+
+    let published = Arc::new(vec![].into());
+    let rt = Runtime::builder()
+        .messaging(Arc::new(Messaging(Arc::clone(&published))))
+        .build()?;
+    let wasm = fs::read(test_actors::RUST_MESSAGING_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+
+    let handler = actor.as_messaging_handler().await?;
+    handler
+        .handle_message(messaging::types::BrokerMessage {
+            subject: "test-subject".into(),
+            body: Some("foo".into()),
+            reply_to: Some("test-reply".into()),
+        })
+        .await
+        .context("failed to handle message")?;
+    handler
+        .handle_message(messaging::types::BrokerMessage {
+            subject: "test-subject".into(),
+            body: Some("bar".into()),
+            reply_to: None,
+        })
+        .await
+        .expect_err("message without a reply subject must fail");
+
+    match published.lock().await.as_slice() {
+        [messaging::types::BrokerMessage {
+            subject,
+            body,
+            reply_to,
+        }] => {
+            assert_eq!(subject, "test-reply");
+            assert_eq!(body.as_deref(), Some(b"test-subject: foo".as_slice()));
+            assert_eq!(*reply_to, None);
+        }
+        _ => bail!("expected exactly one message to be published"),
+    }
+
+    // messages delivered by providers are translated for actors exporting `wasmcloud:messaging/handler`
+    handler
+        .handle_message(
+            compat::SubMessage {
+                subject: "test-compat".into(),
+                reply_to: Some("test-reply".into()),
+                body: b"baz".to_vec(),
+            }
+            .into(),
+        )
+        .await
+        .context("failed to handle compat message")?;
+    let msg = published
+        .lock()
+        .await
+        .pop()
+        .context("no message published")?;
+    assert_eq!(msg.body.as_deref(), Some(b"test-compat: baz".as_slice()));
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct PreopenAccess {
+    read: Option<String>,
+    write: bool,
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct WasiConfig {
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    ro: PreopenAccess,
+    rw: PreopenAccess,
+}
+
+#[tokio::test]
+async fn wasi_config() -> anyhow::Result<()> {
+    init();
+
+    for path in [
+        test_actors::RUST_CONFIG_MODULE_COMMAND,
+        test_actors::RUST_CONFIG_MODULE_COMMAND_PREVIEW2,
+    ] {
+        let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+        let ro = dir.path().join("ro");
+        let rw = dir.path().join("rw");
+        for dir in [&ro, &rw] {
+            fs::create_dir(dir).await?;
+            fs::write(dir.join("input.txt"), "input").await?;
+        }
+        let rt = Runtime::builder()
+            .actor_config(ActorConfig {
+                require_signature: false,
+                argv0: Some("config.wasm".into()),
+                env: vec![("FOO".into(), "bar".into())],
+                preopens: vec![
+                    Preopen {
+                        host_path: ro.clone(),
+                        guest_path: "/ro".into(),
+                        read_only: true,
+                    },
+                    Preopen {
+                        host_path: rw.clone(),
+                        guest_path: "/rw".into(),
+                        read_only: false,
+                    },
+                ],
+            })
+            .build()?;
+        let wasm = fs::read(path).await.context("failed to read Wasm")?;
+        let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
+        let mut instance = actor.instantiate().await?;
+
+        // preopened directories are opened once on instantiation and not reopened on invocation
+        let moved = dir.path().join("moved");
+        fs::rename(&ro, &moved).await?;
+
+        let (response_w, mut response_r) = duplex(4096);
+        instance
+            .call("Config.Get", empty(), response_w)
+            .await
+            .context("failed to call `Config.Get`")?
+            .expect("`Config.Get` must not fail");
+        // NOTE: Output stream is only closed once the instance is dropped
+        drop(instance);
+        let mut response = vec![];
+        response_r
+            .read_to_end(&mut response)
+            .await
+            .context("failed to read response")?;
+        let config: WasiConfig =
+            serde_json::from_slice(&response).context("failed to decode response as JSON")?;
+        assert_eq!(
+            config,
+            WasiConfig {
+                args: vec!["config.wasm".into(), "Config.Get".into()],
+                env: HashMap::from([("FOO".into(), "bar".into())]),
+                ro: PreopenAccess {
+                    read: Some("input".into()),
+                    write: false,
+                },
+                rw: PreopenAccess {
+                    read: Some("input".into()),
+                    write: true,
+                },
+            }
+        );
+        assert!(!moved.join("output.txt").exists());
+        assert_eq!(fs::read_to_string(rw.join("output.txt")).await?, "output");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn actor_claims() -> anyhow::Result<()> {
+    init();
+
+    for (path, kind) in [
+        (
+            test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED,
+            Kind::Module,
+        ),
+        (
+            test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED,
+            Kind::Component,
+        ),
+        (
+            test_actors::RUST_BUS_FANOUT_COMPONENT_REACTOR_PREVIEW2_SIGNED,
+            Kind::Component,
+        ),
+    ] {
+        let wasm = fs::read(path).await.context("failed to read Wasm")?;
+        assert_eq!(Kind::detect(&wasm)?, kind);
+
+        let expected = wascap::wasm::extract_claims(&wasm)?
+            .context("claims missing")?
+            .claims;
+        let claims = wasmcloud_runtime::actor::claims(&wasm)?.context("claims missing")?;
+        assert_eq!(claims.subject, expected.subject);
+        assert_eq!(claims.issuer, expected.issuer);
+        assert_eq!(
+            claims.metadata.map(|actor| actor.module_hash),
+            expected.metadata.map(|actor| actor.module_hash)
+        );
+
+        // appending a custom section invalidates the module hash
+        let mut tampered = wasm.clone();
+        tampered.extend([0, 4, 3, b'f', b'o', b'o']);
+        assert!(wasmcloud_runtime::actor::claims(&tampered).is_err());
+    }
+
+    let wasm = fs::read(test_actors::RUST_BUILTINS_MODULE_REACTOR)
+        .await
+        .context("failed to read Wasm")?;
+    assert!(wasmcloud_runtime::actor::claims(&wasm)?.is_none());
+    assert!(Kind::detect(b"\x00asm").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn keyvalue_mem() -> anyhow::Result<()> {
+    init();
+
+    let kv = MemoryKeyValue::from(HashMap::from([(
+        "bucket".into(),
+        HashMap::from([("foo".into(), b"bar".to_vec())]),
+    )]));
+    assert_eq!(kv.increment("bucket", "counter".into(), 2).await?, 2);
+    assert_eq!(kv.increment("bucket", "counter".into(), 40).await?, 42);
+    assert!(kv.increment("bucket", "foo".into(), 1).await.is_err());
+    assert!(
+        !kv.compare_and_swap("bucket", "counter".into(), 0, 1)
+            .await?
+    );
+    assert!(
+        kv.compare_and_swap("bucket", "counter".into(), 42, 1)
+            .await?
+    );
+
+    kv.set_many(
+        "bucket",
+        vec![
+            (
+                "baz".into(),
+                Box::new(b"qux".as_slice()) as Box<dyn AsyncRead + Sync + Send + Unpin>,
+            ),
+            ("binary".into(), Box::new(b"\xff\x00".as_slice())),
+        ],
+    )
+    .await?;
+    let mut keys = kv.get_keys("bucket").await?;
+    keys.sort();
+    assert_eq!(keys, ["baz", "binary", "counter", "foo"]);
+
+    let mut values = vec![];
+    for (mut value, size) in kv
+        .get_many("bucket", vec!["counter".into(), "binary".into()])
+        .await?
+    {
+        let mut buf = vec![];
+        value.read_to_end(&mut buf).await?;
+        assert_eq!(buf.len() as u64, size);
+        values.push(buf);
+    }
+    assert_eq!(values, [b"1".to_vec(), vec![0xff, 0x00]]);
+    assert!(kv
+        .get_many("bucket", vec!["foo".into(), "missing".into()])
+        .await
+        .is_err());
+
+    kv.delete_many("bucket", vec!["foo".into(), "baz".into()])
+        .await?;
+    let mut keys = kv.get_keys("bucket").await?;
+    keys.sort();
+    assert_eq!(keys, ["binary", "counter"]);
+    assert_eq!(kv.get_keys("missing").await?, Vec::<String>::new());
+    Ok(())
+}
+
+#[tokio::test]
+async fn keyvalue_file() -> anyhow::Result<()> {
+    init();
+
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+    let kv = FileKeyValue::open(dir.path())
+        .await?
+        .max_value_size(Some(8))
+        .max_bucket_size(Some(16));
+    assert!(kv.get("bucket", "foo".into()).await.is_err());
+    assert!(kv.exists("bucket", "foo".into()).await.is_err());
+    assert_eq!(kv.get_keys("bucket").await?, Vec::<String>::new());
+
+    kv.set("bucket", "foo".into(), Box::new(b"bar".as_slice()))
+        .await?;
+    assert!(kv.exists("bucket", "foo".into()).await?);
+    assert!(!kv.exists("bucket", "missing".into()).await?);
+    assert_eq!(kv.increment("bucket", "counter".into(), 42).await?, 42);
+    assert!(kv.increment("bucket", "foo".into(), 1).await.is_err());
+    assert!(
+        kv.compare_and_swap("bucket", "counter".into(), 42, 1)
+            .await?
+    );
+    assert!(kv.delete("bucket", "missing".into()).await.is_err());
+
+    // size limits are enforced and rejected writes leave the store unchanged
+    assert!(kv
+        .set("bucket", "big".into(), Box::new(Cursor::new(vec![0; 9])))
+        .await
+        .is_err());
+    // values are not read past the limit, so endless streams are rejected
+    let err = timeout(
+        Duration::from_secs(10),
+        kv.set("bucket", "endless".into(), Box::new(repeat(0))),
+    )
+    .await
+    .context("reading endless value did not stop at the limit")?
+    .expect_err("endless value must exceed the limit");
+    assert!(format!("{err:#}").contains("exceeds limit"));
+    assert!(kv
+        .set_many("bucket", vec![("endless".into(), Box::new(repeat(0)) as _)])
+        .await
+        .is_err());
+    kv.set("bucket", "a".into(), Box::new(Cursor::new(vec![0; 8])))
+        .await?;
+    assert!(kv
+        .set("bucket", "b".into(), Box::new(Cursor::new(vec![0; 8])))
+        .await
+        .is_err());
+    // overwriting a value only counts the difference in size
+    kv.set("bucket", "a".into(), Box::new(Cursor::new(vec![1; 8])))
+        .await?;
+    kv.delete_many("bucket", vec!["a".into()]).await?;
+    kv.set("bucket", "b".into(), Box::new(Cursor::new(vec![0; 8])))
+        .await?;
+
+    // values written before are available after reopening the store
+    let kv = FileKeyValue::open(dir.path()).await?;
+    let mut keys = kv.get_keys("bucket").await?;
+    keys.sort();
+    assert_eq!(keys, ["b", "counter", "foo"]);
+    let mut values = vec![];
+    for (mut value, size) in kv
+        .get_many("bucket", vec!["foo".into(), "counter".into()])
+        .await?
+    {
+        let mut buf = vec![];
+        value.read_to_end(&mut buf).await?;
+        assert_eq!(buf.len() as u64, size);
+        values.push(buf);
+    }
+    assert_eq!(values, [b"bar".to_vec(), b"1".to_vec()]);
+    kv.delete("bucket", "foo".into()).await?;
+    assert!(!kv.exists("bucket", "foo".into()).await?);
+    Ok(())
+}
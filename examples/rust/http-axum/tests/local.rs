@@ -46,13 +46,23 @@ async fn local() -> anyhow::Result<()> {
     ))
     .expect("failed to parse Wasm path");
     let _host = Host::new(HostConfig {
-        actors: HashMap::from([("server".into(), ActorConfig { url })]),
+        actors: HashMap::from([(
+            "server".into(),
+            ActorConfig {
+                url,
+                argv0: None,
+                env: HashMap::default(),
+                preopens: vec![],
+                sha256: None,
+            },
+        )]),
         links: vec![LinkConfig::Tcp {
             socket: TcpSocketConfig {
                 addr: format!("[::]:{port}"),
             },
             chain: vec!["server".into()],
         }],
+        ..Default::default()
     })
     .await
     .context("failed to initialize host")?;
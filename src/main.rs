@@ -4,20 +4,24 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::{self, Context};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use tokio::{select, signal};
 use tracing::Level as LogLevel;
 use tracing_subscriber::prelude::*;
 use wasmcloud_host::oci::Config as OciConfig;
 use wasmcloud_host::url::Url;
-use wasmcloud_host::WasmbusHostConfig;
+use wasmcloud_host::wasmbus::config::{EventHistory, NativeProfiler};
+use wasmcloud_host::{LocalHost, LocalHostConfig, LocalHostOverrides, WasmbusHostConfig};
 
 #[derive(Debug, Parser)]
 #[allow(clippy::struct_excessive_bools)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Controls the verbosity of logs from the wasmCloud host
-    #[clap(long = "log-level", alias = "structured-log-level", default_value_t = LogLevel::INFO, env = "WASMCLOUD_LOG_LEVEL")]
+    #[clap(long = "log-level", alias = "structured-log-level", default_value_t = LogLevel::INFO, env = "WASMCLOUD_LOG_LEVEL", global = true)]
     pub log_level: LogLevel,
     /// NATS server host to connect to
     #[clap(long = "nats-host", default_value = "127.0.0.1", env = "NATS_HOST")]
@@ -82,6 +86,39 @@ struct Args {
     /// Enables IPV6 addressing for wasmCloud hosts
     #[clap(long = "enable-ipv6", env = "WASMCLOUD_ENABLE_IPV6", hide = true)]
     enable_ipv6: bool,
+    /// Publish actor logs on `wasmbus.log.{lattice-prefix}.{actor-id}` NATS subjects
+    #[clap(long = "publish-actor-logs", env = "WASMCLOUD_PUBLISH_ACTOR_LOGS")]
+    publish_actor_logs: bool,
+    /// Directory, to which guest profiles of actor invocations are written in Firefox profiler format. Guest profiling is disabled if not set
+    #[clap(long = "profile-dir", env = "WASMCLOUD_PROFILE_DIR")]
+    profile_dir: Option<PathBuf>,
+    /// Interval, in milliseconds, at which profiled actor invocations are sampled
+    #[clap(long = "profile-interval-ms", env = "WASMCLOUD_PROFILE_INTERVAL_MS", value_parser = parse_duration)]
+    profile_interval: Option<Duration>,
+    /// Profile invocations of all actors, rather than only the ones enabled via the control interface
+    #[clap(long = "profile-all-actors", env = "WASMCLOUD_PROFILE_ALL_ACTORS")]
+    profile_all_actors: bool,
+    /// Native profiler integration to enable, one of `perfmap`, `jitdump` or `vtune`
+    #[clap(long = "native-profiler", env = "WASMCLOUD_NATIVE_PROFILER", value_parser = parse_native_profiler)]
+    native_profiler: Option<NativeProfiler>,
+    /// Capture lattice events in a JetStream stream, which can be replayed via the control interface
+    #[clap(long = "event-history", env = "WASMCLOUD_EVENT_HISTORY")]
+    event_history: bool,
+    /// Maximum age, in milliseconds, of events retained in the event history. Unbounded if not set
+    #[clap(long = "event-history-max-age-ms", env = "WASMCLOUD_EVENT_HISTORY_MAX_AGE_MS", value_parser = parse_duration)]
+    event_history_max_age: Option<Duration>,
+    /// Maximum number of events retained in the event history. Unbounded if not set
+    #[clap(
+        long = "event-history-max-messages",
+        env = "WASMCLOUD_EVENT_HISTORY_MAX_MESSAGES"
+    )]
+    event_history_max_messages: Option<i64>,
+    /// Maximum total size, in bytes, of events retained in the event history. Unbounded if not set
+    #[clap(
+        long = "event-history-max-bytes",
+        env = "WASMCLOUD_EVENT_HISTORY_MAX_BYTES"
+    )]
+    event_history_max_bytes: Option<i64>,
     /// Enable JSON structured logging from the wasmCloud host
     #[clap(
         long = "enable-structured-logging",
@@ -219,9 +256,31 @@ struct Args {
     oci_password: Option<String>,
 }
 
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run a local host, which does not connect to a lattice, using a declarative configuration file
+    Local {
+        /// Path to the local host configuration file in TOML, YAML or JSON format. `${NAME}` references within string values are replaced by values of environment variables
+        #[clap(
+            short = 'c',
+            long = "config",
+            default_value = "wasmcloud.toml",
+            env = "WASMCLOUD_LOCAL_CONFIG"
+        )]
+        config: PathBuf,
+        /// Only use previously cached remote actors, without accessing the network
+        #[clap(long = "offline", env = "WASMCLOUD_OFFLINE")]
+        offline: bool,
+        /// Watch the configuration file and local actor files, reloading the host when they are modified
+        #[clap(long = "watch", env = "WASMCLOUD_WATCH")]
+        watch: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let Args {
+        command,
         log_level,
         nats_host,
         nats_port,
@@ -236,6 +295,15 @@ async fn main() -> anyhow::Result<()> {
         oci_user,
         oci_password,
         js_domain,
+        publish_actor_logs,
+        profile_dir,
+        profile_interval,
+        profile_all_actors,
+        native_profiler,
+        event_history,
+        event_history_max_age,
+        event_history_max_messages,
+        event_history_max_bytes,
         ..
     } = Args::parse();
 
@@ -248,6 +316,27 @@ async fn main() -> anyhow::Result<()> {
         )
         .init();
 
+    if let Some(Command::Local {
+        config: path,
+        offline,
+        watch,
+    }) = command
+    {
+        let config = LocalHostConfig::load(&path)
+            .await
+            .context("failed to load local host configuration")?;
+        // NOTE: Overrides are applied to every configuration the host is reloaded with
+        let mut host = LocalHost::with_overrides(config, LocalHostOverrides { offline })
+            .await
+            .context("failed to initialize local host")?;
+        select! {
+            () = host.run(&path, watch) => {}
+            res = signal::ctrl_c() => res.context("failed to wait for Ctrl-C")?,
+        }
+        drop(host);
+        return Ok(());
+    }
+
     let ctl_nats_url = Url::parse(&format!("nats://{nats_host}:{nats_port}"))
         .context("failed to construct a valid `ctl_nats_url` using `nats-host` and `nats-port`")?;
     let (host, shutdown) = wasmcloud_host::wasmbus::Host::new(WasmbusHostConfig {
@@ -265,6 +354,16 @@ async fn main() -> anyhow::Result<()> {
             oci_user,
             oci_password,
         },
+        publish_actor_logs,
+        profile_dir,
+        profile_interval,
+        profile_all_actors,
+        native_profiler,
+        event_history: event_history.then_some(EventHistory {
+            max_age: event_history_max_age,
+            max_messages: event_history_max_messages,
+            max_bytes: event_history_max_bytes,
+        }),
     })
     .await
     .context("failed to initialize host")?;
@@ -281,3 +380,12 @@ fn parse_duration(arg: &str) -> anyhow::Result<Duration> {
         .map(Duration::from_millis)
         .map_err(|e| anyhow::anyhow!(e))
 }
+
+fn parse_native_profiler(arg: &str) -> anyhow::Result<NativeProfiler> {
+    match arg {
+        "perfmap" => Ok(NativeProfiler::PerfMap),
+        "jitdump" => Ok(NativeProfiler::JitDump),
+        "vtune" => Ok(NativeProfiler::VTune),
+        _ => anyhow::bail!("unknown native profiler `{arg}`"),
+    }
+}
@@ -232,23 +232,138 @@ async fn install_rust_wasm32_wasi_actors(out_dir: impl AsRef<Path>) -> anyhow::R
                 [
                     "--manifest-path=./rust/Cargo.toml",
                     "--target=wasm32-wasi",
-                    "-p=logging-module-command",
+                    "-p=blobstore-component-reactor",
+                    "-p=counter-component-reactor",
+                    "-p=http-component-reactor",
+                    "-p=messaging-component-reactor",
                 ],
                 |name, kind| {
-                    ["logging-module-command"].contains(&name) && kind.contains(&CrateType::Bin)
+                    [
+                        "blobstore-component-reactor",
+                        "counter-component-reactor",
+                        "http-component-reactor",
+                        "messaging-component-reactor",
+                    ]
+                    .contains(&name)
+                        && kind.contains(&CrateType::Cdylib)
                 },
             )
             .await
-            .context("failed to build `logging-module-command` crate")?;
+            .context("failed to build `blobstore-component-reactor`, `counter-component-reactor`, `http-component-reactor` and `messaging-component-reactor` crates")?;
+            match (
+                artifacts.next().deref_artifact(),
+                artifacts.next().deref_artifact(),
+                artifacts.next().deref_artifact(),
+                artifacts.next().deref_artifact(),
+                artifacts.next(),
+            ) {
+                (
+                    Some(("blobstore-component-reactor", [blobstore_component_reactor])),
+                    Some(("counter-component-reactor", [counter_component_reactor])),
+                    Some(("http-component-reactor", [http_component_reactor])),
+                    Some(("messaging-component-reactor", [messaging_component_reactor])),
+                    None,
+                ) => {
+                    try_join!(
+                        copy(
+                            blobstore_component_reactor,
+                            out_dir.join("rust-blobstore-component-reactor.wasm"),
+                        ),
+                        copy(
+                            counter_component_reactor,
+                            out_dir.join("rust-counter-component-reactor.wasm"),
+                        ),
+                        copy(
+                            http_component_reactor,
+                            out_dir.join("rust-http-component-reactor.wasm"),
+                        ),
+                        copy(
+                            messaging_component_reactor,
+                            out_dir.join("rust-messaging-component-reactor.wasm"),
+                        ),
+                    )
+                }
+                _ => bail!(
+                    "invalid `blobstore-component-reactor`, `counter-component-reactor`, `http-component-reactor` and `messaging-component-reactor` build artifacts"
+                ),
+            }
+        },
+        async {
+            let mut artifacts = build_artifacts(
+                [
+                    "--manifest-path=./rust/Cargo.toml",
+                    "--target=wasm32-wasi",
+                    "-p=bus-fanout-component-reactor",
+                ],
+                |name, kind| {
+                    ["bus-fanout-component-reactor"].contains(&name)
+                        && kind.contains(&CrateType::Cdylib)
+                },
+            )
+            .await
+            .context("failed to build `bus-fanout-component-reactor` crate")?;
             match (artifacts.next().deref_artifact(), artifacts.next()) {
-                (Some(("logging-module-command", [logging_module_command])), None) => {
+                (Some(("bus-fanout-component-reactor", [bus_fanout_component_reactor])), None) => {
                     copy(
-                        logging_module_command,
-                        out_dir.join("rust-logging-module-command.wasm"),
+                        bus_fanout_component_reactor,
+                        out_dir.join("rust-bus-fanout-component-reactor.wasm"),
                     )
                     .await
                 }
-                _ => bail!("invalid `logging-module-command` build artifacts"),
+                _ => bail!("invalid `bus-fanout-component-reactor` build artifacts"),
+            }
+        },
+        async {
+            let mut artifacts = build_artifacts(
+                [
+                    "--manifest-path=./rust/Cargo.toml",
+                    "--target=wasm32-wasi",
+                    "-p=config-module-command",
+                    "-p=logging-module-command",
+                    "-p=pipe-module-command",
+                ],
+                |name, kind| {
+                    [
+                        "config-module-command",
+                        "logging-module-command",
+                        "pipe-module-command",
+                    ]
+                    .contains(&name)
+                        && kind.contains(&CrateType::Bin)
+                },
+            )
+            .await
+            .context("failed to build `config-module-command`, `logging-module-command` and `pipe-module-command` crates")?;
+            match (
+                artifacts.next().deref_artifact(),
+                artifacts.next().deref_artifact(),
+                artifacts.next().deref_artifact(),
+                artifacts.next(),
+            ) {
+                (
+                    Some(("config-module-command", [config_module_command])),
+                    Some(("logging-module-command", [logging_module_command])),
+                    Some(("pipe-module-command", [pipe_module_command])),
+                    None,
+                ) => {
+                    try_join!(
+                        copy(
+                            config_module_command,
+                            out_dir.join("rust-config-module-command.wasm"),
+                        ),
+                        copy(
+                            logging_module_command,
+                            out_dir.join("rust-logging-module-command.wasm"),
+                        ),
+                        copy(
+                            pipe_module_command,
+                            out_dir.join("rust-pipe-module-command.wasm"),
+                        ),
+                    )
+                }
+                _ => bail!(
+                    "invalid `config-module-command`, `logging-module-command` and `pipe-module-command` build artifacts"
+                ),
             }
         },
         async {
@@ -302,7 +417,15 @@ async fn main() -> anyhow::Result<()> {
         install_rust_wasm32_unknown_unknown_actors(&out_dir),
         install_rust_wasm32_wasi_actors(&out_dir),
     )?;
-    for name in ["builtins-compat-reactor", "builtins-component-reactor"] {
+    for name in [
+        "blobstore-component-reactor",
+        "builtins-compat-reactor",
+        "builtins-component-reactor",
+        "bus-fanout-component-reactor",
+        "counter-component-reactor",
+        "http-component-reactor",
+        "messaging-component-reactor",
+    ] {
         let path = out_dir.join(format!("rust-{name}.wasm"));
         let module = fs::read(&path)
             .await
@@ -315,7 +438,11 @@ async fn main() -> anyhow::Result<()> {
             .await
             .with_context(|| format!("failed to write `{}`", path.display()))?;
     }
-    for name in ["http-compat-command", "tcp-component-command"] {
+    for name in [
+        "config-module-command",
+        "http-compat-command",
+        "tcp-component-command",
+    ] {
         let path = out_dir.join(format!("rust-{name}.wasm"));
         let module = fs::read(&path)
             .await
@@ -336,6 +463,14 @@ async fn main() -> anyhow::Result<()> {
     );
 
     for (name, caps) in [
+        (
+            "blobstore-component-reactor",
+            Some(vec![caps::BLOB.into(), caps::LOGGING.into()]),
+        ),
+        (
+            "blobstore-component-reactor-preview2",
+            Some(vec![caps::BLOB.into(), caps::LOGGING.into()]),
+        ),
         (
             "builtins-compat-reactor",
             Some(vec![
@@ -376,12 +511,49 @@ async fn main() -> anyhow::Result<()> {
                 caps::NUMBERGEN.into(),
             ]),
         ),
+        ("bus-fanout-component-reactor", None),
+        ("bus-fanout-component-reactor-preview2", None),
+        ("config-module-command", None),
+        ("config-module-command-preview2", None),
+        (
+            "counter-component-reactor",
+            Some(vec![
+                caps::HTTP_SERVER.into(),
+                caps::KEY_VALUE.into(),
+                caps::MESSAGING.into(),
+            ]),
+        ),
+        (
+            "counter-component-reactor-preview2",
+            Some(vec![
+                caps::HTTP_SERVER.into(),
+                caps::KEY_VALUE.into(),
+                caps::MESSAGING.into(),
+            ]),
+        ),
         ("http-compat-command", Some(vec![caps::HTTP_SERVER.into()])),
+        (
+            "http-component-reactor",
+            Some(vec![caps::HTTP_SERVER.into()]),
+        ),
+        (
+            "http-component-reactor-preview2",
+            Some(vec![caps::HTTP_SERVER.into()]),
+        ),
         (
             "http-compat-command-preview2",
             Some(vec![caps::HTTP_SERVER.into()]),
         ),
         ("logging-module-command", Some(vec![caps::LOGGING.into()])),
+        (
+            "messaging-component-reactor",
+            Some(vec![caps::MESSAGING.into()]),
+        ),
+        (
+            "messaging-component-reactor-preview2",
+            Some(vec![caps::MESSAGING.into()]),
+        ),
+        ("pipe-module-command", None),
         ("tcp-component-command", None),
         ("tcp-component-command-preview2", None),
     ] {
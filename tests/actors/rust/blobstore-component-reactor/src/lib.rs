@@ -0,0 +1,90 @@
+wit_bindgen::generate!("actor");
+
+use wasmcloud_actor::wasi::logging::logging;
+use wasmcloud_actor::wasmcloud::blobstore::{consumer, types};
+
+/// Downloads of objects larger than this are cancelled by the actor
+const MAX_OBJECT_SIZE: u64 = 8;
+
+fn chunk(object: &str, bytes: &[u8], offset: u64, is_last: bool) -> types::Chunk {
+    types::Chunk {
+        object_id: object.into(),
+        container_id: "container".into(),
+        bytes: bytes.to_vec(),
+        offset,
+        is_last,
+    }
+}
+
+struct Actor;
+
+impl exports::wasmcloud::bus::guest::Guest for Actor {
+    fn call(operation: String) -> Result<(), String> {
+        assert_eq!(operation, "Blobstore.Test");
+
+        assert!(!consumer::container_exists("container"));
+        consumer::create_container("container")?;
+        assert!(consumer::container_exists("container"));
+
+        // Upload an object in multiple chunks
+        let stream = consumer::put_object(&chunk("object", b"foo", 0, false), "text/plain", "")?;
+        consumer::put_chunk(&stream, &chunk("object", b"bar", 3, false), false)?;
+        consumer::put_chunk(&stream, &chunk("object", b"baz", 6, true), false)?;
+        let types::ObjectInfo {
+            container_id,
+            object_id,
+            content_length,
+            content_type,
+            content_encoding,
+            ..
+        } = consumer::get_object_info("container", "object")?.expect("object missing");
+        assert_eq!(container_id, "container");
+        assert_eq!(object_id, "object");
+        assert_eq!(content_length, 9);
+        assert_eq!(content_type.as_deref(), Some("text/plain"));
+        assert!(content_encoding.is_none());
+
+        // Chunks at unexpected offsets are rejected
+        let stream = consumer::put_object(&chunk("invalid", b"foo", 0, false), "", "")?;
+        assert!(consumer::put_chunk(&stream, &chunk("invalid", b"bar", 4, true), false).is_err());
+        consumer::put_chunk(&stream, &chunk("invalid", b"", 3, false), true)?;
+
+        // Cancelled uploads are removed
+        let stream = consumer::put_object(&chunk("cancelled", b"foo", 0, false), "", "")?;
+        consumer::put_chunk(&stream, &chunk("cancelled", b"bar", 3, false), true)?;
+        assert!(consumer::get_object_info("container", "cancelled")?.is_none());
+
+        // Unknown and finished streams are rejected
+        assert!(consumer::put_chunk(&stream, &chunk("cancelled", b"baz", 6, true), false).is_err());
+        assert!(consumer::put_chunk(&stream, &chunk("cancelled", b"", 6, false), true).is_err());
+        assert!(consumer::put_chunk("4242", &chunk("object", b"", 9, true), false).is_err());
+
+        // Unfinished uploads are removed by the host once the instance is dropped
+        consumer::put_object(&chunk("unfinished", b"foo", 0, false), "", "")?;
+
+        consumer::stream_object("container", "object")
+    }
+}
+
+impl exports::wasmcloud::blobstore::handler::Handler for Actor {
+    fn receive_chunk(
+        exports::wasmcloud::blobstore::handler::Chunk {
+            object_id,
+            container_id,
+            bytes,
+            offset,
+            is_last,
+        }: exports::wasmcloud::blobstore::handler::Chunk,
+    ) -> Result<bool, String> {
+        let bytes = String::from_utf8(bytes).map_err(|e| format!("invalid chunk: {e}"))?;
+        logging::log(
+            logging::Level::Info,
+            "receive-chunk",
+            &format!("{container_id}/{object_id}@{offset}: {bytes} (last: {is_last})"),
+        );
+        let end = offset + u64::try_from(bytes.len()).map_err(|e| e.to_string())?;
+        Ok(end > MAX_OBJECT_SIZE)
+    }
+}
+
+export_actor!(Actor);
@@ -175,6 +175,14 @@ impl exports::wasmcloud::bus::guest::Guest for Actor {
         keyvalue::readwrite::set(bucket, &result_key, result_value)
             .map_err(keyvalue::wasi_cloud_error::trace)
             .expect("failed to set `result`");
+
+        assert_eq!(keyvalue::batch::get_keys(bucket), [result_key]);
+
+        keyvalue::atomic::compare_and_swap(bucket, "counter", 0, 1)
+            .map_err(keyvalue::wasi_cloud_error::trace)
+            .expect_err(
+                "`compare-and-swap` should have returned an error for missing `counter` key",
+            );
         Ok(())
     }
 }
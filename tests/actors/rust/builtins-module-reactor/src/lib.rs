@@ -1,9 +1,13 @@
+use std::io::{Read, Write};
+
 use serde::Deserialize;
 use serde_json::json;
 use wasmcloud_actor::wasi::logging::logging;
 use wasmcloud_actor::wasi::random::random;
+use wasmcloud_actor::wasmcloud::bus::host;
 use wasmcloud_actor::{
-    debug, error, export_actor, info, trace, warn, HostRng, HttpHandler, HttpRequest, HttpResponse,
+    debug, error, export_actor, info, trace, warn, Handler, HostRng, HttpHandler, HttpRequest,
+    HttpResponse, Payload,
 };
 
 #[derive(Default)]
@@ -32,6 +36,14 @@ impl HttpHandler for HttpLogRng {
         warn!("warn");
         error!("error");
 
+        let mut call = host::Call::start("", "wasmcloud:builtin:logging", "Logging.WriteLog")
+            .expect("failed to start `Logging.WriteLog` call");
+        let entry = rmp_serde::to_vec(&json!({ "level": "info", "text": "streamed" }))
+            .expect("failed to encode log entry");
+        call.write_all(&entry)
+            .expect("failed to write `Logging.WriteLog` request");
+        call.finish().expect("failed to call `Logging.WriteLog`");
+
         #[derive(Deserialize)]
         struct Request {
             min: u32,
@@ -56,4 +68,45 @@ impl HttpHandler for HttpLogRng {
     }
 }
 
-export_actor!(HttpLogRng, HttpHandler);
+/// Handler of `Pipe.Stream`, which streams the request into the response incrementing every byte
+/// by one
+trait Pipe {}
+
+fn pipe(request: &mut impl Read, response: &mut impl Write) -> std::io::Result<()> {
+    // NOTE: The buffer is larger than the chunk size used by the host to read the request
+    let mut buf = vec![0; 256 * 1024];
+    loop {
+        let n = request.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        for b in &mut buf[..n] {
+            *b = b.wrapping_add(1);
+        }
+        response.write_all(&buf[..n])?;
+    }
+}
+
+impl Handler<dyn Pipe> for HttpLogRng {
+    type Error = String;
+
+    fn handle(&self, operation: &str, payload: Vec<u8>) -> Option<Result<Vec<u8>, String>> {
+        (operation == "Pipe.Stream").then(|| {
+            let mut res = vec![];
+            pipe(&mut payload.as_slice(), &mut res).map_err(|e| e.to_string())?;
+            Ok(res)
+        })
+    }
+
+    fn handle_stream(
+        &self,
+        operation: &str,
+        request: &mut Payload<'_>,
+        mut response: &mut dyn Write,
+    ) -> Option<Result<(), String>> {
+        (operation == "Pipe.Stream")
+            .then(|| pipe(request, &mut response).map_err(|e| e.to_string()))
+    }
+}
+
+export_actor!(HttpLogRng, HttpHandler, Pipe);
@@ -0,0 +1,121 @@
+wit_bindgen::generate!("actor");
+
+use std::io::{stdin, stdout, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use wasmcloud_actor::wasi::io::streams;
+use wasmcloud_actor::wasi::poll::poll;
+use wasmcloud_actor::wasmcloud::bus::host;
+use wasmcloud_actor::{InputStreamReader, OutputStreamWriter};
+
+#[derive(Deserialize)]
+struct Request {
+    operations: Vec<String>,
+    body: String,
+}
+
+#[derive(Serialize)]
+struct Response {
+    operation: String,
+    result: Result<String, String>,
+}
+
+struct Call {
+    operation: String,
+    result: host::FutureResult,
+    response: streams::InputStream,
+}
+
+struct Actor;
+
+impl exports::wasmcloud::bus::guest::Guest for Actor {
+    fn call(operation: String) -> Result<(), String> {
+        assert_eq!(operation, "FanOut.Call");
+        let Request { operations, body } =
+            serde_json::from_reader(stdin()).map_err(|e| format!("failed to read request: {e}"))?;
+
+        // Start all calls before waiting on any of them
+        let calls = operations
+            .into_iter()
+            .map(|operation| {
+                let (result, response, request) = host::call(&operation)?;
+                OutputStreamWriter::from(request)
+                    .write_all(body.as_bytes())
+                    .map_err(|e| format!("failed to write `{operation}` request: {e}"))?;
+                streams::drop_output_stream(request);
+                Ok(Call {
+                    operation,
+                    result,
+                    response,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        // Wait on all of the calls together, until each of them has completed
+        let mut results: Vec<_> = calls.iter().map(|_| None).collect();
+        while results.iter().any(Option::is_none) {
+            let pending: Vec<_> = calls
+                .iter()
+                .zip(&results)
+                .filter(|(_, result)| result.is_none())
+                .map(|(Call { result, .. }, _)| *result)
+                .collect();
+            let pollables: Vec<_> = pending
+                .iter()
+                .map(|result| host::listen_to_future_result(*result))
+                .collect();
+            let ready = poll::poll_oneoff(&pollables);
+            for pollable in pollables {
+                poll::drop_pollable(pollable);
+            }
+            for (result, ready) in pending.into_iter().zip(ready) {
+                if !ready {
+                    continue;
+                }
+                let (i, _) = calls
+                    .iter()
+                    .enumerate()
+                    .find(|(_, call)| call.result == result)
+                    .expect("unknown future result");
+                results[i] = host::future_result_get(result);
+                assert!(
+                    results[i].is_some(),
+                    "ready future result must be available"
+                );
+            }
+        }
+
+        let responses = calls
+            .into_iter()
+            .zip(results)
+            .map(
+                |(
+                    Call {
+                        operation,
+                        response,
+                        ..
+                    },
+                    result,
+                )| {
+                    let result = result.expect("result missing").and_then(|()| {
+                        let mut buf = String::new();
+                        InputStreamReader::from(response)
+                            .read_to_string(&mut buf)
+                            .map_err(|e| format!("failed to read `{operation}` response: {e}"))?;
+                        Ok(buf)
+                    });
+                    streams::drop_input_stream(response);
+                    Response { operation, result }
+                },
+            )
+            .collect::<Vec<_>>();
+        let mut stdout = stdout();
+        serde_json::to_writer(stdout.lock(), &responses)
+            .map_err(|e| format!("failed to write response: {e}"))?;
+        stdout
+            .flush()
+            .map_err(|e| format!("failed to flush stdout: {e}"))
+    }
+}
+
+export_actor!(Actor);
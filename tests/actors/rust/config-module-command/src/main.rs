@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+use std::env::{args, vars};
+use std::fs;
+use std::io::{stdout, Write};
+
+use anyhow::Context;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Preopen {
+    /// Contents of `input.txt` within the directory, if it could be read
+    read: Option<String>,
+    /// Whether `output.txt` could be written within the directory
+    write: bool,
+}
+
+impl Preopen {
+    fn access(dir: &str) -> Self {
+        Self {
+            read: fs::read_to_string(format!("{dir}/input.txt")).ok(),
+            write: fs::write(format!("{dir}/output.txt"), "output").is_ok(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Config {
+    args: Vec<String>,
+    env: BTreeMap<String, String>,
+    ro: Preopen,
+    rw: Preopen,
+}
+
+/// Writes the argument vector, environment and results of accessing `/ro` and `/rw` preopened
+/// directories to stdout as JSON
+fn main() -> anyhow::Result<()> {
+    let config = Config {
+        args: args().collect(),
+        env: vars().collect(),
+        ro: Preopen::access("/ro"),
+        rw: Preopen::access("/rw"),
+    };
+    let mut stdout = stdout().lock();
+    serde_json::to_writer(&mut stdout, &config).context("failed to write config")?;
+    stdout.flush().context("failed to flush")?;
+    Ok(())
+}
@@ -0,0 +1,54 @@
+wit_bindgen::generate!("actor");
+
+use std::io::Write;
+
+use wasmcloud_actor::wasi::http::types;
+use wasmcloud_actor::wasi::keyvalue;
+use wasmcloud_actor::wasmcloud::messaging;
+use wasmcloud_actor::OutputStreamWriter;
+
+struct Actor;
+
+impl exports::wasi::http::incoming_handler::IncomingHandler for Actor {
+    fn handle(request: types::IncomingRequest, response_out: types::ResponseOutparam) {
+        // Request the counter named by the request path to be incremented and respond with its value
+        let path = types::incoming_request_path_with_query(request).unwrap_or_default();
+        types::drop_incoming_request(request);
+        let subject = format!("counter.{}", path.trim_start_matches('/'));
+        let (status, body) = match messaging::consumer::request(&subject, None, 1000) {
+            Ok(messaging::types::BrokerMessage { body, .. }) => (200, body.unwrap_or_default()),
+            Err(e) => (500, e.into_bytes()),
+        };
+
+        let headers = types::new_fields(&[("content-type", b"text/plain".as_slice())]);
+        let response =
+            types::new_outgoing_response(status, headers).expect("failed to construct response");
+        let body_stream =
+            types::outgoing_response_write(response).expect("failed to get response body");
+        types::set_response_outparam(response_out, Ok(response)).expect("failed to set response");
+        OutputStreamWriter::from(body_stream)
+            .write_all(&body)
+            .expect("failed to write response body");
+        types::finish_outgoing_stream(body_stream);
+    }
+}
+
+impl exports::wasmcloud::messaging::handler::Handler for Actor {
+    fn handle_message(
+        exports::wasmcloud::messaging::handler::BrokerMessage {
+            subject, reply_to, ..
+        }: exports::wasmcloud::messaging::handler::BrokerMessage,
+    ) -> Result<(), String> {
+        let reply_to = reply_to.ok_or_else(|| format!("no reply subject set on `{subject}`"))?;
+        let bucket = keyvalue::types::open_bucket("").map_err(keyvalue::wasi_cloud_error::trace)?;
+        let count = keyvalue::atomic::increment(bucket, &subject, 1)
+            .map_err(keyvalue::wasi_cloud_error::trace)?;
+        messaging::consumer::publish(&messaging::types::BrokerMessage {
+            subject: reply_to,
+            body: Some(count.to_string().into_bytes()),
+            reply_to: None,
+        })
+    }
+}
+
+export_actor!(Actor);
@@ -0,0 +1,50 @@
+wit_bindgen::generate!("actor");
+
+use std::io::{Read, Write};
+
+use wasmcloud_actor::wasi::http::types;
+use wasmcloud_actor::{InputStreamReader, OutputStreamWriter};
+
+struct Actor;
+
+impl exports::wasi::http::incoming_handler::IncomingHandler for Actor {
+    fn handle(request: types::IncomingRequest, response_out: types::ResponseOutparam) {
+        let method = match types::incoming_request_method(request) {
+            types::Method::Get => "GET".into(),
+            types::Method::Post => "POST".into(),
+            types::Method::Put => "PUT".into(),
+            types::Method::Other(method) => method,
+            _ => "OTHER".into(),
+        };
+        let path = types::incoming_request_path_with_query(request).unwrap_or_default();
+        let headers = types::incoming_request_headers(request);
+        let values = types::fields_get(headers, "x-test");
+        types::drop_fields(headers);
+
+        let body = types::incoming_request_consume(request).expect("failed to consume body");
+        let mut buf = vec![];
+        InputStreamReader::from(body)
+            .read_to_end(&mut buf)
+            .expect("failed to read body");
+        if let Some(trailers) = types::finish_incoming_stream(body) {
+            types::drop_future_trailers(trailers);
+        }
+        types::drop_incoming_request(request);
+
+        // Echo `x-test` request headers and the request body back to the caller
+        let mut entries = vec![("content-type", b"text/plain".as_slice())];
+        entries.extend(values.iter().map(|value| ("x-test", value.as_slice())));
+        let headers = types::new_fields(&entries);
+        let response =
+            types::new_outgoing_response(200, headers).expect("failed to construct response");
+        let body = types::outgoing_response_write(response).expect("failed to get response body");
+        types::set_response_outparam(response_out, Ok(response)).expect("failed to set response");
+
+        let mut w = OutputStreamWriter::from(body);
+        writeln!(w, "{method} {path}").expect("failed to write response body");
+        w.write_all(&buf).expect("failed to write response body");
+        types::finish_outgoing_stream(body);
+    }
+}
+
+export_actor!(Actor);
@@ -0,0 +1,26 @@
+wit_bindgen::generate!("actor");
+
+use wasmcloud_actor::wasmcloud::messaging::{consumer, types};
+
+struct Actor;
+
+impl exports::wasmcloud::messaging::handler::Handler for Actor {
+    fn handle_message(
+        exports::wasmcloud::messaging::handler::BrokerMessage {
+            subject,
+            body,
+            reply_to,
+        }: exports::wasmcloud::messaging::handler::BrokerMessage,
+    ) -> Result<(), String> {
+        let reply_to = reply_to.ok_or_else(|| format!("no reply subject set on `{subject}`"))?;
+        let mut reply = format!("{subject}: ").into_bytes();
+        reply.extend(body.unwrap_or_default());
+        consumer::publish(&types::BrokerMessage {
+            subject: reply_to,
+            body: Some(reply),
+            reply_to: None,
+        })
+    }
+}
+
+export_actor!(Actor);
@@ -0,0 +1,23 @@
+use std::io::{stdin, stdout, Read, Write};
+
+use anyhow::Context;
+
+/// Streams stdin to stdout, incrementing every byte by one, without buffering the whole input
+fn main() -> anyhow::Result<()> {
+    let mut stdin = stdin().lock();
+    let mut stdout = stdout().lock();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = stdin.read(&mut buf).context("failed to read from stdin")?;
+        if n == 0 {
+            return Ok(());
+        }
+        for b in &mut buf[..n] {
+            *b = b.wrapping_add(1);
+        }
+        stdout
+            .write_all(&buf[..n])
+            .context("failed to write to stdout")?;
+        stdout.flush().context("failed to flush stdout")?;
+    }
+}
@@ -1,5 +1,21 @@
 pub const ISSUER: &str = env!("ISSUER");
 
+pub const RUST_BLOBSTORE_COMPONENT_REACTOR: &str =
+    concat!(env!("OUT_DIR"), "/rust-blobstore-component-reactor.wasm");
+pub const RUST_BLOBSTORE_COMPONENT_REACTOR_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-blobstore-component-reactor.signed.wasm"
+);
+
+pub const RUST_BLOBSTORE_COMPONENT_REACTOR_PREVIEW2: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-blobstore-component-reactor-preview2.wasm"
+);
+pub const RUST_BLOBSTORE_COMPONENT_REACTOR_PREVIEW2_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-blobstore-component-reactor-preview2.signed.wasm"
+);
+
 pub const RUST_BUILTINS_COMPAT_REACTOR: &str =
     concat!(env!("OUT_DIR"), "/rust-builtins-compat-reactor.wasm");
 pub const RUST_BUILTINS_COMPAT_REACTOR_SIGNED: &str =
@@ -35,6 +51,50 @@ pub const RUST_BUILTINS_MODULE_REACTOR: &str =
 pub const RUST_BUILTINS_MODULE_REACTOR_SIGNED: &str =
     concat!(env!("OUT_DIR"), "/rust-builtins-module-reactor.signed.wasm");
 
+pub const RUST_BUS_FANOUT_COMPONENT_REACTOR: &str =
+    concat!(env!("OUT_DIR"), "/rust-bus-fanout-component-reactor.wasm");
+pub const RUST_BUS_FANOUT_COMPONENT_REACTOR_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-bus-fanout-component-reactor.signed.wasm"
+);
+
+pub const RUST_BUS_FANOUT_COMPONENT_REACTOR_PREVIEW2: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-bus-fanout-component-reactor-preview2.wasm"
+);
+pub const RUST_BUS_FANOUT_COMPONENT_REACTOR_PREVIEW2_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-bus-fanout-component-reactor-preview2.signed.wasm"
+);
+
+pub const RUST_CONFIG_MODULE_COMMAND: &str =
+    concat!(env!("OUT_DIR"), "/rust-config-module-command.wasm");
+pub const RUST_CONFIG_MODULE_COMMAND_SIGNED: &str =
+    concat!(env!("OUT_DIR"), "/rust-config-module-command.signed.wasm");
+
+pub const RUST_CONFIG_MODULE_COMMAND_PREVIEW2: &str =
+    concat!(env!("OUT_DIR"), "/rust-config-module-command-preview2.wasm");
+pub const RUST_CONFIG_MODULE_COMMAND_PREVIEW2_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-config-module-command-preview2.signed.wasm"
+);
+
+pub const RUST_COUNTER_COMPONENT_REACTOR: &str =
+    concat!(env!("OUT_DIR"), "/rust-counter-component-reactor.wasm");
+pub const RUST_COUNTER_COMPONENT_REACTOR_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-counter-component-reactor.signed.wasm"
+);
+
+pub const RUST_COUNTER_COMPONENT_REACTOR_PREVIEW2: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-counter-component-reactor-preview2.wasm"
+);
+pub const RUST_COUNTER_COMPONENT_REACTOR_PREVIEW2_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-counter-component-reactor-preview2.signed.wasm"
+);
+
 pub const RUST_HTTP_COMPAT_COMMAND: &str =
     concat!(env!("OUT_DIR"), "/rust-http-compat-command.wasm");
 pub const RUST_HTTP_COMPAT_COMMAND_SIGNED: &str =
@@ -47,11 +107,46 @@ pub const RUST_HTTP_COMPAT_COMMAND_PREVIEW2_SIGNED: &str = concat!(
     "/rust-http-compat-command-preview2.signed.wasm"
 );
 
+pub const RUST_HTTP_COMPONENT_REACTOR: &str =
+    concat!(env!("OUT_DIR"), "/rust-http-component-reactor.wasm");
+pub const RUST_HTTP_COMPONENT_REACTOR_SIGNED: &str =
+    concat!(env!("OUT_DIR"), "/rust-http-component-reactor.signed.wasm");
+
+pub const RUST_HTTP_COMPONENT_REACTOR_PREVIEW2: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-http-component-reactor-preview2.wasm"
+);
+pub const RUST_HTTP_COMPONENT_REACTOR_PREVIEW2_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-http-component-reactor-preview2.signed.wasm"
+);
+
 pub const RUST_LOGGING_MODULE_COMMAND: &str =
     concat!(env!("OUT_DIR"), "/rust-logging-module-command.wasm");
 pub const RUST_LOGGING_MODULE_COMMAND_SIGNED: &str =
     concat!(env!("OUT_DIR"), "/rust-logging-module-command.signed.wasm");
 
+pub const RUST_MESSAGING_COMPONENT_REACTOR: &str =
+    concat!(env!("OUT_DIR"), "/rust-messaging-component-reactor.wasm");
+pub const RUST_MESSAGING_COMPONENT_REACTOR_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-messaging-component-reactor.signed.wasm"
+);
+
+pub const RUST_MESSAGING_COMPONENT_REACTOR_PREVIEW2: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-messaging-component-reactor-preview2.wasm"
+);
+pub const RUST_MESSAGING_COMPONENT_REACTOR_PREVIEW2_SIGNED: &str = concat!(
+    env!("OUT_DIR"),
+    "/rust-messaging-component-reactor-preview2.signed.wasm"
+);
+
+pub const RUST_PIPE_MODULE_COMMAND: &str =
+    concat!(env!("OUT_DIR"), "/rust-pipe-module-command.wasm");
+pub const RUST_PIPE_MODULE_COMMAND_SIGNED: &str =
+    concat!(env!("OUT_DIR"), "/rust-pipe-module-command.signed.wasm");
+
 pub const RUST_TCP_COMPONENT_COMMAND: &str =
     concat!(env!("OUT_DIR"), "/rust-tcp-component-command.wasm");
 pub const RUST_TCP_COMPONENT_COMMAND_SIGNED: &str =
@@ -1,16 +1,63 @@
 use std::collections::HashMap;
 use std::net::{Ipv6Addr, TcpListener};
 use std::path::Path;
+use std::time::Duration;
 
 use anyhow::Context;
+use futures::try_join;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
+use tokio::{fs, spawn};
 use tracing_subscriber::prelude::*;
-use wasmcloud_host::local::{ActorConfig, Host, HostConfig, LinkConfig, TcpSocketConfig};
+use wasmcloud_host::fetch::Fetcher;
+use wasmcloud_host::local::{
+    ActorConfig, AdminConfig, CacheConfig, Host, HostConfig, HttpRouteConfig, LinkConfig,
+    Overrides, TcpSocketConfig, TlsConfig,
+};
 use wasmcloud_host::url::Url;
 
 fn wasm_url(path: impl AsRef<Path>) -> Url {
     Url::from_file_path(path).expect("failed to parse Wasm path")
 }
 
+fn free_port() -> anyhow::Result<u16> {
+    let port = TcpListener::bind((Ipv6Addr::UNSPECIFIED, 0))
+        .context("failed to start TCP listener")?
+        .local_addr()
+        .context("failed to query listener local address")?
+        .port();
+    Ok(port)
+}
+
+/// Pipes `input` through TCP socket at `port`, retrying until a listener is started
+async fn pipe_tcp(port: u16, input: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let stream = timeout(Duration::from_secs(10), async {
+        loop {
+            if let Ok(stream) = TcpStream::connect(("localhost", port)).await {
+                return stream;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .with_context(|| format!("timed out connecting to port `{port}`"))?;
+    let (mut rx, mut tx) = stream.into_split();
+    let mut output = vec![];
+    try_join!(
+        async {
+            tx.write_all(input).await.context("failed to write input")?;
+            tx.shutdown().await.context("failed to shutdown stream")
+        },
+        async {
+            rx.read_to_end(&mut output)
+                .await
+                .context("failed to read output")
+        },
+    )?;
+    Ok(output)
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "skip local host test by default"]
 async fn local() -> anyhow::Result<()> {
@@ -23,30 +70,37 @@ async fn local() -> anyhow::Result<()> {
         )
         .init();
 
-    let port = TcpListener::bind((Ipv6Addr::UNSPECIFIED, 0))
-        .context("failed to start TCP listener")?
-        .local_addr()
-        .context("failed to query listener local address")?
-        .port();
-
-    let _host = Host::new(HostConfig {
+    let port = free_port()?;
+    let config = HostConfig {
         actors: HashMap::from([
             (
                 "logging".into(),
                 ActorConfig {
                     url: wasm_url(test_actors::RUST_LOGGING_MODULE_COMMAND),
+                    argv0: None,
+                    env: HashMap::default(),
+                    preopens: vec![],
+                    sha256: None,
                 },
             ),
             (
                 "http".into(),
                 ActorConfig {
                     url: wasm_url(test_actors::RUST_HTTP_COMPAT_COMMAND_PREVIEW2),
+                    argv0: None,
+                    env: HashMap::default(),
+                    preopens: vec![],
+                    sha256: None,
                 },
             ),
             (
                 "tcp".into(),
                 ActorConfig {
                     url: wasm_url(test_actors::RUST_TCP_COMPONENT_COMMAND_PREVIEW2),
+                    argv0: None,
+                    env: HashMap::default(),
+                    preopens: vec![],
+                    sha256: None,
                 },
             ),
         ]),
@@ -68,9 +122,11 @@ async fn local() -> anyhow::Result<()> {
                 chain: vec!["tcp".into()],
             },
         ],
-    })
-    .await
-    .context("failed to initialize cloud")?;
+        ..Default::default()
+    };
+    let mut host = Host::new(config.clone())
+        .await
+        .context("failed to initialize cloud")?;
 
     eprintln!("sending a GET request on port `{port}`");
 
@@ -94,5 +150,509 @@ async fn local() -> anyhow::Result<()> {
         res,
         "[tcp-component-command] received an HTTP POST request with body: `42`"
     );
+
+    let new_port = free_port()?;
+    let links = config
+        .links
+        .iter()
+        .cloned()
+        .map(|link| match link {
+            LinkConfig::Tcp { chain, .. } => LinkConfig::Tcp {
+                socket: TcpSocketConfig {
+                    addr: format!("[::]:{new_port}"),
+                },
+                chain,
+            },
+            link => link,
+        })
+        .collect();
+    host.reload(HostConfig { links, ..config })
+        .await
+        .context("failed to reload host")?;
+
+    eprintln!("sending a GET request on reloaded port `{new_port}`");
+
+    let res = reqwest::get(format!("http://localhost:{new_port}"))
+        .await?
+        .text()
+        .await?;
+    assert_eq!(
+        res,
+        "[tcp-component-command] received an HTTP GET request with body: ``"
+    );
+    reqwest::get(format!("http://localhost:{port}"))
+        .await
+        .expect_err("listener on the previous port must be stopped");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tcp_chains() -> anyhow::Result<()> {
+    // NOTE: This is much larger than socket buffers, so it can only pass through a chain if all
+    // actors in it run concurrently
+    const PAYLOAD_SIZE: usize = 8 * 1024 * 1024;
+
+    let ports = (1..=5)
+        .map(|n| free_port().map(|port| (n, port)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let _host = Host::new(HostConfig {
+        actors: HashMap::from([(
+            "pipe".into(),
+            ActorConfig {
+                url: wasm_url(test_actors::RUST_PIPE_MODULE_COMMAND),
+                argv0: None,
+                env: HashMap::default(),
+                preopens: vec![],
+                sha256: None,
+            },
+        )]),
+        links: ports
+            .iter()
+            .map(|(n, port)| LinkConfig::Tcp {
+                socket: TcpSocketConfig {
+                    addr: format!("[::]:{port}"),
+                },
+                chain: vec!["pipe".into(); *n],
+            })
+            .collect(),
+        ..Default::default()
+    })
+    .await
+    .context("failed to initialize host")?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let payload: Vec<u8> = (0..PAYLOAD_SIZE).map(|i| (i % 251) as u8).collect();
+    for (n, port) in ports {
+        let stream = TcpStream::connect(("localhost", port))
+            .await
+            .with_context(|| format!("failed to connect to port `{port}`"))?;
+        let (mut rx, mut tx) = stream.into_split();
+        let mut output = Vec::with_capacity(PAYLOAD_SIZE);
+        try_join!(
+            async {
+                tx.write_all(&payload)
+                    .await
+                    .context("failed to write payload")?;
+                tx.shutdown().await.context("failed to shutdown stream")
+            },
+            async {
+                rx.read_to_end(&mut output)
+                    .await
+                    .context("failed to read output")
+            },
+        )
+        .with_context(|| format!("failed to pipe payload through chain of length {n}"))?;
+        assert_eq!(output.len(), PAYLOAD_SIZE, "chain of length {n}");
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = n as u8;
+        assert!(
+            payload
+                .iter()
+                .zip(&output)
+                .all(|(input, output)| input.wrapping_add(offset) == *output),
+            "chain of length {n} produced invalid output"
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn admin() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+    let path = dir.path().join("wasmcloud.json");
+    let admin_port = free_port()?;
+    let tcp_port = free_port()?;
+    let mut config = HostConfig {
+        actors: HashMap::from([(
+            "pipe".into(),
+            ActorConfig {
+                url: wasm_url(test_actors::RUST_PIPE_MODULE_COMMAND),
+                argv0: None,
+                env: HashMap::default(),
+                preopens: vec![],
+                sha256: None,
+            },
+        )]),
+        admin: Some(AdminConfig {
+            addr: format!("127.0.0.1:{admin_port}"),
+            token: "secret".into(),
+        }),
+        ..Default::default()
+    };
+    fs::write(&path, serde_json::to_vec(&config)?).await?;
+    let mut host = Host::new(config.clone())
+        .await
+        .context("failed to initialize host")?;
+    let run = {
+        let path = path.clone();
+        spawn(async move { host.run(path, false).await })
+    };
+
+    let url = format!("http://127.0.0.1:{admin_port}");
+    let client = reqwest::Client::new();
+    let res = client.get(format!("{url}/actors")).send().await?;
+    assert_eq!(res.status(), 401);
+    let res = client
+        .get(format!("{url}/actors"))
+        .bearer_auth("wrong")
+        .send()
+        .await?;
+    assert_eq!(res.status(), 401);
+
+    let res = client
+        .get(format!("{url}/actors"))
+        .bearer_auth("secret")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let actors: serde_json::Value = serde_json::from_str(&res)?;
+    assert_eq!(actors[0]["name"], "pipe");
+    assert!(actors[0]["instances"]["pooled"].is_u64());
+
+    let res = client
+        .post(format!(
+            "{url}/actors/pipe/invoke/wasmcloud:actor/stdio-handler.handle"
+        ))
+        .bearer_auth("secret")
+        .body("abc")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    assert_eq!(res, "bcd");
+    let res = client
+        .post(format!("{url}/actors/missing/invoke/foo"))
+        .bearer_auth("secret")
+        .send()
+        .await?;
+    assert_eq!(res.status(), 404);
+
+    config.links = vec![LinkConfig::Tcp {
+        socket: TcpSocketConfig {
+            addr: format!("[::]:{tcp_port}"),
+        },
+        chain: vec!["pipe".into()],
+    }];
+    fs::write(&path, serde_json::to_vec(&config)?).await?;
+    client
+        .post(format!("{url}/reload"))
+        .bearer_auth("secret")
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let res = client
+        .get(format!("{url}/links"))
+        .bearer_auth("secret")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let links: serde_json::Value = serde_json::from_str(&res)?;
+    assert_eq!(links[0]["kind"], "tcp");
+    let res = client
+        .get(format!("{url}/listeners"))
+        .bearer_auth("secret")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let listeners: serde_json::Value = serde_json::from_str(&res)?;
+    assert_eq!(listeners[0]["addr"], format!("[::]:{tcp_port}"));
+    assert_eq!(listeners[0]["kind"], "tcp");
+
+    run.abort();
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn watch() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+    let path = dir.path().join("wasmcloud.json");
+    let cache_dir = dir.path().join("cache");
+    fs::create_dir(&cache_dir).await?;
+
+    // NOTE: Nothing listens on this URL, so the actor can only be loaded from cache
+    let remote_url: Url = "https://127.0.0.1:1/pipe.wasm".parse()?;
+    let fetcher = Fetcher {
+        cache_dir: cache_dir.clone(),
+        ..Default::default()
+    };
+    fs::copy(
+        test_actors::RUST_PIPE_MODULE_COMMAND,
+        fetcher.cache_path(&remote_url),
+    )
+    .await
+    .context("failed to populate actor cache")?;
+
+    let old_port = free_port()?;
+    let new_port = free_port()?;
+    let mut config = HostConfig {
+        actors: HashMap::from([(
+            "pipe".into(),
+            ActorConfig {
+                url: wasm_url(test_actors::RUST_PIPE_MODULE_COMMAND),
+                argv0: None,
+                env: HashMap::default(),
+                preopens: vec![],
+                sha256: None,
+            },
+        )]),
+        links: vec![LinkConfig::Tcp {
+            socket: TcpSocketConfig {
+                addr: format!("[::]:{old_port}"),
+            },
+            chain: vec!["pipe".into()],
+        }],
+        cache: CacheConfig {
+            dir: Some(cache_dir),
+            offline: false,
+        },
+        ..Default::default()
+    };
+    fs::write(&path, serde_json::to_vec(&config)?).await?;
+    let mut host = Host::with_overrides(config.clone(), Overrides { offline: true })
+        .await
+        .context("failed to initialize host")?;
+    let run = {
+        let path = path.clone();
+        spawn(async move { host.run(path, true).await })
+    };
+    assert_eq!(pipe_tcp(old_port, b"abc").await?, b"bcd");
+
+    config.actors.insert(
+        "remote".into(),
+        ActorConfig {
+            url: remote_url,
+            argv0: None,
+            env: HashMap::default(),
+            preopens: vec![],
+            sha256: None,
+        },
+    );
+    config.links = vec![LinkConfig::Tcp {
+        socket: TcpSocketConfig {
+            addr: format!("[::]:{new_port}"),
+        },
+        chain: vec!["pipe".into(), "remote".into()],
+    }];
+    // NOTE: Ensure the modification time changes on file systems with coarse timestamps
+    sleep(Duration::from_secs(1)).await;
+    fs::write(&path, serde_json::to_vec(&config)?).await?;
+
+    // NOTE: This only succeeds if the offline override is preserved across the reload
+    assert_eq!(pipe_tcp(new_port, b"abc").await?, b"cde");
+
+    run.abort();
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn http() -> anyhow::Result<()> {
+    let http_port = free_port()?;
+    let https_port = free_port()?;
+    let http_link = |prefix: &str| LinkConfig::Http {
+        socket: TcpSocketConfig {
+            addr: format!("[::]:{http_port}"),
+        },
+        routes: vec![HttpRouteConfig {
+            prefix: prefix.into(),
+            target: "http".into(),
+        }],
+        tls: None,
+        timeout_ms: Some(1000),
+    };
+    let mut config = HostConfig {
+        actors: HashMap::from([(
+            "http".into(),
+            ActorConfig {
+                url: wasm_url(test_actors::RUST_HTTP_COMPONENT_REACTOR_PREVIEW2),
+                argv0: None,
+                env: HashMap::default(),
+                preopens: vec![],
+                sha256: None,
+            },
+        )]),
+        links: vec![
+            http_link("/"),
+            LinkConfig::Http {
+                socket: TcpSocketConfig {
+                    addr: format!("[::]:{https_port}"),
+                },
+                routes: vec![HttpRouteConfig {
+                    prefix: "/".into(),
+                    target: "http".into(),
+                }],
+                tls: Some(TlsConfig {
+                    cert: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/cert.pem")
+                        .into(),
+                    key: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/key.pem").into(),
+                    handshake_timeout_ms: Some(500),
+                }),
+                timeout_ms: None,
+            },
+        ],
+        ..Default::default()
+    };
+    let mut host = Host::new(config.clone())
+        .await
+        .context("failed to initialize host")?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let payload: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let client = reqwest::Client::new();
+    let res = client
+        .post(format!("http://localhost:{http_port}/echo"))
+        .header("x-test", "foo")
+        .body(payload.clone())
+        .send()
+        .await?
+        .error_for_status()?;
+    assert_eq!(res.headers()["x-test"], "foo");
+    let body = res.bytes().await?;
+    let body = body
+        .strip_prefix(b"POST /echo\n".as_slice())
+        .context("unexpected response prefix")?;
+    assert!(body == payload, "response body does not match request body");
+
+    let res = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()?
+        .get(format!("https://localhost:{https_port}/"))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    assert_eq!(res, "GET /\n");
+    reqwest::get(format!("http://localhost:{https_port}/"))
+        .await
+        .expect_err("plaintext request to HTTPS link must fail");
+
+    // A client never starting the TLS handshake is disconnected
+    let mut stream = TcpStream::connect(("localhost", https_port)).await?;
+    let mut buf = vec![];
+    timeout(Duration::from_secs(5), stream.read_to_end(&mut buf))
+        .await
+        .context("TLS handshake did not time out")??;
+    assert!(buf.is_empty());
+
+    // An actor waiting for the rest of the request body does not respond in time
+    let mut stream = TcpStream::connect(("localhost", http_port)).await?;
+    stream
+        .write_all(b"POST / HTTP/1.1\r\nhost: localhost\r\ncontent-length: 10\r\n\r\nabc")
+        .await?;
+    let mut buf = [0; 12];
+    timeout(Duration::from_secs(5), stream.read_exact(&mut buf))
+        .await
+        .context("request did not time out")??;
+    assert_eq!(&buf, b"HTTP/1.1 504");
+
+    // Requests on keep-alive connections use the reloaded configuration
+    let res = client
+        .get(format!("http://localhost:{http_port}/"))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 200);
+    res.bytes().await?;
+    config.links[0] = http_link("/api");
+    host.reload(config).await.context("failed to reload host")?;
+    let res = client
+        .get(format!("http://localhost:{http_port}/"))
+        .send()
+        .await?;
+    assert_eq!(res.status(), 404);
+    let res = client
+        .get(format!("http://localhost:{http_port}/api/users"))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    assert_eq!(res, "GET /api/users\n");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keyvalue_messaging() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+    let snapshot = dir.path().join("kv.json");
+    let config = |port: u16| HostConfig {
+        actors: HashMap::from([(
+            "counter".into(),
+            ActorConfig {
+                url: wasm_url(test_actors::RUST_COUNTER_COMPONENT_REACTOR_PREVIEW2),
+                argv0: None,
+                env: HashMap::default(),
+                preopens: vec![],
+                sha256: None,
+            },
+        )]),
+        links: vec![
+            LinkConfig::Http {
+                socket: TcpSocketConfig {
+                    addr: format!("[::]:{port}"),
+                },
+                routes: vec![HttpRouteConfig {
+                    prefix: "/".into(),
+                    target: "counter".into(),
+                }],
+                tls: None,
+                timeout_ms: None,
+            },
+            LinkConfig::KeyValue {
+                source: "counter".into(),
+                store: "default".into(),
+                snapshot: Some(snapshot.clone()),
+            },
+            LinkConfig::Messaging {
+                source: "counter".into(),
+                subscriptions: vec!["counter.*".into()],
+            },
+        ],
+        ..Default::default()
+    };
+    let client = reqwest::Client::new();
+
+    // Each HTTP request is forwarded over messaging to the actor, which increments a counter in
+    // the key-value store
+    let port = free_port()?;
+    let host = Host::new(config(port))
+        .await
+        .context("failed to initialize host")?;
+    for (name, expected) in [("a", "1"), ("a", "2"), ("b", "1")] {
+        let res = client
+            .get(format!("http://localhost:{port}/{name}"))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        assert_eq!(res, expected, "counter `{name}`");
+    }
+    drop(host);
+
+    // Writes are appended to the log instead of rewriting the snapshot
+    assert_eq!(fs::read_to_string(&snapshot).await?, "{}");
+    let log = fs::read_to_string(dir.path().join("kv.json.log")).await?;
+    assert_eq!(log.lines().count(), 3);
+
+    let port = free_port()?;
+    let _host = Host::new(config(port))
+        .await
+        .context("failed to initialize host")?;
+    let res = client
+        .get(format!("http://localhost:{port}/a"))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    assert_eq!(res, "3");
     Ok(())
 }
@@ -16,7 +16,7 @@ use tokio::net::TcpListener;
 use tokio::process::Command;
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
-use tokio::time::interval;
+use tokio::time::{interval, timeout};
 use tokio::{fs, select, spawn, try_join};
 use tokio_stream::wrappers::IntervalStream;
 use tokio_stream::StreamExt;
@@ -31,6 +31,8 @@ use wasmcloud_control_interface::{
     Host as HostInfo, HostInventory, ProviderAuctionAck,
 };
 use wasmcloud_host::oci::Config as OciConfig;
+use wasmcloud_host::wasmbus::config::EventHistory;
+use wasmcloud_host::wasmbus::event::{HistoryResponse, SCHEMA_VERSION};
 use wasmcloud_host::wasmbus::{Host, HostConfig};
 
 async fn free_port() -> anyhow::Result<u16> {
@@ -251,6 +253,15 @@ async fn wasmbus() -> anyhow::Result<()> {
         host_seed: Some(host_key.seed().unwrap()),
         provider_shutdown_delay: Some(Duration::from_millis(300)),
         oci_opts: OciConfig::default(),
+        publish_actor_logs: false,
+        profile_dir: None,
+        profile_interval: None,
+        profile_all_actors: false,
+        native_profiler: None,
+        event_history: Some(EventHistory {
+            max_age: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        }),
     })
     .await
     .context("failed to initialize host")?;
@@ -264,6 +275,12 @@ async fn wasmbus() -> anyhow::Result<()> {
         host_seed: Some(host_key_two.seed().unwrap()),
         provider_shutdown_delay: Some(Duration::from_millis(400)),
         oci_opts: OciConfig::default(),
+        publish_actor_logs: false,
+        profile_dir: None,
+        profile_interval: None,
+        profile_all_actors: false,
+        native_profiler: None,
+        event_history: None,
     })
     .await
     .context("failed to initialize host two")?;
@@ -640,10 +657,22 @@ expected: {expected_labels:?}"#
         _ => bail!("invalid provider count"),
     }
 
-    let (mut nats_publish_sub, mut nats_request_sub, mut nats_request_multi_sub) = try_join!(
+    let kvredis_rpc_subject = format!(
+        "wasmbus.rpc.{TEST_PREFIX}.{}.{kvredis_provider_link_name}",
+        kvredis_provider_key.public_key()
+    );
+    let (
+        mut nats_publish_sub,
+        mut nats_request_sub,
+        mut nats_request_multi_sub,
+        mut kvredis_rpc_sub,
+        mut kvredis_linkdefs_del_sub,
+    ) = try_join!(
         nats_client.subscribe("test-messaging-publish".into()),
         nats_client.subscribe("test-messaging-request".into()),
         nats_client.subscribe("test-messaging-request-multi".into()),
+        nats_client.subscribe(kvredis_rpc_subject.clone()),
+        nats_client.subscribe(format!("{kvredis_rpc_subject}.linkdefs.del")),
     )
     .context("failed to subscribe to NATS topics")?;
 
@@ -651,28 +680,31 @@ expected: {expected_labels:?}"#
         .req_command(&redis::Cmd::set("foo", "bar"))
         .context("failed to set `foo` key in Redis")?;
 
+    // NOTE: Requests are handled twice, once before and once after the keyvalue link is removed
     let nats_requests = spawn(async move {
-        let res = nats_request_sub
-            .next()
-            .await
-            .context("failed to receive NATS response to `request`")?;
-        ensure!(res.payload == "foo");
-        let reply = res.reply.context("no reply set on `request`")?;
-        nats_client
-            .publish(reply, "bar".into())
-            .await
-            .context("failed to publish response to `request`")?;
-
-        let res = nats_request_multi_sub
-            .next()
-            .await
-            .context("failed to receive NATS response to `request_multi`")?;
-        ensure!(res.payload == "foo");
-        let reply = res.reply.context("no reply on set `request_multi`")?;
-        nats_client
-            .publish(reply, "bar".into())
-            .await
-            .context("failed to publish response to `request_multi`")?;
+        for _ in 0..2 {
+            let res = nats_request_sub
+                .next()
+                .await
+                .context("failed to receive NATS response to `request`")?;
+            ensure!(res.payload == "foo");
+            let reply = res.reply.context("no reply set on `request`")?;
+            nats_client
+                .publish(reply, "bar".into())
+                .await
+                .context("failed to publish response to `request`")?;
+
+            let res = nats_request_multi_sub
+                .next()
+                .await
+                .context("failed to receive NATS response to `request_multi`")?;
+            ensure!(res.payload == "foo");
+            let reply = res.reply.context("no reply on set `request_multi`")?;
+            nats_client
+                .publish(reply, "bar".into())
+                .await
+                .context("failed to publish response to `request_multi`")?;
+        }
         Ok(())
     });
 
@@ -724,15 +756,13 @@ expected: {expected_labels:?}"#
     ensure!(nats_res.payload == http_res);
     ensure!(nats_res.reply.as_deref() == Some("noreply"));
 
-    nats_requests
-        .await
-        .context("failed to await NATS request task")?
-        .context("failed to handle NATS requests")?;
-
-    let redis_keys = redis_client
+    let mut redis_keys: Vec<String> = redis_client
         .req_command(&redis::Cmd::keys("*"))
+        .and_then(|keys| redis::from_redis_value(&keys))
         .context("failed to list keys in Redis")?;
-    let expected_redis_keys = redis::Value::Bulk(vec![redis::Value::Data(b"result".to_vec())]);
+    redis_keys.sort_unstable();
+    // NOTE: `wasmcloud:keys` is the set tracking the keys written by the actor
+    let expected_redis_keys = ["result", "wasmcloud:keys"];
     ensure!(
         redis_keys == expected_redis_keys,
         r#"invalid keys in Redis:
@@ -745,6 +775,44 @@ expected: {expected_redis_keys:?}"#
         .context("failed to get `result` key in Redis")?;
     ensure!(redis_res == redis::Value::Data(http_res.into()));
 
+    // Remove the keyvalue link and ensure the actor cannot reach the provider through it anymore
+    assert_remove_link(
+        &ctl_client,
+        &actor_claims,
+        "wasmcloud:keyvalue",
+        kvredis_provider_link_name,
+    )
+    .await
+    .context("failed to remove keyvalue link")?;
+    // NOTE: Both hosts process the link deletion
+    for _ in 0..2 {
+        kvredis_linkdefs_del_sub
+            .next()
+            .await
+            .context("failed to receive keyvalue link deletion")?;
+    }
+    let http_res = http_client
+        .post(format!("http://localhost:{http_port}"))
+        .body(r#"{"min":42,"max":4242}"#)
+        .send()
+        .await
+        .context("failed to connect to server")?;
+    ensure!(
+        !http_res.status().is_success(),
+        "request using a removed link must fail"
+    );
+    ensure!(
+        timeout(Duration::from_secs(1), kvredis_rpc_sub.next())
+            .await
+            .is_err(),
+        "keyvalue provider invoked using a removed link"
+    );
+
+    nats_requests
+        .await
+        .context("failed to await NATS request task")?
+        .context("failed to handle NATS requests")?;
+
     try_join!(
         assert_remove_link(
             &ctl_client,
@@ -752,12 +820,6 @@ expected: {expected_redis_keys:?}"#
             "wasmcloud:messaging",
             httpserver_provider_link_name
         ),
-        assert_remove_link(
-            &ctl_client,
-            &actor_claims,
-            "wasmcloud:keyvalue",
-            kvredis_provider_link_name
-        ),
         assert_remove_link(
             &ctl_client,
             &actor_claims,
@@ -767,6 +829,79 @@ expected: {expected_redis_keys:?}"#
     )
     .context("failed to remove links")?;
 
+    // Replay the lattice event history, which only host one captures
+    let events_client = async_nats::connect(ctl_nats_url.as_str())
+        .await
+        .context("failed to connect to NATS")?;
+    let events_subject = format!("wasmbus.ctl.{TEST_PREFIX}.get.events");
+    let res = events_client
+        .request(events_subject.clone(), "{}".into())
+        .await
+        .context("failed to request event history")?;
+    let HistoryResponse {
+        events,
+        last_sequence,
+    } = serde_json::from_slice(&res.payload).context("failed to decode event history")?;
+    ensure!(events.first().map(|ev| ev.sequence) == Some(1));
+    ensure!(events.iter().any(|ev| ev.sequence == last_sequence));
+    ensure!(events
+        .windows(2)
+        .all(|evs| evs[0].sequence < evs[1].sequence));
+    for ev in &events {
+        ensure!(ev.event["data"]["schema_version"] == SCHEMA_VERSION);
+    }
+    let types: Vec<_> = events
+        .iter()
+        .filter_map(|ev| ev.event["type"].as_str())
+        .collect();
+    for ty in [
+        "com.wasmcloud.lattice.host_started",
+        "com.wasmcloud.lattice.actors_started",
+        "com.wasmcloud.lattice.provider_started",
+        "com.wasmcloud.lattice.linkdef_set",
+        "com.wasmcloud.lattice.linkdef_deleted",
+    ] {
+        ensure!(types.contains(&ty), "`{ty}` event missing from history");
+    }
+
+    let res = events_client
+        .request(
+            events_subject.clone(),
+            format!(r#"{{"since_sequence":{last_sequence},"limit":1}}"#).into(),
+        )
+        .await
+        .context("failed to request event history")?;
+    let HistoryResponse { events, .. } =
+        serde_json::from_slice(&res.payload).context("failed to decode event history")?;
+    match events.as_slice() {
+        [ev] => ensure!(ev.sequence == last_sequence),
+        _ => bail!("expected exactly one event"),
+    }
+
+    let res = events_client
+        .request(events_subject, r#"{"since_sequence":0,"limit":1}"#.into())
+        .await
+        .context("failed to request event history")?;
+    let HistoryResponse { events, .. } =
+        serde_json::from_slice(&res.payload).context("failed to decode event history")?;
+    match events.as_slice() {
+        [ev] => ensure!(ev.sequence == 1),
+        _ => bail!("expected exactly one event"),
+    }
+
+    // A host with retention limits conflicting with the existing event stream fails to start
+    ensure!(Host::new(HostConfig {
+        ctl_nats_url: ctl_nats_url.clone(),
+        lattice_prefix: TEST_PREFIX.to_string(),
+        event_history: Some(EventHistory {
+            max_age: Some(Duration::from_secs(60)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await
+    .is_err());
+
     // Shutdown host one
     let CtlOperationAck { accepted, error } = ctl_client
         .stop_host(&host_key.public_key(), None)